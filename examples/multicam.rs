@@ -0,0 +1,173 @@
+use egui::{CentralPanel, Color32, Slider, TopBottomPanel, Vec2};
+use egui_video::{PlayerGroup, VideoPlayer};
+use std::time::Duration;
+
+struct MultiCamApp {
+    group: PlayerGroup,
+    error_message: Option<String>,
+}
+
+impl MultiCamApp {
+    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+        Self {
+            group: PlayerGroup::new(),
+            error_message: None,
+        }
+    }
+
+    fn open_files(&mut self, ctx: &egui::Context) {
+        let Some(paths) = rfd::FileDialog::new()
+            .add_filter("Video", &["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv"])
+            .pick_files()
+        else {
+            return;
+        };
+
+        self.error_message = None;
+        for path in paths {
+            match VideoPlayer::open(&path, ctx.clone()) {
+                Ok(player) => {
+                    self.group.add(player);
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to open {}: {}", path.display(), e));
+                }
+            }
+        }
+    }
+
+    fn show_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let play_pause_text = if self.group.is_playing() {
+                "⏸"
+            } else {
+                "▶"
+            };
+            if ui.button(play_pause_text).clicked() {
+                if self.group.is_playing() {
+                    self.group.pause();
+                } else {
+                    self.group.play();
+                }
+            }
+            if ui.button("⏹").clicked() {
+                self.group.stop();
+            }
+
+            ui.separator();
+
+            let duration = self.group.duration().as_secs_f64();
+            let mut position = self.group.position().as_secs_f64();
+            let response = ui.add(
+                Slider::new(&mut position, 0.0..=duration)
+                    .show_value(false)
+                    .trailing_fill(true),
+            );
+            if response.drag_stopped() || response.clicked() {
+                self.group.seek(Duration::from_secs_f64(position));
+            }
+
+            ui.separator();
+
+            // Per-camera audio mute
+            for index in 0..self.group.len() {
+                let mut muted = self.group.player(index).is_some_and(VideoPlayer::is_muted);
+                if ui
+                    .checkbox(&mut muted, format!("Mute {}", index + 1))
+                    .changed()
+                {
+                    self.group.set_muted(index, muted);
+                }
+            }
+        });
+    }
+}
+
+impl eframe::App for MultiCamApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Add cameras...").clicked() {
+                        ui.close_menu();
+                        self.open_files(ctx);
+                    }
+                    if ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+        });
+
+        if !self.group.is_empty() {
+            TopBottomPanel::bottom("controls").show(ctx, |ui| {
+                self.show_controls(ui);
+            });
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+            if let Some(ref err) = self.error_message {
+                ui.colored_label(Color32::RED, err);
+            }
+
+            if self.group.is_empty() {
+                ui.centered_and_justified(|ui| {
+                    if ui.button("Add camera recordings...").clicked() {
+                        self.open_files(ctx);
+                    }
+                });
+                return;
+            }
+
+            self.group.update(ctx);
+
+            // Lay the players out in a near-square grid
+            let count = self.group.len();
+            let columns = (count as f32).sqrt().ceil() as usize;
+            let rows = count.div_ceil(columns);
+            let available = ui.available_size();
+            let cell = Vec2::new(available.x / columns as f32, available.y / rows as f32);
+
+            egui::Grid::new("camera_grid")
+                .spacing(Vec2::ZERO)
+                .show(ui, |ui| {
+                    for (index, player) in self.group.players().iter().enumerate() {
+                        if let Some(texture_id) = player.texture_id() {
+                            let (width, height) = player.video_size();
+                            let aspect = width as f32 / height as f32;
+                            let size = if cell.x / cell.y > aspect {
+                                Vec2::new(cell.y * aspect, cell.y)
+                            } else {
+                                Vec2::new(cell.x, cell.x / aspect)
+                            };
+                            ui.allocate_ui(cell, |ui| {
+                                ui.centered_and_justified(|ui| {
+                                    ui.image((texture_id, size));
+                                });
+                            });
+                        }
+                        if (index + 1) % columns == 0 {
+                            ui.end_row();
+                        }
+                    }
+                });
+        });
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    ffmpeg_next::init().expect("Failed to initialize FFmpeg");
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([1280.0, 720.0])
+            .with_min_inner_size([640.0, 480.0]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Multi-camera Player",
+        options,
+        Box::new(|cc| Ok(Box::new(MultiCamApp::new(cc)))),
+    )
+}
@@ -1,35 +1,479 @@
-use egui::{CentralPanel, Color32, ScrollArea, TopBottomPanel, Vec2};
-use egui_video::{DisplayMode, PlayerControls, VideoPlayer};
+use clap::Parser;
+use egui::{CentralPanel, Color32, SidePanel, TextureFilter, TopBottomPanel};
+use egui_video::{
+    is_supported_media, AudioBackend, AudioFormat, CompareMode, CompareView, ControlsLayout,
+    ControlsStyle, DecodedVideoFrame, DisplayMode, DropZone, InterpolationMode, Oscilloscope,
+    PlayerControls, PlayerEvent, Playlist, RepeatMode, ScrubAudio, Settings, SilenceAction,
+    SilenceSkip, Spectrogram, SpectrumBars, StopBehavior, SubtitleList, SubtitleOverlay,
+    SubtitleStyle, TelemetryOverlay, VideoEffect, VideoPlayer, VideoView, ViewTransform,
+    Visualizer, Volume,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Command line, for previewing files from the shell or a file manager
+#[derive(Parser)]
+#[command(about = "Play a video file or stream")]
+struct Args {
+    /// File, playlist or URL to play; `-` reads from stdin
+    path: Option<PathBuf>,
+    /// Start position, as seconds or [HH:]MM:SS[.mmm]
+    #[arg(long, value_parser = parse_time)]
+    start: Option<Duration>,
+    /// End position; with it, only the clip from --start to here is played
+    #[arg(long, value_parser = parse_time)]
+    end: Option<Duration>,
+    /// Volume from 0.0 to 1.0
+    #[arg(long, value_parser = parse_volume)]
+    volume: Option<Volume>,
+    /// Playback speed from 0.25 to 4.0
+    #[arg(long)]
+    speed: Option<f32>,
+    /// Start in fullscreen
+    #[arg(long)]
+    fullscreen: bool,
+    /// Repeat the file, or the whole playlist
+    #[arg(long = "loop")]
+    repeat: bool,
+    /// Audio backend: rodio, or cpal and sdl when built with their features
+    #[arg(long, value_parser = parse_audio_backend)]
+    audio_backend: Option<AudioBackend>,
+    /// Accept JSON-RPC remote control over WebSocket on this address, e.g. 127.0.0.1:9090
+    #[cfg(feature = "remote")]
+    #[arg(long)]
+    remote: Option<String>,
+}
+
+fn parse_time(text: &str) -> Result<Duration, String> {
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        let value: f64 = part
+            .parse()
+            .map_err(|_| format!("invalid time {:?}", text))?;
+        seconds = seconds * 60.0 + value;
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+fn parse_volume(text: &str) -> Result<Volume, String> {
+    text.parse()
+        .ok()
+        .and_then(Volume::new)
+        .ok_or_else(|| "volume must be between 0.0 and 1.0".to_owned())
+}
+
+fn parse_audio_backend(text: &str) -> Result<AudioBackend, String> {
+    match text {
+        "rodio" => Ok(AudioBackend::Rodio),
+        #[cfg(feature = "audio-cpal")]
+        "cpal" => Ok(AudioBackend::Cpal),
+        #[cfg(feature = "audio-sdl")]
+        "sdl" => Ok(AudioBackend::Sdl),
+        _ => Err(format!("unknown or disabled audio backend {:?}", text)),
+    }
+}
+
+/// Playlist change picked from an item's context menu
+enum QueueEdit {
+    Move(usize, usize),
+    PlayNext(usize),
+    Remove(usize),
+}
+
+impl QueueEdit {
+    fn apply(self, playlist: &mut Playlist) {
+        match self {
+            QueueEdit::Move(from, to) => playlist.move_item(from, to),
+            QueueEdit::PlayNext(index) => {
+                if let Some(item) = playlist.remove(index) {
+                    playlist.insert_next(item.path);
+                }
+            }
+            QueueEdit::Remove(index) => {
+                playlist.remove(index);
+            }
+        }
+    }
+}
+
+/// View settings kept in eframe storage between runs
+#[derive(Default, Serialize, Deserialize)]
+struct SavedView {
+    display_mode: Option<DisplayMode>,
+    /// Restore the zoom and pan when a file is opened again
+    remember_view: bool,
+    views: HashMap<PathBuf, ViewTransform>,
+}
+
+/// A watching session saved from the File menu
+#[derive(Serialize, Deserialize)]
+struct Session {
+    playlist: Playlist,
+    /// Position in the current item in seconds
+    position: f64,
+    settings: Settings,
+}
 
 struct VideoPlayerApp {
     player: Option<VideoPlayer>,
     error_message: Option<String>,
+    status_message: Option<String>,
+    show_telemetry: bool,
+    show_subtitle_list: bool,
+    show_frame_metadata: bool,
+    subtitle_query: String,
+    controls_style: ControlsStyle,
+    recording: bool,
+    grayscale: bool,
+    visualizer: Visualization,
+    saved_view: SavedView,
+    /// Reference frame comparison layout, when comparing
+    compare: Option<CompareMode>,
+    compare_split: f32,
+    /// File the current zoom and pan belong to
+    view_path: Option<PathBuf>,
+    /// Fed by the window's key messages, see `media_key_hook`
+    media_keys: egui_video::MediaKeys,
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<egui_video::GamepadSurface>,
+    #[cfg(feature = "remote")]
+    remote: Option<egui_video::RemoteServer>,
+    #[cfg(feature = "log-viewer")]
+    log: Option<egui_video::LogCapture>,
+    #[cfg(feature = "log-viewer")]
+    show_log: bool,
+    #[cfg(feature = "log-viewer")]
+    log_filter: String,
+}
+
+/// What the player shows with video off
+#[derive(Clone, Copy, PartialEq)]
+enum Visualization {
+    Cover,
+    Bars,
+    Scope,
+    Spectrogram,
+}
+
+/// Example `VideoEffect`: drop the color from every frame
+struct Grayscale;
+
+impl VideoEffect for Grayscale {
+    fn process(&mut self, frame: &mut DecodedVideoFrame) {
+        for pixel in &mut frame.pixels {
+            let [r, g, b, a] = pixel.to_array();
+            let luma = (0.2126 * f32::from(r) + 0.7152 * f32::from(g) + 0.0722 * f32::from(b))
+                .round() as u8;
+            *pixel = Color32::from_rgba_premultiplied(luma, luma, luma, a);
+        }
+    }
 }
 
 impl VideoPlayerApp {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        args: Args,
+        media_keys: egui_video::MediaKeys,
+    ) -> Self {
+        let mut app = Self {
             player: None,
             error_message: None,
+            status_message: None,
+            show_telemetry: true,
+            show_subtitle_list: false,
+            show_frame_metadata: false,
+            subtitle_query: String::new(),
+            controls_style: ControlsStyle::default(),
+            recording: false,
+            grayscale: false,
+            visualizer: Visualization::Cover,
+            saved_view: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+                .unwrap_or_default(),
+            view_path: None,
+            compare: None,
+            compare_split: 0.5,
+            media_keys,
+            #[cfg(feature = "gamepad")]
+            gamepad: egui_video::GamepadSurface::new().ok(),
+            #[cfg(feature = "remote")]
+            remote: None,
+            #[cfg(feature = "log-viewer")]
+            log: egui_video::LogCapture::new(2000).install().ok(),
+            #[cfg(feature = "log-viewer")]
+            show_log: false,
+            #[cfg(feature = "log-viewer")]
+            log_filter: String::new(),
+        };
+        #[cfg(feature = "remote")]
+        if let Some(ref address) = args.remote {
+            match egui_video::RemoteServer::start(address, cc.egui_ctx.clone()) {
+                Ok(server) => app.remote = Some(server),
+                Err(e) => app.status_message = Some(e.to_string()),
+            }
+        }
+        if let Some(path) = args.path {
+            app.load_video(path, &cc.egui_ctx);
+        }
+        if let Some(ref mut player) = app.player {
+            if args.repeat {
+                if player.playlist().is_empty() {
+                    let _ = player.set_playlist(Playlist::new(vec![player.path().to_path_buf()]));
+                }
+                player.playlist_mut().set_repeat(RepeatMode::All);
+            }
+            if let Some(backend) = args.audio_backend {
+                if let Err(e) = player.set_audio_backend(backend) {
+                    app.error_message = Some(e.to_string());
+                }
+            }
+            if let Some(volume) = args.volume {
+                player.set_volume(volume);
+            }
+            if let Some(speed) = args.speed {
+                player.set_speed(speed);
+            }
+            if let Some(end) = args.end {
+                if let Err(e) = player.set_virtual_range(args.start.unwrap_or_default(), end) {
+                    app.error_message = Some(e.to_string());
+                }
+            } else if let Some(start) = args.start {
+                player.seek(start);
+            }
+            player.play();
         }
+        app
     }
 
     fn open_file(&mut self, ctx: &egui::Context) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("Video", &["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv"])
+            .add_filter(
+                "Image",
+                &["png", "jpg", "jpeg", "bmp", "tif", "tiff", "exr"],
+            )
+            .add_filter("Playlist", &["m3u", "m3u8", "pls"])
             .pick_file()
         {
             self.load_video(path, ctx);
         }
     }
 
+    /// Open the next (or previous) file next to the current one
+    fn step_file(&mut self, forward: bool) {
+        let Some(ref mut player) = self.player else {
+            return;
+        };
+        let result = if forward {
+            player.next_file()
+        } else {
+            player.previous_file()
+        };
+        if let Err(e) = result {
+            self.status_message = Some(e.to_string());
+        }
+    }
+
+    /// Play a folder of numbered images as a video
+    fn open_image_sequence(&mut self, ctx: &egui::Context) {
+        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+            self.load_video(path, ctx);
+        }
+    }
+
+    fn open_playlist(&mut self, ctx: &egui::Context) {
+        if let Some(paths) = rfd::FileDialog::new()
+            .add_filter("Video", &["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv"])
+            .pick_files()
+        {
+            self.load_playlist(paths, ctx);
+        }
+    }
+
+    fn export_audio(&mut self) {
+        let Some(ref player) = self.player else {
+            return;
+        };
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("MP3", &["mp3"])
+            .add_filter("FLAC", &["flac"])
+            .add_filter("WAV", &["wav"])
+            .set_file_name("audio.mp3")
+            .save_file()
+        {
+            let format = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(AudioFormat::from_extension)
+                .unwrap_or(AudioFormat::Mp3);
+            player.export_audio(&path, format, std::time::Duration::ZERO..player.duration());
+        }
+    }
+
+    /// Save the frames around the current one to a folder, to pick the sharpest
+    fn capture_burst(&mut self) {
+        let Some(ref player) = self.player else {
+            return;
+        };
+        if let Some(directory) = rfd::FileDialog::new().pick_folder() {
+            self.status_message = Some("Capturing frames...".to_owned());
+            player.capture_burst(&directory, 9, 1);
+        }
+    }
+
+    /// Restore the saved zoom and pan when another file starts, and remember changes to it
+    fn sync_view(&mut self) {
+        let Some(ref mut player) = self.player else {
+            return;
+        };
+        let path = player.path().to_path_buf();
+        if self.view_path.as_ref() != Some(&path) {
+            if self.saved_view.remember_view {
+                if let Some(view) = self.saved_view.views.get(&path) {
+                    player.set_view_transform(*view);
+                }
+            }
+            self.view_path = Some(path);
+        } else if self.saved_view.remember_view {
+            let view = player.view_transform();
+            if view.is_identity() {
+                self.saved_view.views.remove(&path);
+            } else {
+                self.saved_view.views.insert(path, view);
+            }
+        }
+    }
+
+    fn save_session(&mut self) {
+        let Some(ref player) = self.player else {
+            return;
+        };
+        let mut playlist = player.playlist().clone();
+        if playlist.is_empty() {
+            playlist = Playlist::new(vec![player.path().to_path_buf()]);
+        }
+        let session = Session {
+            playlist,
+            position: player.position().as_secs_f64(),
+            settings: player.settings(),
+        };
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Session", &["json"])
+            .set_file_name("session.json")
+            .save_file()
+        {
+            let result = serde_json::to_string_pretty(&session)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                self.status_message = Some(format!("Failed to save session: {}", e));
+            }
+        }
+    }
+
+    fn restore_session(&mut self, ctx: &egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Session", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let session: Session = match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        {
+            Ok(session) => session,
+            Err(e) => {
+                self.status_message = Some(format!("Failed to restore session: {}", e));
+                return;
+            }
+        };
+        let Some(current) = session.playlist.current().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        self.load_video(current, ctx);
+        if let Some(ref mut player) = self.player {
+            let result = player
+                .set_playlist(session.playlist)
+                .and_then(|()| player.set_settings(session.settings));
+            player.seek(Duration::from_secs_f64(session.position));
+            if let Err(e) = result {
+                self.status_message = Some(format!("Failed to restore session: {}", e));
+            }
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        let Some(ref player) = self.player else {
+            return;
+        };
+        if self.recording {
+            player.stop_recording();
+        } else if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Matroska", &["mkv"])
+            .add_filter("MPEG-TS", &["ts"])
+            .set_file_name("recording.mkv")
+            .save_file()
+        {
+            player.start_recording(&path);
+        }
+    }
+
+    /// Load a subtitle file as the primary track, or as the secondary one
+    /// shown at the top of the frame
+    fn load_subtitles(&mut self, secondary: bool) {
+        let Some(ref mut player) = self.player else {
+            return;
+        };
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Subtitles", &["srt", "vtt", "ass", "ssa"])
+            .pick_file()
+        {
+            let result = if secondary {
+                player.load_secondary_subtitles(&path)
+            } else {
+                player.load_subtitles(&path)
+            };
+            if let Err(e) = result {
+                self.status_message = Some(format!("Failed to load subtitles: {:#}", e));
+            }
+        }
+    }
+
+    /// Open the first file and queue the rest behind it
+    fn load_playlist(&mut self, paths: Vec<PathBuf>, ctx: &egui::Context) {
+        let Some(first) = paths.first().cloned() else {
+            return;
+        };
+        self.load_video(first, ctx);
+        if let Some(ref mut player) = self.player {
+            if let Err(e) = player.set_playlist(Playlist::new(paths)) {
+                self.status_message = Some(format!("Failed to open playlist: {}", e));
+            }
+        }
+    }
+
     fn load_video(&mut self, path: PathBuf, ctx: &egui::Context) {
         self.error_message = None;
-        match VideoPlayer::open(&path, ctx.clone()) {
-            Ok(player) => {
-                self.player = Some(player);
+        self.recording = false; // Opening another file ends the recording
+
+        // Reuse the running player to avoid an audio pop and a black flash
+        if let Some(ref mut player) = self.player {
+            if let Err(e) = player.replace_media(&path) {
+                self.status_message = Some(format!("Failed to open video: {}", e));
             }
+            return;
+        }
+
+        let mut builder = VideoPlayer::builder(&path);
+        if let Some(mode) = self.saved_view.display_mode {
+            builder = builder.display_mode(mode);
+        }
+        match builder.build(ctx.clone()) {
+            Ok(player) => self.player = Some(player),
             Err(e) => {
                 self.error_message = Some(format!("Failed to open video: {}", e));
             }
@@ -38,7 +482,83 @@ impl VideoPlayerApp {
 }
 
 impl eframe::App for VideoPlayerApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if let Some(ref player) = self.player {
+            self.saved_view.display_mode = Some(player.display_mode());
+        }
+        eframe::set_value(storage, eframe::APP_KEY, &self.saved_view);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.sync_view();
+
+        // Page Up/Down flip through the clips in the current folder
+        let (previous_file, next_file) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::PageUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::PageDown),
+            )
+        });
+        if previous_file || next_file {
+            self.step_file(next_file);
+        }
+
+        // Ctrl+Z / Ctrl+Shift+Z jump back and forth through the seek history
+        let (redo_seek, undo_seek) = ctx.input_mut(|i| {
+            (
+                i.consume_key(
+                    egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                    egui::Key::Z,
+                ),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z),
+            )
+        });
+        // Comma and period step one frame back and forth
+        let (previous_frame, next_frame) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Comma),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Period),
+            )
+        });
+        // Left and right arrows jump by the small increment, with Shift the large one
+        let jump = ctx.input_mut(|i| {
+            let keys = [
+                (egui::Modifiers::SHIFT, egui::Key::ArrowLeft, -1.0, true),
+                (egui::Modifiers::SHIFT, egui::Key::ArrowRight, 1.0, true),
+                (egui::Modifiers::NONE, egui::Key::ArrowLeft, -1.0, false),
+                (egui::Modifiers::NONE, egui::Key::ArrowRight, 1.0, false),
+            ];
+            keys.into_iter()
+                .find(|&(modifiers, key, _, _)| i.consume_key(modifiers, key))
+                .map(|(_, _, direction, large)| (direction, large))
+        });
+        if let Some(ref mut player) = self.player {
+            if let Some((direction, large)) = jump {
+                let (small_step, large_step) = player.seek_increments();
+                let step = if large { large_step } else { small_step };
+                player.seek_relative(direction * step.as_secs_f64());
+            }
+            if undo_seek {
+                player.undo_seek();
+            } else if redo_seek {
+                player.redo_seek();
+            }
+            if previous_frame || next_frame {
+                player.step_frames(if next_frame { 1 } else { -1 });
+            }
+            player.poll_control_surface(&mut self.media_keys);
+            #[cfg(feature = "gamepad")]
+            if let Some(ref mut gamepad) = self.gamepad {
+                player.poll_control_surface(gamepad);
+                // Gamepad input does not wake egui, so keep polling while paused
+                ctx.request_repaint_after(Duration::from_millis(50));
+            }
+            #[cfg(feature = "remote")]
+            if let Some(ref mut remote) = self.remote {
+                remote.update(player);
+            }
+        }
+
         // Menu bar
         TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -47,106 +567,721 @@ impl eframe::App for VideoPlayerApp {
                         ui.close_menu();
                         self.open_file(ctx);
                     }
+                    if ui.button("Open playlist...").clicked() {
+                        ui.close_menu();
+                        self.open_playlist(ctx);
+                    }
+                    if ui.button("Open image sequence...").clicked() {
+                        ui.close_menu();
+                        self.open_image_sequence(ctx);
+                    }
+                    if ui
+                        .add_enabled(
+                            self.player.is_some(),
+                            egui::Button::new("Previous file in folder").shortcut_text("PgUp"),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.step_file(false);
+                    }
+                    if ui
+                        .add_enabled(
+                            self.player.is_some(),
+                            egui::Button::new("Next file in folder").shortcut_text("PgDn"),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.step_file(true);
+                    }
+                    if ui.button("Restore session...").clicked() {
+                        ui.close_menu();
+                        self.restore_session(ctx);
+                    }
+                    if ui
+                        .add_enabled(self.player.is_some(), egui::Button::new("Save session..."))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.save_session();
+                    }
+                    if ui
+                        .add_enabled(self.player.is_some(), egui::Button::new("Export audio..."))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.export_audio();
+                    }
+                    if ui
+                        .add_enabled(self.player.is_some(), egui::Button::new("Burst capture..."))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.capture_burst();
+                    }
+                    let record_label = if self.recording {
+                        "Stop recording"
+                    } else {
+                        "Record stream..."
+                    };
+                    if ui
+                        .add_enabled(self.player.is_some(), egui::Button::new(record_label))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.toggle_recording();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.player.is_some(),
+                            egui::Button::new("Load subtitles..."),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.load_subtitles(false);
+                    }
+                    if ui
+                        .add_enabled(
+                            self.player.is_some(),
+                            egui::Button::new("Load secondary subtitles..."),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.load_subtitles(true);
+                    }
                     if ui.button("Quit").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                 });
-            });
-        });
-
-        // Control bar at bottom
-        if let Some(ref mut player) = self.player {
-            TopBottomPanel::bottom("controls").show(ctx, |ui| {
-                PlayerControls::show(ui, player);
-            });
-        }
-
-        // Video display area
-        CentralPanel::default().show(ctx, |ui| {
-            if let Some(ref mut player) = self.player {
-                // Update player and get current frame
-                player.update(ctx);
-
-                // Get data we need before the closures to avoid borrow conflicts
-                let texture_id = player.texture().map(|t| t.id());
-                let video_size = player.video_size();
-                let display_mode = player.display_mode();
-
-                let mut should_toggle = false;
-
-                if let Some(tex_id) = texture_id {
-                    let available_size = ui.available_size();
-
-                    match display_mode {
-                        DisplayMode::FitToWindow => {
-                            // Scale to fit while maintaining aspect ratio
-                            let aspect = video_size.0 as f32 / video_size.1 as f32;
-                            let available_aspect = available_size.x / available_size.y;
-
-                            let display_size = if aspect > available_aspect {
-                                Vec2::new(available_size.x, available_size.x / aspect)
+                if let Some(ref mut player) = self.player {
+                    if !player.playlist().is_empty() {
+                        ui.menu_button("Playlist", |ui| {
+                            let mut selected = None;
+                            let mut edit = None;
+                            let current = player.playlist().current_index();
+                            let len = player.playlist().len();
+                            for (i, item) in player.playlist().items().iter().enumerate() {
+                                // Length and size arrive once the item has been probed
+                                let mut label = item.display_name();
+                                if let Some(ref metadata) = item.metadata {
+                                    let secs = metadata.duration.as_secs();
+                                    label = format!("{}  ({}:{:02})", label, secs / 60, secs % 60);
+                                }
+                                let mut response = ui.radio(current == Some(i), label);
+                                if let Some(ref metadata) = item.metadata {
+                                    response = response.on_hover_text(format!(
+                                        "{}x{}",
+                                        metadata.width, metadata.height
+                                    ));
+                                }
+                                if response.clicked() {
+                                    selected = Some(i);
+                                }
+                                // Right-click an item to rearrange the queue
+                                response.context_menu(|ui| {
+                                    if ui
+                                        .add_enabled(i > 0, egui::Button::new("Move up"))
+                                        .clicked()
+                                    {
+                                        edit = Some(QueueEdit::Move(i, i - 1));
+                                    }
+                                    if ui
+                                        .add_enabled(i + 1 < len, egui::Button::new("Move down"))
+                                        .clicked()
+                                    {
+                                        edit = Some(QueueEdit::Move(i, i + 1));
+                                    }
+                                    if ui.button("Play next").clicked() {
+                                        edit = Some(QueueEdit::PlayNext(i));
+                                    }
+                                    if ui.button("Remove").clicked() {
+                                        edit = Some(QueueEdit::Remove(i));
+                                    }
+                                });
+                            }
+                            if let Some(edit) = edit {
+                                edit.apply(player.playlist_mut());
+                            }
+                            ui.separator();
+                            let mut shuffled = player.playlist().is_shuffled();
+                            if ui.checkbox(&mut shuffled, "Shuffle").changed() {
+                                if shuffled {
+                                    player.playlist_mut().shuffle();
+                                } else {
+                                    player.playlist_mut().unshuffle();
+                                }
+                            }
+                            let mut repeat = player.playlist().repeat();
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut repeat, RepeatMode::Off, "No repeat");
+                                ui.radio_value(&mut repeat, RepeatMode::One, "Repeat one");
+                                ui.radio_value(&mut repeat, RepeatMode::All, "Repeat all");
+                            });
+                            if repeat != player.playlist().repeat() {
+                                player.playlist_mut().set_repeat(repeat);
+                            }
+                            ui.separator();
+                            let result = if ui.button("Previous").clicked() {
+                                player.previous_item()
+                            } else if ui.button("Next").clicked() {
+                                player.next_item()
                             } else {
-                                Vec2::new(available_size.y * aspect, available_size.y)
+                                selected.map_or(Ok(()), |i| player.play_item(i))
                             };
-
-                            ui.centered_and_justified(|ui| {
-                                let response = ui.image((tex_id, display_size));
-                                if response.double_clicked() {
-                                    should_toggle = true;
+                            if let Err(e) = result {
+                                self.status_message = Some(format!("{}", e));
+                            }
+                        });
+                    }
+                }
+                ui.menu_button("View", |ui| {
+                    ui.checkbox(&mut self.show_telemetry, "Telemetry overlay");
+                    ui.checkbox(&mut self.show_subtitle_list, "Subtitle list");
+                    ui.checkbox(&mut self.show_frame_metadata, "Frame metadata");
+                    #[cfg(feature = "log-viewer")]
+                    ui.checkbox(&mut self.show_log, "Log");
+                    ui.checkbox(
+                        &mut self.saved_view.remember_view,
+                        "Remember zoom and pan per file",
+                    );
+                    let mut layout = self.controls_style.layout;
+                    ui.horizontal(|ui| {
+                        ui.label("Controls:");
+                        ui.radio_value(&mut layout, ControlsLayout::Full, "Full");
+                        ui.radio_value(&mut layout, ControlsLayout::Compact, "Compact");
+                        ui.radio_value(&mut layout, ControlsLayout::Overlay, "Overlay");
+                    });
+                    if layout != self.controls_style.layout {
+                        self.controls_style = match layout {
+                            ControlsLayout::Full => ControlsStyle::default(),
+                            ControlsLayout::Compact => ControlsStyle::compact(),
+                            ControlsLayout::Overlay => ControlsStyle::overlay(),
+                        };
+                    }
+                    if let Some(ref mut player) = self.player {
+                        ui.horizontal(|ui| {
+                            if ui.button("Freeze reference").clicked() {
+                                match player.freeze_reference(ctx) {
+                                    Ok(()) => {
+                                        self.compare.get_or_insert(CompareMode::SideBySide);
+                                    }
+                                    Err(e) => self.status_message = Some(format!("{}", e)),
                                 }
-                            });
+                            }
+                            let mut compare = self.compare;
+                            ui.radio_value(&mut compare, None, "Off");
+                            ui.radio_value(
+                                &mut compare,
+                                Some(CompareMode::SideBySide),
+                                "Side by side",
+                            );
+                            ui.radio_value(&mut compare, Some(CompareMode::Wipe), "Wipe");
+                            if compare.is_some() && player.reference_texture().is_none() {
+                                compare = None;
+                            }
+                            if compare.is_none() {
+                                player.clear_reference();
+                            }
+                            self.compare = compare;
+                        });
+                        if ui
+                            .add_enabled(
+                                player.waveform().is_none(),
+                                egui::Button::new("Show audio waveform"),
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            player.generate_waveform();
+                        }
+                        if ui
+                            .add_enabled(
+                                player.thumbnails().is_none(),
+                                egui::Button::new("Timeline previews"),
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            player.generate_thumbnails(
+                                Duration::from_secs(10),
+                                Some(std::env::temp_dir().join("egui_video_thumbnails")),
+                            );
+                        }
+                        if ui.button("Detect scene cuts").clicked() {
+                            ui.close_menu();
+                            player.detect_scenes();
+                        }
+                        if ui
+                            .button("Split into tracks")
+                            .on_hover_text("Chapters at long silences, for albums and audiobooks")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            player.split_tracks();
                         }
-                        DisplayMode::NativeSize => {
-                            ScrollArea::both().show(ui, |ui| {
-                                let response = ui.image((
-                                    tex_id,
-                                    Vec2::new(video_size.0 as f32, video_size.1 as f32),
-                                ));
-                                if response.double_clicked() {
-                                    should_toggle = true;
+                        let chapters = player.chapters();
+                        if !chapters.is_empty() {
+                            let current = player.current_chapter();
+                            ui.menu_button("Chapters", |ui| {
+                                for chapter in chapters {
+                                    let selected = current.as_ref() == Some(&chapter);
+                                    if ui.selectable_label(selected, &chapter.title).clicked() {
+                                        ui.close_menu();
+                                        player.seek(chapter.start);
+                                    }
                                 }
                             });
                         }
-                    }
-                }
+                        if ui
+                            .checkbox(&mut self.grayscale, "Grayscale effect")
+                            .changed()
+                        {
+                            if self.grayscale {
+                                player.add_video_effect(Box::new(Grayscale));
+                            } else {
+                                player.clear_video_effects();
+                            }
+                        }
+                        let stats = player.stats();
+                        ui.weak(format!(
+                            "Frame queue: {:.0} of {:.0} MB",
+                            stats.frame_memory as f64 / 1e6,
+                            stats.frame_memory_limit as f64 / 1e6
+                        ));
+                        let cache = stats.cache;
+                        if cache.hits + cache.misses > 0 {
+                            ui.weak(format!(
+                                "Network cache: {} hits, {} misses, {:.1} MB downloaded",
+                                cache.hits,
+                                cache.misses,
+                                cache.bytes_fetched as f64 / 1e6
+                            ));
+                        }
+                        let mut metering = player.audio_levels().is_some();
+                        if ui.checkbox(&mut metering, "Level meter").changed() {
+                            player.set_level_meter(metering);
+                        }
+                        let mut video = player.is_video_enabled();
+                        if ui.checkbox(&mut video, "Show video").changed() {
+                            player.set_video_enabled(video);
+                        }
+                        ui.add_enabled_ui(!video, |ui| {
+                            ui.label("Visualizer:");
+                            let before = self.visualizer;
+                            ui.radio_value(&mut self.visualizer, Visualization::Cover, "Cover art");
+                            ui.radio_value(&mut self.visualizer, Visualization::Bars, "Bars");
+                            ui.radio_value(&mut self.visualizer, Visualization::Scope, "Scope");
+                            ui.radio_value(
+                                &mut self.visualizer,
+                                Visualization::Spectrogram,
+                                "Spectrogram",
+                            );
+                            if self.visualizer != before {
+                                let visualizer: Box<dyn Visualizer> = match self.visualizer {
+                                    Visualization::Cover => {
+                                        player.clear_visualizer();
+                                        return;
+                                    }
+                                    Visualization::Bars => Box::new(SpectrumBars::default()),
+                                    Visualization::Scope => Box::new(Oscilloscope),
+                                    Visualization::Spectrogram => Box::new(Spectrogram),
+                                };
+                                player.set_visualizer(visualizer, [640, 360], 30.0);
+                            }
+                        });
+                        let mut watching = player.is_watching_file();
+                        if ui
+                            .checkbox(&mut watching, "Reload when file changes")
+                            .changed()
+                        {
+                            player.watch_file(watching);
+                        }
+                        ui.separator();
+                        let mut mode = player.display_mode();
+                        ui.radio_value(&mut mode, DisplayMode::FitToWindow, "Fit to window");
+                        ui.radio_value(&mut mode, DisplayMode::NativeSize, "100% logical");
+                        ui.radio_value(&mut mode, DisplayMode::NativePhysical, "100% physical");
+                        ui.radio_value(&mut mode, DisplayMode::IntegerScale, "Integer scaling");
+                        player.set_display_mode(mode);
 
-                if should_toggle {
-                    player.toggle_display_mode();
-                }
-            } else {
-                // No video loaded - show drop zone / open button
-                ui.centered_and_justified(|ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(ui.available_height() / 3.0);
+                        let mut checkerboard = player.checkerboard();
+                        if ui
+                            .checkbox(&mut checkerboard, "Checkerboard behind transparency")
+                            .changed()
+                        {
+                            player.set_checkerboard(checkerboard);
+                        }
+                        let mut timecode = player.timecode_overlay();
+                        if ui.checkbox(&mut timecode, "Timecode overlay").changed() {
+                            player.set_timecode_overlay(timecode);
+                        }
+                        ui.separator();
+                        let mut stop = player.stop_behavior();
+                        ui.label("On stop:");
+                        ui.radio_value(&mut stop, StopBehavior::ShowFirstFrame, "Show first frame");
+                        ui.radio_value(&mut stop, StopBehavior::ShowLastFrame, "Keep last frame");
+                        ui.radio_value(&mut stop, StopBehavior::Clear, "Clear");
+                        player.set_stop_behavior(stop);
+                        let mut nearest = player.texture_filter() == TextureFilter::Nearest;
+                        if ui
+                            .checkbox(&mut nearest, "Nearest-neighbor filtering")
+                            .changed()
+                        {
+                            player.set_texture_filter(if nearest {
+                                TextureFilter::Nearest
+                            } else {
+                                TextureFilter::Linear
+                            });
+                        }
 
-                        if let Some(ref err) = self.error_message {
-                            ui.colored_label(Color32::RED, err);
-                            ui.add_space(20.0);
+                        let mut settings = player.settings();
+                        let deinterlace_changed = ui
+                            .checkbox(&mut settings.deinterlace, "Deinterlace")
+                            .changed();
+                        let forced_changed = ui
+                            .checkbox(
+                                &mut settings.prefer_forced_subtitles,
+                                "Prefer forced subtitles",
+                            )
+                            .changed();
+                        if deinterlace_changed || forced_changed {
+                            if let Err(e) = player.set_settings(settings) {
+                                self.error_message = Some(e.to_string());
+                            }
+                        }
+                        let mut speed = player.speed();
+                        ui.horizontal(|ui| {
+                            ui.label("Speed");
+                            ui.add(
+                                egui::DragValue::new(&mut speed)
+                                    .speed(0.05)
+                                    .range(0.25..=4.0)
+                                    .suffix("x"),
+                            );
+                        });
+                        player.set_speed(speed);
+                        let mut pitch = player.pitch();
+                        let pitch_changed = ui
+                            .horizontal(|ui| {
+                                ui.label("Pitch");
+                                ui.add(
+                                    egui::DragValue::new(&mut pitch)
+                                        .speed(0.1)
+                                        .range(-12.0..=12.0)
+                                        .suffix(" semitones"),
+                                )
+                                .changed()
+                            })
+                            .inner;
+                        if pitch_changed {
+                            player.set_pitch(pitch);
+                        }
+                        let mut scrub = player.scrub_audio() == ScrubAudio::Snippets;
+                        if ui.checkbox(&mut scrub, "Audio while scrubbing").changed() {
+                            player.set_scrub_audio(if scrub {
+                                ScrubAudio::Snippets
+                            } else {
+                                ScrubAudio::Off
+                            });
+                        }
+                        let mut fade = player.audio_fade();
+                        ui.horizontal(|ui| {
+                            ui.label("Audio fade");
+                            ui.add(egui::DragValue::new(&mut fade).range(5..=50).suffix(" ms"));
+                        });
+                        player.set_audio_fade(fade);
+                        let mut fade_at_end = player.fade_out_at_end();
+                        if ui.checkbox(&mut fade_at_end, "Fade out at end").changed() {
+                            player.set_fade_out_at_end(fade_at_end);
+                        }
+                        let mut silence = player.silence_skip();
+                        let mut skip_silence = silence.is_some();
+                        if ui.checkbox(&mut skip_silence, "Skip silence").changed() {
+                            silence = skip_silence.then(SilenceSkip::default);
+                        }
+                        if let Some(settings) = silence.as_mut() {
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut settings.action, SilenceAction::Skip, "Skip");
+                                ui.radio_value(
+                                    &mut settings.action,
+                                    SilenceAction::SpeedUp(2.5),
+                                    "Speed up",
+                                );
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Silence threshold");
+                                ui.add(
+                                    egui::Slider::new(&mut settings.threshold_db, -60.0..=-20.0)
+                                        .suffix(" dB"),
+                                );
+                            });
+                        }
+                        if silence != player.silence_skip() {
+                            player.set_silence_skip(silence);
+                        }
+                        let mut sync = player.sync_policy();
+                        let mut video_master = !sync.audio_master;
+                        if ui
+                            .checkbox(&mut video_master, "Video master clock")
+                            .on_hover_text("Pace video on wall time, for clips with silent audio")
+                            .changed()
+                        {
+                            sync.audio_master = !video_master;
+                            player.set_sync_policy(sync);
+                        }
+                        let mut fps = player.image_frame_rate();
+                        let fps_changed = ui
+                            .horizontal(|ui| {
+                                ui.label("Image sequence rate");
+                                ui.add(
+                                    egui::DragValue::new(&mut fps)
+                                        .speed(1.0)
+                                        .range(0.0..=240.0)
+                                        .suffix(" fps"),
+                                )
+                                .changed()
+                            })
+                            .inner;
+                        if fps_changed {
+                            if let Err(e) = player.set_image_frame_rate(fps) {
+                                self.error_message = Some(e.to_string());
+                            }
+                        }
+                        let mut interpolation = player.interpolation();
+                        ui.label("Slow motion");
+                        ui.radio_value(&mut interpolation, InterpolationMode::Off, "Hold frames");
+                        ui.radio_value(
+                            &mut interpolation,
+                            InterpolationMode::Blend,
+                            "Blend frames",
+                        );
+                        ui.radio_value(
+                            &mut interpolation,
+                            InterpolationMode::MotionCompensated,
+                            "Motion interpolation",
+                        );
+                        if interpolation != player.interpolation() {
+                            if let Err(e) = player.set_interpolation(interpolation) {
+                                self.error_message = Some(e.to_string());
+                            }
                         }
 
-                        ui.heading("No video loaded");
-                        ui.add_space(10.0);
+                        ui.separator();
+                        let mut delay = player.subtitle_delay();
+                        ui.horizontal(|ui| {
+                            ui.label("Subtitle delay");
+                            ui.add(egui::DragValue::new(&mut delay).speed(10).suffix(" ms"));
+                        });
+                        player.set_subtitle_delay(delay);
+                        let mut latency = player.output_latency();
+                        ui.horizontal(|ui| {
+                            ui.label("Audio output latency");
+                            ui.add(egui::DragValue::new(&mut latency).speed(5).suffix(" ms"));
+                        });
+                        player.set_output_latency(latency);
+                        let mut scale = player.subtitle_scale();
+                        ui.horizontal(|ui| {
+                            ui.label("Subtitle scale");
+                            ui.add(
+                                egui::DragValue::new(&mut scale)
+                                    .speed(0.001)
+                                    .range(0.5..=2.0),
+                            );
+                        });
+                        player.set_subtitle_scale(scale);
+                    }
+                });
+                if let Some(ref status) = self.status_message {
+                    ui.separator();
+                    ui.label(status);
+                }
+            });
+        });
 
-                        if ui.button("Open Video File...").clicked() {
-                            self.open_file(ctx);
-                        }
+        // Show export progress and warnings in the menu bar
+        if let Some(ref mut player) = self.player {
+            if let Some(error) = player.error() {
+                self.status_message = Some(error.to_string());
+            }
+            while let Some(event) = player.poll_event() {
+                self.status_message = match event {
+                    PlayerEvent::Warning(message) => Some(message),
+                    PlayerEvent::ExportProgress(progress) => {
+                        Some(format!("Exporting audio... {:.0}%", progress * 100.0))
+                    }
+                    PlayerEvent::ExportFinished(path) => {
+                        Some(format!("Exported audio to {}", path.display()))
+                    }
+                    PlayerEvent::ExportFailed(e) => Some(format!("Audio export failed: {}", e)),
+                    PlayerEvent::PerformanceWarning {
+                        dropped_frames,
+                        drift_ms,
+                    } => Some(format!(
+                        "Playback is struggling ({} frames dropped, A/V drift {:.0} ms), try hardware decoding or a smaller window",
+                        dropped_frames, drift_ms
+                    )),
+                    PlayerEvent::BurstFinished(paths) => {
+                        Some(format!("Saved {} frames", paths.len()))
+                    }
+                    PlayerEvent::BurstFailed(e) => Some(format!("Burst capture failed: {}", e)),
+                    PlayerEvent::AudioOutputFallback { reason, .. } => {
+                        Some(format!("Audio output: {}", reason))
+                    }
+                    PlayerEvent::AudioDeviceLost => Some(
+                        "Audio device lost, switching to the default output".to_owned(),
+                    ),
+                    PlayerEvent::MediaReloaded(path) => {
+                        Some(format!("Reloaded {}", path.display()))
+                    }
+                    PlayerEvent::RecordingStarted(path) => {
+                        self.recording = true;
+                        Some(format!("Recording to {}", path.display()))
+                    }
+                    PlayerEvent::RecordingFinished(path) => {
+                        self.recording = false;
+                        Some(format!("Saved recording to {}", path.display()))
+                    }
+                    _ => continue,
+                };
+            }
+        }
 
-                        ui.add_space(10.0);
-                        ui.label("Or drag and drop a video file");
-                    });
+        // Control bar at bottom, unless the overlay layout puts it on the video
+        let overlay_controls = self.controls_style.layout == ControlsLayout::Overlay;
+        if let Some(ref mut player) = self.player {
+            if !overlay_controls {
+                TopBottomPanel::bottom("controls").show(ctx, |ui| {
+                    PlayerControls::show_with_style(ui, player, &self.controls_style);
                 });
             }
-        });
+            if self.show_subtitle_list {
+                SidePanel::right("subtitle_list").show(ctx, |ui| {
+                    SubtitleList::show(ui, player, &mut self.subtitle_query);
+                });
+            }
+        }
 
-        // Handle file drops
-        ctx.input(|i| {
-            if !i.raw.dropped_files.is_empty() {
-                if let Some(path) = i.raw.dropped_files[0].path.clone() {
-                    self.load_video(path, ctx);
+        if let Some(ref player) = self.player {
+            egui::Window::new("Frame metadata")
+                .open(&mut self.show_frame_metadata)
+                .show(ctx, |ui| match player.frame_metadata() {
+                    Some(metadata) if !metadata.is_empty() => {
+                        if let Some(ref timecode) = metadata.timecode {
+                            ui.label(format!("Timecode: {}", timecode));
+                        }
+                        if let Some(luminance) = metadata
+                            .mastering_display
+                            .and_then(|display| display.luminance)
+                        {
+                            ui.label(format!(
+                                "Mastering display: {:.4}-{:.0} cd/m²",
+                                luminance.0, luminance.1
+                            ));
+                        }
+                        if let Some((max_cll, max_fall)) = metadata.content_light_level {
+                            ui.label(format!("MaxCLL {} / MaxFALL {} cd/m²", max_cll, max_fall));
+                        }
+                        if let Some(ref sei) = metadata.sei_unregistered {
+                            ui.label(format!("User data SEI: {} bytes", sei.len()));
+                        }
+                        if let Some(ref gpmf) = metadata.gpmf {
+                            ui.label(format!("GPMF telemetry: {} bytes", gpmf.len()));
+                        }
+                    }
+                    _ => {
+                        ui.label("No side data on this frame");
+                    }
+                });
+        }
+
+        #[cfg(feature = "log-viewer")]
+        if let Some(ref log) = self.log {
+            egui::Window::new("Log")
+                .open(&mut self.show_log)
+                .default_size([600.0, 300.0])
+                .show(ctx, |ui| {
+                    egui_video::LogViewer::show(ui, log, &mut self.log_filter);
+                });
+        }
+
+        // Video display area
+        let mut dropped = None;
+        CentralPanel::default().show(ctx, |ui| {
+            if let Some(ref mut player) = self.player {
+                // Update player and get current frame
+                player.update(ctx);
+
+                // Show the frame, then draw overlays on top of it
+                let show_telemetry = self.show_telemetry;
+                if let Some(mode) = self.compare {
+                    CompareView::show(ui, player, mode, &mut self.compare_split);
+                } else if let Some(response) = VideoView::show(ui, player) {
+                    if let Some(sample) = player.telemetry().filter(|_| show_telemetry) {
+                        TelemetryOverlay::show(ui, response.rect, sample);
+                    }
+                    #[cfg(feature = "libass")]
+                    let styled = egui_video::AssOverlay::show(ui, response.rect, player);
+                    #[cfg(not(feature = "libass"))]
+                    let styled = false;
+                    if let Some(text) = player.subtitle().filter(|_| !styled) {
+                        SubtitleOverlay::show_with_font(
+                            ui,
+                            response.rect,
+                            &text,
+                            player.subtitle_font(),
+                        );
+                    }
+                    if let Some(text) = player.secondary_subtitle() {
+                        SubtitleOverlay::show_styled(
+                            ui,
+                            response.rect,
+                            &text,
+                            player.subtitle_font(),
+                            &SubtitleStyle::secondary(),
+                        );
+                    }
+                    if overlay_controls {
+                        PlayerControls::show_overlay_with_style(
+                            ui,
+                            player,
+                            response.rect,
+                            &self.controls_style,
+                        );
+                    }
                 }
+            } else {
+                // No video loaded - show drop zone / open button
+                let drop_zone = DropZone::show(ui, self.error_message.as_deref());
+                if drop_zone.open_clicked {
+                    self.open_file(ctx);
+                }
+                dropped = Some(drop_zone);
             }
         });
 
+        // Handle file drops - several files become a playlist
+        let (dropped, rejected) = match dropped {
+            Some(drop_zone) => (drop_zone.dropped, Vec::new()),
+            None => ctx.input(|i| {
+                i.raw
+                    .dropped_files
+                    .iter()
+                    .filter_map(|file| file.path.clone())
+                    .partition(|path| is_supported_media(path))
+            }),
+        };
+        if let Some(path) = rejected.first() {
+            self.status_message = Some(format!("Unsupported file type: {}", path.display()));
+        }
+        match dropped.len() {
+            0 => {}
+            1 => self.load_video(dropped[0].clone(), ctx),
+            _ => self.load_playlist(dropped, ctx),
+        }
+
         // Request continuous repaint during playback
         if let Some(ref player) = self.player {
             if player.is_playing() {
@@ -157,18 +1292,72 @@ impl eframe::App for VideoPlayerApp {
 }
 
 fn main() -> eframe::Result<()> {
+    let args = Args::parse();
     ffmpeg_next::init().expect("Failed to initialize FFmpeg");
 
+    let media_keys = egui_video::MediaKeys::new();
+    // Window size and position are restored from eframe storage
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1280.0, 720.0])
-            .with_min_inner_size([640.0, 480.0]),
+            .with_min_inner_size([640.0, 480.0])
+            .with_fullscreen(args.fullscreen),
+        event_loop_builder: media_key_hook(media_keys.sender()),
         ..Default::default()
     };
 
     eframe::run_native(
         "Video Player",
         options,
-        Box::new(|cc| Ok(Box::new(VideoPlayerApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(VideoPlayerApp::new(cc, args, media_keys)))),
     )
 }
+
+/// egui drops media keys, so catch them in the window messages before winit
+/// translates them. The message still goes on to winit, whose key event
+/// wakes egui to poll the surface.
+#[cfg(windows)]
+fn media_key_hook(sender: egui_video::MediaKeySender) -> Option<eframe::EventLoopBuilderHook> {
+    use egui_video::MediaKey;
+    use winit::platform::windows::EventLoopBuilderExtWindows;
+
+    /// Leading fields of the Win32 `MSG`
+    #[repr(C)]
+    struct Msg {
+        hwnd: isize,
+        message: u32,
+        w_param: usize,
+        l_param: isize,
+    }
+    const WM_KEYDOWN: u32 = 0x0100;
+    /// Set in `lParam` when the key was already down
+    const REPEAT_BIT: isize = 1 << 30;
+
+    Some(Box::new(
+        move |builder: &mut eframe::EventLoopBuilder<eframe::UserEvent>| {
+            builder.with_msg_hook(move |msg| {
+                // SAFETY: winit passes a valid `MSG` for the duration of the call
+                let msg = unsafe { &*msg.cast::<Msg>() };
+                if msg.message == WM_KEYDOWN && msg.l_param & REPEAT_BIT == 0 {
+                    let key = match msg.w_param {
+                        0xB0 => Some(MediaKey::NextTrack),
+                        0xB1 => Some(MediaKey::PreviousTrack),
+                        0xB2 => Some(MediaKey::Stop),
+                        0xB3 => Some(MediaKey::PlayPause),
+                        _ => None,
+                    };
+                    if let Some(key) = key {
+                        sender.press(key);
+                    }
+                }
+                false
+            });
+        },
+    ))
+}
+
+/// Other platforms give egui no way to see media keys
+#[cfg(not(windows))]
+fn media_key_hook(_sender: egui_video::MediaKeySender) -> Option<eframe::EventLoopBuilderHook> {
+    None
+}
@@ -0,0 +1,90 @@
+use egui::{CentralPanel, TopBottomPanel};
+use egui_video::{PlayerControls, VideoPlayer, VideoRenderer};
+
+/// Unsharp mask followed by a warm color grade
+const SHADER: &str = "
+fn post_process(color: vec4<f32>, uv: vec2<f32>) -> vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(video));
+    let blur = (textureSample(video, video_sampler, uv + vec2<f32>(texel.x, 0.0))
+        + textureSample(video, video_sampler, uv - vec2<f32>(texel.x, 0.0))
+        + textureSample(video, video_sampler, uv + vec2<f32>(0.0, texel.y))
+        + textureSample(video, video_sampler, uv - vec2<f32>(0.0, texel.y))) * 0.25;
+    let sharpened = color.rgb + (color.rgb - blur.rgb) * strength;
+    return vec4<f32>(sharpened * vec3<f32>(1.05, 1.0, 0.92), color.a);
+}
+
+const strength: f32 = 0.8;
+";
+
+struct ShaderApp {
+    player: Option<VideoPlayer>,
+    renderer: VideoRenderer,
+    error_message: Option<String>,
+}
+
+impl ShaderApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let render_state = cc
+            .wgpu_render_state
+            .as_ref()
+            .expect("the shader example needs the wgpu renderer");
+        let mut error_message = None;
+        let renderer = VideoRenderer::with_shader(render_state, SHADER).unwrap_or_else(|e| {
+            error_message = Some(format!("Shader error: {}", e));
+            VideoRenderer::new(render_state)
+        });
+        Self {
+            player: None,
+            renderer,
+            error_message,
+        }
+    }
+}
+
+impl eframe::App for ShaderApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            if ui.button("Open...").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_file() {
+                    match VideoPlayer::open(&path, ctx.clone()) {
+                        Ok(player) => self.player = Some(player),
+                        Err(e) => self.error_message = Some(format!("Failed to open video: {}", e)),
+                    }
+                }
+            }
+            if let Some(ref message) = self.error_message {
+                ui.colored_label(egui::Color32::RED, message);
+            }
+        });
+
+        if let Some(ref mut player) = self.player {
+            player.update(ctx);
+            TopBottomPanel::bottom("controls").show(ctx, |ui| {
+                PlayerControls::show(ui, player);
+            });
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+            if let Some(ref player) = self.player {
+                self.renderer.show(ui, player);
+            } else {
+                ui.centered_and_justified(|ui| ui.label("Open a video to see it sharpened"));
+            }
+        });
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    ffmpeg_next::init().expect("Failed to initialize FFmpeg");
+
+    let options = eframe::NativeOptions {
+        renderer: eframe::Renderer::Wgpu,
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Video Shader",
+        options,
+        Box::new(|cc| Ok(Box::new(ShaderApp::new(cc)))),
+    )
+}
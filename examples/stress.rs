@@ -0,0 +1,78 @@
+//! Opens and closes players in a loop and checks that resource counts return
+//! to their steady state after every iteration.
+//!
+//! cargo run --release --example stress -- <video file> [iterations]
+
+use egui_video::diagnostics::{live_resources, LiveResources};
+use egui_video::{StreamKind, VideoPlayer};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn main() -> anyhow::Result<()> {
+    ffmpeg_next::init().expect("Failed to initialize FFmpeg");
+
+    let mut args = std::env::args().skip(1);
+    let path = PathBuf::from(
+        args.next()
+            .ok_or_else(|| anyhow::anyhow!("usage: stress <video file> [iterations]"))?,
+    );
+    let iterations: usize = args.next().map_or(Ok(100), |s| s.parse())?;
+
+    // Headless context - textures are allocated but never rendered
+    let ctx = egui::Context::default();
+    let textures_before = ctx.tex_manager().read().num_allocated();
+    let baseline = live_resources();
+    let start = Instant::now();
+
+    for i in 0..iterations {
+        let mut player = VideoPlayer::open(&path, ctx.clone())?;
+        player.play();
+
+        // Let the decoder and audio output do some work
+        for _ in 0..10 {
+            player.update(&ctx);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let has_audio = player
+            .streams()
+            .iter()
+            .any(|stream| stream.kind == StreamKind::Audio);
+        let open = live_resources();
+        assert_eq!(
+            open,
+            LiveResources {
+                players: baseline.players + 1,
+                decoder_threads: baseline.decoder_threads + 1,
+                decode_workers: baseline.decode_workers + 1 + usize::from(has_audio),
+                textures: baseline.textures + 1,
+                audio_outputs: baseline.audio_outputs + 1,
+            },
+            "unexpected resource count while open (iteration {})",
+            i
+        );
+
+        drop(player);
+
+        assert_eq!(
+            live_resources(),
+            baseline,
+            "resources leaked after close (iteration {})",
+            i
+        );
+        assert_eq!(
+            ctx.tex_manager().read().num_allocated(),
+            textures_before,
+            "egui textures leaked after close (iteration {})",
+            i
+        );
+    }
+
+    println!(
+        "{} open/close cycles in {:.2?}, steady state: {:?}",
+        iterations,
+        start.elapsed(),
+        live_resources()
+    );
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Process-wide accounting of resources held by players.
+//!
+//! Applications that open and close many players (media browsers, playlists)
+//! can compare `live_resources()` before and after to detect leaks.
+
+use egui::TextureHandle;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PLAYERS: AtomicUsize = AtomicUsize::new(0);
+static DECODER_THREADS: AtomicUsize = AtomicUsize::new(0);
+static DECODE_WORKERS: AtomicUsize = AtomicUsize::new(0);
+static TEXTURES: AtomicUsize = AtomicUsize::new(0);
+static AUDIO_OUTPUTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Snapshot of resources currently alive across all players
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiveResources {
+    /// `VideoPlayer` instances not yet dropped
+    pub players: usize,
+    /// Decoder threads still running
+    pub decoder_threads: usize,
+    /// Per-stream video and audio decode threads still running
+    pub decode_workers: usize,
+    /// Video textures allocated in an egui context
+    pub textures: usize,
+    /// Open audio output streams
+    pub audio_outputs: usize,
+}
+
+/// Get the resources currently held by all players in this process
+#[must_use]
+pub fn live_resources() -> LiveResources {
+    LiveResources {
+        players: PLAYERS.load(Ordering::Relaxed),
+        decoder_threads: DECODER_THREADS.load(Ordering::Relaxed),
+        decode_workers: DECODE_WORKERS.load(Ordering::Relaxed),
+        textures: TEXTURES.load(Ordering::Relaxed),
+        audio_outputs: AUDIO_OUTPUTS.load(Ordering::Relaxed),
+    }
+}
+
+/// Kinds of tracked resources
+#[derive(Clone, Copy)]
+pub(crate) enum Resource {
+    Player,
+    DecoderThread,
+    DecodeWorker,
+    Texture,
+    AudioOutput,
+}
+
+impl Resource {
+    fn counter(self) -> &'static AtomicUsize {
+        match self {
+            Resource::Player => &PLAYERS,
+            Resource::DecoderThread => &DECODER_THREADS,
+            Resource::DecodeWorker => &DECODE_WORKERS,
+            Resource::Texture => &TEXTURES,
+            Resource::AudioOutput => &AUDIO_OUTPUTS,
+        }
+    }
+}
+
+/// Registers a resource for as long as the guard is alive
+pub(crate) struct ResourceGuard(Resource);
+
+impl ResourceGuard {
+    pub fn new(resource: Resource) -> Self {
+        resource.counter().fetch_add(1, Ordering::Relaxed);
+        Self(resource)
+    }
+}
+
+impl Drop for ResourceGuard {
+    fn drop(&mut self) {
+        self.0.counter().fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Texture handle counted for as long as the texture is allocated
+pub(crate) struct TrackedTexture {
+    handle: TextureHandle,
+    _guard: ResourceGuard,
+}
+
+impl TrackedTexture {
+    pub fn new(handle: TextureHandle) -> Self {
+        Self {
+            handle,
+            _guard: ResourceGuard::new(Resource::Texture),
+        }
+    }
+}
+
+impl Deref for TrackedTexture {
+    type Target = TextureHandle;
+
+    fn deref(&self) -> &TextureHandle {
+        &self.handle
+    }
+}
+
+impl DerefMut for TrackedTexture {
+    fn deref_mut(&mut self) -> &mut TextureHandle {
+        &mut self.handle
+    }
+}
@@ -1,5 +1,40 @@
+pub mod diagnostics;
 pub mod player;
 pub mod ui;
 
-pub use player::{DisplayMode, PlayerState, VideoPlayer, Volume};
-pub use ui::controls::PlayerControls;
+pub use player::is_supported_media;
+pub use player::thumbnail;
+#[cfg(feature = "gamepad")]
+pub use player::GamepadSurface;
+#[cfg(feature = "remote")]
+pub use player::RemoteServer;
+pub use player::{
+    Attachment, AudioBackend, AudioFormat, AudioLevels, AudioOutputFormat, AudioTap, CacheStats,
+    Chapter, ColorManagement, ColorMatrix, ColorRange, ControlSurface, DecodedVideoFrame,
+    DecoderOptions, DisplayMode, FrameIntervals, FrameMetadata, FramePacing, FrameSink,
+    InterpolationMode, MasteringDisplay, MediaKey, MediaKeySender, MediaKeys, MemoryBudget,
+    NetworkCache, Oscilloscope, OverlayFlags, PlaybackStats, PlayerConfig, PlayerError,
+    PlayerEvent, PlayerGroup, PlayerState, Playlist, RecoveryPolicy, RepeatMode, ScrubAudio,
+    SeekMode, Settings, SilenceAction, SilenceSkip, Spectrogram, SpectrumBars, StopBehavior,
+    StreamInfo, StreamKind, SubtitleCue, SurfaceAction, SyncPolicy, TelemetrySample,
+    ThreadPriority, ThumbnailTrack, TimeDisplayMode, VideoEffect, VideoPlayer, VideoPlayerBuilder,
+    ViewTransform, Visualizer, Volume, Waveform,
+};
+#[cfg(feature = "libass")]
+pub use ui::ass::AssOverlay;
+pub use ui::compare::{CompareMode, CompareView};
+pub use ui::controls::{ControlIcons, ControlsLayout, ControlsStyle, PlayerControls};
+pub use ui::drop_zone::{DropZone, DropZoneResponse};
+pub use ui::level_meter::LevelMeter;
+#[cfg(feature = "log-viewer")]
+pub use ui::log_viewer::{LogCapture, LogRecord, LogViewer};
+pub use ui::media_info::{media_badge, MediaInfoWindow};
+#[cfg(feature = "render-wgpu")]
+pub use ui::renderer::VideoRenderer;
+pub use ui::seek_slider::SeekSlider;
+pub use ui::subtitle_list::SubtitleList;
+pub use ui::subtitles::{SubtitleOverlay, SubtitlePosition, SubtitleStyle};
+pub use ui::telemetry::TelemetryOverlay;
+pub use ui::timecode::TimecodeOverlay;
+pub use ui::video::VideoView;
+pub use ui::waveform::WaveformBar;
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::Sender;
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::frame::Video as VideoFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::software::scaling::{Context as ScalerContext, Flags};
+use ffmpeg_next::{codec, Packet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::events::PlayerEvent;
+use super::silence::{self, SilenceSkip};
+
+/// Size frames are reduced to before comparing them
+const THUMB_WIDTH: u32 = 64;
+const THUMB_HEIGHT: u32 = 36;
+/// Histogram bins per color channel
+const BINS: usize = 16;
+/// Fraction of the histogram that must change between frames to count as a cut
+const SCENE_THRESHOLD: f32 = 0.35;
+/// Shortest scene reported; suppresses flashes and fast pans
+const MIN_SCENE_SECS: f64 = 0.5;
+/// Quiet gaps between tracks of an album or concert recording: quieter and
+/// longer than pauses in speech or music
+const TRACK_GAP: SilenceSkip = SilenceSkip {
+    action: silence::SilenceAction::Skip,
+    threshold_db: -50.0,
+    min_duration: Duration::from_secs(2),
+};
+/// Shortest track reported; suppresses splits at quiet passages within a piece
+pub const MIN_TRACK_LENGTH: Duration = Duration::from_secs(30);
+
+type Histogram = [u32; BINS * 3];
+
+/// Decode the video of `path` on a background thread and send the timestamp of
+/// every detected scene cut as it is found. The channel closes when the pass ends.
+pub fn start_scene_detection_thread(
+    path: PathBuf,
+    sender: Sender<Duration>,
+    cancel: Arc<AtomicBool>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || {
+        if let Err(e) = detect_scenes(&path, &sender, &cancel) {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Scene detection failed: {:#}",
+                e
+            )));
+        }
+    });
+}
+
+fn detect_scenes(path: &Path, sender: &Sender<Duration>, cancel: &AtomicBool) -> Result<()> {
+    let mut input = ffmpeg_next::format::input(path).context("Failed to open input file")?;
+
+    let stream = input
+        .streams()
+        .best(Type::Video)
+        .ok_or_else(|| anyhow!("No video stream found"))?;
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+    let mut decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()?;
+
+    let mut scaler: Option<(ScalerContext, (Pixel, u32, u32))> = None;
+    let mut previous: Option<Histogram> = None;
+    let mut last_cut = f64::NEG_INFINITY;
+
+    let mut packet = Packet::empty();
+    let mut decoded = VideoFrame::empty();
+    let mut thumb = VideoFrame::empty();
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match packet.read(&mut input) {
+            Ok(()) => {}
+            Err(ffmpeg_next::Error::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        }
+        // Skip other streams and corrupt packets
+        if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+            continue;
+        }
+
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            // Recreate the scaler if the frame size or format changes mid-stream
+            let source = (decoded.format(), decoded.width(), decoded.height());
+            if scaler.as_ref().map(|(_, format)| *format) != Some(source) {
+                let context = ScalerContext::get(
+                    source.0,
+                    source.1,
+                    source.2,
+                    Pixel::RGB24,
+                    THUMB_WIDTH,
+                    THUMB_HEIGHT,
+                    Flags::AREA,
+                )?;
+                scaler = Some((context, source));
+            }
+            let Some((ref mut context, _)) = scaler else {
+                continue;
+            };
+            context.run(&decoded, &mut thumb)?;
+
+            let histogram = histogram(&thumb);
+            let pts = decoded.timestamp().unwrap_or(0) as f64 * time_base;
+            if let Some(ref previous) = previous {
+                if difference(previous, &histogram) > SCENE_THRESHOLD
+                    && pts - last_cut >= MIN_SCENE_SECS
+                    && pts > 0.0
+                {
+                    last_cut = pts;
+                    if sender.send(Duration::from_secs_f64(pts)).is_err() {
+                        return Ok(()); // Player went away
+                    }
+                }
+            }
+            previous = Some(histogram);
+        }
+    }
+
+    Ok(())
+}
+
+/// Per-channel color histogram of a packed RGB24 frame
+fn histogram(frame: &VideoFrame) -> Histogram {
+    let mut histogram = [0u32; BINS * 3];
+    let stride = frame.stride(0);
+    let row_len = frame.width() as usize * 3;
+    for row in frame.data(0).chunks(stride).take(frame.height() as usize) {
+        for pixel in row[..row_len.min(row.len())].chunks_exact(3) {
+            for (channel, &value) in pixel.iter().enumerate() {
+                histogram[channel * BINS + value as usize * BINS / 256] += 1;
+            }
+        }
+    }
+    histogram
+}
+
+/// Fraction of samples that moved between histogram bins (0.0 to 1.0)
+fn difference(a: &Histogram, b: &Histogram) -> f32 {
+    let total: u32 = a.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let moved: u32 = a.iter().zip(b).map(|(&x, &y)| x.abs_diff(y)).sum();
+    moved as f32 / (2 * total) as f32
+}
+
+/// Decode the audio of `path` on a background thread and send the start of
+/// every track after the first, in the middle of the quiet gap before it, for
+/// long recordings without chapters. The channel closes when the pass ends.
+pub fn start_track_split_thread(
+    path: PathBuf,
+    sender: Sender<Duration>,
+    cancel: Arc<AtomicBool>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || {
+        let mut last = Duration::ZERO;
+        let found = |gap: Range<Duration>| {
+            let split = gap.start + (gap.end - gap.start) / 2;
+            if split - last >= MIN_TRACK_LENGTH {
+                last = split;
+                let _ = sender.send(split);
+            }
+        };
+        if let Err(e) = silence::detect_silence(&path, &TRACK_GAP, found, &cancel) {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Track splitting failed: {:#}",
+                e
+            )));
+        }
+    });
+}
@@ -0,0 +1,238 @@
+//! ASS/SSA subtitles rendered by libass, with their styles, positioning and
+//! karaoke effects. Requires the `libass` feature and the libass library.
+
+use anyhow::{anyhow, Context, Result};
+use egui::{Color32, ColorImage};
+use parking_lot::Mutex;
+use std::ffi::{c_char, c_int, c_longlong, c_void, CString};
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+
+use super::attachments::Attachment;
+
+/// Let fontconfig or the platform's font provider find system fonts
+const ASS_FONTPROVIDER_AUTODETECT: c_int = 1;
+
+#[repr(C)]
+struct AssImage {
+    w: c_int,
+    h: c_int,
+    stride: c_int,
+    /// One byte of coverage per pixel
+    bitmap: *const u8,
+    /// RGBA, with alpha as transparency (0 is opaque)
+    color: u32,
+    dst_x: c_int,
+    dst_y: c_int,
+    next: *const AssImage,
+    kind: c_int,
+}
+
+#[link(name = "ass")]
+extern "C" {
+    fn ass_library_init() -> *mut c_void;
+    fn ass_library_done(library: *mut c_void);
+    fn ass_add_font(library: *mut c_void, name: *const c_char, data: *const c_char, size: c_int);
+    fn ass_renderer_init(library: *mut c_void) -> *mut c_void;
+    fn ass_renderer_done(renderer: *mut c_void);
+    fn ass_set_frame_size(renderer: *mut c_void, w: c_int, h: c_int);
+    fn ass_set_storage_size(renderer: *mut c_void, w: c_int, h: c_int);
+    fn ass_set_fonts(
+        renderer: *mut c_void,
+        default_font: *const c_char,
+        default_family: *const c_char,
+        font_provider: c_int,
+        config: *const c_char,
+        update: c_int,
+    );
+    fn ass_read_memory(
+        library: *mut c_void,
+        buf: *mut c_char,
+        size: usize,
+        codepage: *const c_char,
+    ) -> *mut c_void;
+    fn ass_free_track(track: *mut c_void);
+    fn ass_render_frame(
+        renderer: *mut c_void,
+        track: *mut c_void,
+        now: c_longlong,
+        detect_change: *mut c_int,
+    ) -> *const AssImage;
+}
+
+/// An ASS/SSA script with its libass renderer
+pub(crate) struct AssSubtitles(Mutex<AssState>);
+
+struct AssState {
+    library: *mut c_void,
+    renderer: *mut c_void,
+    track: *mut c_void,
+    /// Last rendered image, reused while libass reports no change
+    last: Option<Arc<ColorImage>>,
+}
+
+// The libass objects are only touched while the mutex is held
+unsafe impl Send for AssState {}
+
+impl AssSubtitles {
+    /// Read a script file, with the fonts attached to the media available to it
+    pub fn load(path: &Path, fonts: &[Attachment], video_size: [u32; 2]) -> Result<Self> {
+        let script = std::fs::read(path)
+            .with_context(|| format!("Failed to read subtitles from {}", path.display()))?;
+        Self::new(&script, fonts, video_size)
+    }
+
+    /// Load a script, with the fonts attached to the media available to it
+    /// alongside system fonts. `video_size` is the size of the unscaled video.
+    pub fn new(script: &[u8], fonts: &[Attachment], video_size: [u32; 2]) -> Result<Self> {
+        unsafe {
+            let library = ass_library_init();
+            if library.is_null() {
+                return Err(anyhow!("Failed to initialize libass"));
+            }
+            for font in fonts.iter().filter(|attachment| attachment.is_font()) {
+                let name = CString::new(font.filename.as_str()).unwrap_or_default();
+                ass_add_font(
+                    library,
+                    name.as_ptr(),
+                    font.data.as_ptr().cast(),
+                    font.data.len() as c_int,
+                );
+            }
+            let renderer = ass_renderer_init(library);
+            if renderer.is_null() {
+                ass_library_done(library);
+                return Err(anyhow!("Failed to create the libass renderer"));
+            }
+            ass_set_fonts(
+                renderer,
+                ptr::null(),
+                c"sans-serif".as_ptr(),
+                ASS_FONTPROVIDER_AUTODETECT,
+                ptr::null(),
+                1,
+            );
+            ass_set_storage_size(renderer, video_size[0] as c_int, video_size[1] as c_int);
+            let mut buffer = script.to_vec();
+            let track = ass_read_memory(
+                library,
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+                ptr::null(),
+            );
+            if track.is_null() {
+                ass_renderer_done(renderer);
+                ass_library_done(library);
+                return Err(anyhow!("Not a valid ASS/SSA script"));
+            }
+            Ok(Self(Mutex::new(AssState {
+                library,
+                renderer,
+                track,
+                last: None,
+            })))
+        }
+    }
+
+    /// Render the subtitles shown at `time_ms` on the script's timeline onto a
+    /// transparent image of `size`. Returns None when nothing is shown.
+    pub fn render(&self, time_ms: i64, size: [usize; 2]) -> Option<Arc<ColorImage>> {
+        let mut state = self.0.lock();
+        let [width, height] = size;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut change: c_int = 0;
+        let mut image = unsafe {
+            ass_set_frame_size(state.renderer, width as c_int, height as c_int);
+            ass_render_frame(state.renderer, state.track, time_ms, &mut change)
+        };
+        if image.is_null() {
+            state.last = None;
+            return None;
+        }
+        if let Some(ref last) = state.last {
+            if change == 0 && last.size == size {
+                return Some(last.clone());
+            }
+        }
+
+        let mut out = ColorImage::new(size, Color32::TRANSPARENT);
+        while let Some(part) = unsafe { image.as_ref() } {
+            blend(&mut out, part);
+            image = part.next;
+        }
+        let out = Arc::new(out);
+        state.last = Some(out.clone());
+        Some(out)
+    }
+}
+
+/// Check if `path` is an ASS or SSA script
+pub fn is_ass_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ass") || ext.eq_ignore_ascii_case("ssa"))
+}
+
+/// Draw a rendered subtitle image over a frame of the same size
+pub fn draw_over(frame: &mut ColorImage, overlay: &ColorImage) {
+    for (pixel, &top) in frame.pixels.iter_mut().zip(&overlay.pixels) {
+        let keep = 255 - u16::from(top.a());
+        let mix = |top: u8, bottom: u8| (u16::from(top) + u16::from(bottom) * keep / 255) as u8;
+        *pixel = Color32::from_rgba_premultiplied(
+            mix(top.r(), pixel.r()),
+            mix(top.g(), pixel.g()),
+            mix(top.b(), pixel.b()),
+            mix(top.a(), pixel.a()),
+        );
+    }
+}
+
+/// Draw one coverage bitmap of a single color over `out`
+fn blend(out: &mut ColorImage, image: &AssImage) {
+    let [r, g, b] = [24, 16, 8].map(|shift| (image.color >> shift) as u8);
+    let opacity = 255 - (image.color & 0xff) as u8;
+    let [width, height] = out.size;
+    for y in 0..image.h.max(0) as usize {
+        let out_y = image.dst_y as usize + y;
+        if out_y >= height {
+            break;
+        }
+        let row = unsafe {
+            std::slice::from_raw_parts(
+                image.bitmap.add(y * image.stride as usize),
+                image.w.max(0) as usize,
+            )
+        };
+        for (x, &coverage) in row.iter().enumerate() {
+            let out_x = image.dst_x as usize + x;
+            if out_x >= width || coverage == 0 {
+                continue;
+            }
+            let alpha = u16::from(coverage) * u16::from(opacity) / 255;
+            let pixel = &mut out.pixels[out_y * width + out_x];
+            // Source over, on premultiplied colors
+            let mix = |src: u8, dst: u8| {
+                ((u16::from(src) * alpha + u16::from(dst) * (255 - alpha)) / 255) as u8
+            };
+            *pixel = Color32::from_rgba_premultiplied(
+                mix(r, pixel.r()),
+                mix(g, pixel.g()),
+                mix(b, pixel.b()),
+                (alpha + u16::from(pixel.a()) * (255 - alpha) / 255) as u8,
+            );
+        }
+    }
+}
+
+impl Drop for AssState {
+    fn drop(&mut self) {
+        unsafe {
+            ass_free_track(self.track);
+            ass_renderer_done(self.renderer);
+            ass_library_done(self.library);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use egui::ColorImage;
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::format::stream::{Disposition, Stream};
+use ffmpeg_next::media::Type;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Font extensions accepted when an attachment's MIME type is missing or generic
+const FONT_EXTENSIONS: [&str; 3] = ["ttf", "otf", "ttc"];
+
+/// A file embedded in the container, such as a font or cover image in MKV
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub data: Arc<[u8]>,
+}
+
+impl Attachment {
+    /// Whether this is a TrueType or OpenType font
+    #[must_use]
+    pub fn is_font(&self) -> bool {
+        let mime = self.mime_type.to_ascii_lowercase();
+        mime.starts_with("font/")
+            || mime.contains("truetype")
+            || mime.contains("opentype")
+            || mime.contains("font-ttf")
+            || Path::new(&self.filename)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| FONT_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+    }
+
+    /// Whether this is an image
+    #[must_use]
+    pub fn is_image(&self) -> bool {
+        self.mime_type.to_ascii_lowercase().starts_with("image/")
+    }
+}
+
+/// Collect the attachment streams of an input, and the cover pictures of audio
+/// files (MP3, M4A, FLAC) as `cover.*` images. Attachment data lives in the
+/// codec extradata.
+pub fn read_attachments(input: &Input) -> Vec<Attachment> {
+    input
+        .streams()
+        .filter_map(|stream| {
+            if stream.disposition().contains(Disposition::ATTACHED_PIC) {
+                return attached_picture(&stream);
+            }
+            if stream.parameters().medium() != Type::Attachment {
+                return None;
+            }
+            let parameters = stream.parameters();
+            let data = unsafe {
+                let params = &*parameters.as_ptr();
+                if params.extradata.is_null() || params.extradata_size <= 0 {
+                    return None;
+                }
+                std::slice::from_raw_parts(params.extradata, params.extradata_size as usize)
+            };
+            let metadata = stream.metadata();
+            Some(Attachment {
+                filename: metadata.get("filename").unwrap_or_default().to_owned(),
+                mime_type: metadata.get("mimetype").unwrap_or_default().to_owned(),
+                data: Arc::from(data),
+            })
+        })
+        .collect()
+}
+
+/// The picture of a stream flagged as attached, e.g. ID3 front cover art
+fn attached_picture(stream: &Stream) -> Option<Attachment> {
+    let data = unsafe {
+        let packet = &(*stream.as_ptr()).attached_pic;
+        if packet.data.is_null() || packet.size <= 0 {
+            return None;
+        }
+        std::slice::from_raw_parts(packet.data, packet.size as usize)
+    };
+    let format = image::guess_format(data).ok()?;
+    Some(Attachment {
+        filename: format!("cover.{}", format.extensions_str().first()?),
+        mime_type: format.to_mime_type().to_owned(),
+        data: Arc::from(data),
+    })
+}
+
+/// Pick the cover image, preferring the Matroska convention of a file named `cover.*`
+pub fn cover_art(attachments: &[Attachment]) -> Option<&Attachment> {
+    let is_cover = |attachment: &&Attachment| {
+        Path::new(&attachment.filename)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("cover"))
+    };
+    let mut images = attachments.iter().filter(|a| a.is_image());
+    images.clone().find(is_cover).or_else(|| images.next())
+}
+
+/// Decode an image attachment
+pub fn decode_image(attachment: &Attachment) -> Result<ColorImage> {
+    let image = image::load_from_memory(&attachment.data)
+        .with_context(|| format!("Failed to decode {}", attachment.filename))?
+        .to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
@@ -1,54 +1,206 @@
 use rodio::Source;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use super::circular_buffer::CircularBuffer;
 use super::clock::AudioClock;
+use super::ring_buffer::AudioConsumer;
+use super::visualizer::SampleHistory;
 
-/// Audio source that pulls from a circular buffer and updates the audio clock.
+/// Samples moved from the queue per read. Small enough that the clock,
+/// which advances once per block, is updated several times per output callback.
+const BLOCK_SIZE: usize = 512;
+
+/// Allowed length of volume ramps in milliseconds
+pub const FADE_RANGE_MS: RangeInclusive<u32> = 5..=50;
+
+/// Volume ramp shared by the player and its `AudioSource`, to avoid clicks.
+/// When faded out, the source holds its place and plays silence instead of
+/// pausing the output abruptly; after a seek it fades back in.
+#[derive(Clone)]
+pub struct AudioFade {
+    audible: Arc<AtomicBool>,
+    duration_ms: Arc<AtomicU32>,
+}
+
+impl AudioFade {
+    pub fn new(duration_ms: u32) -> Self {
+        Self {
+            audible: Arc::new(AtomicBool::new(true)),
+            duration_ms: Arc::new(AtomicU32::new(duration_ms)),
+        }
+    }
+
+    pub fn fade_in(&self) {
+        self.audible.store(true, Ordering::Relaxed);
+    }
+
+    pub fn fade_out(&self) {
+        self.audible.store(false, Ordering::Relaxed);
+    }
+
+    pub fn set_duration_ms(&self, ms: u32) {
+        self.duration_ms.store(ms, Ordering::Relaxed);
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        self.duration_ms.load(Ordering::Relaxed)
+    }
+}
+
+/// Left/right balance shared by the player and its `AudioSource`,
+/// from -1.0 (left only) to 1.0 (right only)
+#[derive(Clone, Default)]
+pub struct AudioBalance(Arc<AtomicU32>);
+
+impl AudioBalance {
+    pub fn set(&self, balance: f32) {
+        self.0
+            .store(balance.clamp(-1.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Gains of the left and right channels; the louder side stays at full volume
+    fn gains(&self) -> (f32, f32) {
+        let balance = self.get();
+        ((1.0 - balance).min(1.0), (1.0 + balance).min(1.0))
+    }
+}
+
+/// Audio source that reads blocks from the decoder's queue and updates the audio clock.
 /// Implements rodio::Source for playback.
 pub struct AudioSource {
-    buffer: Arc<CircularBuffer<f32>>,
+    consumer: AudioConsumer,
     clock: AudioClock,
-    samples_consumed: u64,
+    fade: AudioFade,
+    /// Current volume of the ramp (0.0 to 1.0)
+    gain: f32,
+    balance: AudioBalance,
+    /// Channel of the next sample handed to the output
+    channel: u16,
+    block: Box<[f32]>,
+    /// Samples read into `block`, and the index of the next one to play
+    block_len: usize,
+    block_position: usize,
+    /// Samples played while the clock was paused (during a fade-out),
+    /// added once it runs again so the clock does not fall behind
+    unclocked: u64,
+    /// Samples taken since the output was last counted as pulling
+    pulled: usize,
+    /// Record of the blocks played, for visualizers
+    history: Option<SampleHistory>,
 }
 
 impl AudioSource {
-    pub fn new(buffer: Arc<CircularBuffer<f32>>, clock: AudioClock) -> Self {
+    pub fn new(consumer: AudioConsumer, clock: AudioClock, fade: AudioFade) -> Self {
         Self {
-            buffer,
+            consumer,
             clock,
-            samples_consumed: 0,
+            fade,
+            gain: 0.0,
+            balance: AudioBalance::default(),
+            channel: 0,
+            block: vec![0.0; BLOCK_SIZE].into_boxed_slice(),
+            block_len: 0,
+            block_position: 0,
+            unclocked: 0,
+            pulled: 0,
+            history: None,
         }
     }
+
+    /// Record the played samples for audio visualizers
+    pub fn with_history(mut self, history: SampleHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Scale the first two channels (front left and right) by a shared balance
+    pub fn with_balance(mut self, balance: AudioBalance) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    /// Start at full volume instead of fading in, for gapless transitions
+    pub fn without_fade_in(mut self) -> Self {
+        self.gain = 1.0;
+        self
+    }
+
+    /// Move the gain one sample towards the fade target
+    fn step_gain(&mut self, audible: bool) {
+        let samples_per_ms = self.clock.sample_rate() * u32::from(self.clock.channels()) / 1000;
+        let step = 1.0 / (self.fade.duration_ms() * samples_per_ms).max(1) as f32;
+        self.gain = if audible {
+            (self.gain + step).min(1.0)
+        } else {
+            (self.gain - step).max(0.0)
+        };
+    }
 }
 
 impl Iterator for AudioSource {
     type Item = f32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // Check if we need to clear the buffer (after seek)
+        // Let the player notice when the device stops asking for samples
+        self.pulled += 1;
+        if self.pulled == BLOCK_SIZE {
+            self.pulled = 0;
+            self.clock.mark_pulled();
+        }
+        // Every sample returned is one channel of the interleaved output
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.clock.channels().max(1);
+
+        // Drop the partly played block after a seek. The decoder discards the
+        // queued samples itself, as the queue may already hold the new position.
         if self.clock.should_clear_buffer() {
-            self.buffer.clear();
-            self.samples_consumed = 0;
+            self.block_len = 0;
+            self.block_position = 0;
+            self.unclocked = 0;
+            self.gain = 0.0; // Fade in at the new position
             return Some(0.0); // Return silence
         }
 
-        // Try to get a sample from the circular buffer
-        match self.buffer.try_pop() {
-            Some(sample) => {
-                self.samples_consumed += 1;
-                // Update clock every batch of samples for efficiency
-                if self.samples_consumed % 256 == 0 {
-                    self.clock.advance_samples(256);
-                }
-                Some(sample)
+        let audible = self.fade.audible.load(Ordering::Relaxed);
+        if !audible && self.gain == 0.0 {
+            return Some(0.0); // Faded out - hold the position
+        }
+
+        if self.block_position == self.block_len {
+            // The finished block has been handed to the output
+            if self.clock.is_paused() {
+                self.unclocked += self.block_len as u64;
+            } else if self.block_len > 0 || self.unclocked > 0 {
+                self.clock
+                    .advance_samples(self.block_len as u64 + self.unclocked);
+                self.unclocked = 0;
             }
-            None => {
-                // Buffer underrun - return silence
-                Some(0.0)
+            self.block_len = self.consumer.read(&mut self.block);
+            self.block_position = 0;
+            if self.block_len == 0 {
+                return Some(0.0); // Buffer underrun - return silence
+            }
+            if let Some(ref history) = self.history {
+                history.push(&self.block[..self.block_len]);
             }
         }
+
+        self.step_gain(audible);
+        let (left, right) = self.balance.gains();
+        let pan = match channel {
+            0 => left,
+            1 => right,
+            _ => 1.0,
+        };
+        let sample = self.block[self.block_position] * self.gain * pan;
+        self.block_position += 1;
+        Some(sample)
     }
 }
 
@@ -0,0 +1,169 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Limit of the budget shared by players that do not set their own
+const DEFAULT_LIMIT: u64 = 1 << 30;
+/// How often a decoder waiting for memory checks again
+const WAIT_POLL: Duration = Duration::from_millis(2);
+
+/// Memory limit for decoded frames waiting to be shown, shared by players.
+///
+/// Each player may queue an equal share of the limit in RGBA frames, so its
+/// queue holds fewer 4K frames than 1080p ones. A player always gets at least
+/// one frame. Players use `MemoryBudget::global()` unless `PlayerConfig` sets
+/// another budget.
+#[derive(Clone)]
+pub struct MemoryBudget {
+    state: Arc<BudgetState>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: AtomicU64,
+    used: AtomicU64,
+    players: AtomicUsize,
+}
+
+impl MemoryBudget {
+    /// A budget of `limit` bytes for the players given it
+    #[must_use]
+    pub fn new(limit: u64) -> Self {
+        Self {
+            state: Arc::new(BudgetState {
+                limit: AtomicU64::new(limit),
+                used: AtomicU64::new(0),
+                players: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// The process-wide budget, 1 GiB unless changed with `set_limit()`
+    #[must_use]
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<MemoryBudget> = OnceLock::new();
+        GLOBAL.get_or_init(|| Self::new(DEFAULT_LIMIT)).clone()
+    }
+
+    /// Change the limit. Queues above their new share shrink as frames are shown.
+    pub fn set_limit(&self, limit: u64) {
+        self.state.limit.store(limit, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn limit(&self) -> u64 {
+        self.state.limit.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of frames queued by all players
+    #[must_use]
+    pub fn used(&self) -> u64 {
+        self.state.used.load(Ordering::Relaxed)
+    }
+
+    /// Take a share of the budget for one player
+    pub(crate) fn join(&self) -> FrameBudget {
+        self.state.players.fetch_add(1, Ordering::Relaxed);
+        FrameBudget {
+            share: Arc::new(Share {
+                budget: self.clone(),
+                used: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::global()
+    }
+}
+
+impl PartialEq for MemoryBudget {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("limit", &self.limit())
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+/// One player's share of a `MemoryBudget`, used by its decoder threads
+#[derive(Clone)]
+pub(crate) struct FrameBudget {
+    share: Arc<Share>,
+}
+
+struct Share {
+    budget: MemoryBudget,
+    used: AtomicU64,
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.budget.state.players.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl FrameBudget {
+    /// Bytes this player may queue
+    pub fn limit(&self) -> u64 {
+        let state = &self.share.budget.state;
+        state.limit.load(Ordering::Relaxed) / state.players.load(Ordering::Relaxed).max(1) as u64
+    }
+
+    /// Bytes of frames this player has queued
+    pub fn used(&self) -> u64 {
+        self.share.used.load(Ordering::Relaxed)
+    }
+
+    /// Wait until `bytes` more fit in the share, then hold them until the
+    /// lease is dropped. Gives up, returning None, once `wanted` turns false.
+    pub fn acquire(&self, bytes: u64, wanted: impl Fn() -> bool) -> Option<FrameLease> {
+        loop {
+            if !wanted() {
+                return None;
+            }
+            let used = self.used();
+            if used == 0 || used + bytes <= self.limit() {
+                break;
+            }
+            thread::sleep(WAIT_POLL);
+        }
+        self.share.used.fetch_add(bytes, Ordering::Relaxed);
+        self.share
+            .budget
+            .state
+            .used
+            .fetch_add(bytes, Ordering::Relaxed);
+        Some(FrameLease {
+            share: self.share.clone(),
+            bytes,
+        })
+    }
+}
+
+/// Memory held by a queued frame, returned to the budget when it is dropped
+pub(crate) struct FrameLease {
+    share: Arc<Share>,
+    bytes: u64,
+}
+
+impl Drop for FrameLease {
+    fn drop(&mut self) {
+        self.share.used.fetch_sub(self.bytes, Ordering::Relaxed);
+        self.share
+            .budget
+            .state
+            .used
+            .fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
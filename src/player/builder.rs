@@ -0,0 +1,186 @@
+use egui::{ColorImage, Context, TextureFilter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::budget::MemoryBudget;
+use super::config::PlayerConfig;
+use super::decoder::{DecoderOptions, RecoveryPolicy};
+use super::output::AudioBackend;
+use super::playlist::{Playlist, RepeatMode};
+use super::settings::Settings;
+use super::video::SyncPolicy;
+use super::{DisplayMode, PlayerError, StopBehavior, VideoPlayer, Volume};
+
+/// Options for opening a `VideoPlayer`, from `VideoPlayer::builder`.
+/// Options not set keep their `PlayerConfig` defaults.
+#[must_use]
+pub struct VideoPlayerBuilder {
+    path: PathBuf,
+    config: PlayerConfig,
+    start_at: Option<Duration>,
+    volume: Option<Volume>,
+    muted: bool,
+    speed: Option<f32>,
+    loop_mode: Option<RepeatMode>,
+    autoplay: bool,
+}
+
+impl VideoPlayerBuilder {
+    pub(crate) fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            config: PlayerConfig::default(),
+            start_at: None,
+            volume: None,
+            muted: false,
+            speed: None,
+            loop_mode: None,
+            autoplay: false,
+        }
+    }
+
+    /// Replace all `PlayerConfig` options at once
+    pub fn config(mut self, config: PlayerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Decoder error recovery policy
+    pub fn recovery(mut self, recovery: RecoveryPolicy) -> Self {
+        self.config.recovery = recovery;
+        self
+    }
+
+    /// Filtergraphs and other decoder options
+    pub fn decoder(mut self, options: DecoderOptions) -> Self {
+        self.config.decoder = options;
+        self
+    }
+
+    /// Initial display mode
+    pub fn display_mode(mut self, mode: DisplayMode) -> Self {
+        self.config.display_mode = mode;
+        self
+    }
+
+    /// What `stop()` leaves on screen
+    pub fn stop_behavior(mut self, behavior: StopBehavior) -> Self {
+        self.config.stop_behavior = behavior;
+        self
+    }
+
+    /// Texture sampling when the frame is scaled
+    pub fn texture_filter(mut self, filter: TextureFilter) -> Self {
+        self.config.texture_filter = filter;
+        self
+    }
+
+    /// Frame drop/hold thresholds, drift resync and which clock video follows
+    pub fn sync(mut self, policy: SyncPolicy) -> Self {
+        self.config.sync = policy;
+        self
+    }
+
+    /// Memory shared with other players for queued frames
+    pub fn memory_budget(mut self, budget: MemoryBudget) -> Self {
+        self.config.memory_budget = budget;
+        self
+    }
+
+    /// Debug name of the video texture
+    pub fn texture_name(mut self, name: impl Into<String>) -> Self {
+        self.config.texture_name = Some(name.into());
+        self
+    }
+
+    /// Preferences applied on open, overriding the display mode
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.config.settings = Some(settings);
+        self
+    }
+
+    /// Image shown until the first frame is decoded when the media has no cover art
+    pub fn placeholder(mut self, image: ColorImage) -> Self {
+        self.config.placeholder = Some(image);
+        self
+    }
+
+    /// Library the audio is played through
+    pub fn audio_backend(mut self, backend: AudioBackend) -> Self {
+        self.config.audio_backend = backend;
+        self
+    }
+
+    /// Memory for recently shown frames (bytes, 0 disables)
+    pub fn frame_cache_size(mut self, bytes: usize) -> Self {
+        self.config.frame_cache_bytes = bytes;
+        self
+    }
+
+    /// Position to open at instead of the start
+    pub fn start_at(mut self, position: Duration) -> Self {
+        self.start_at = Some(position);
+        self
+    }
+
+    /// Initial volume, overriding the settings profile
+    pub fn volume(mut self, volume: Volume) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    /// Open with the audio muted
+    pub fn muted(mut self, muted: bool) -> Self {
+        self.muted = muted;
+        self
+    }
+
+    /// Initial playback speed, overriding the settings profile
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+
+    /// Repeat mode of the playlist. A single file is put in a playlist of its
+    /// own, so `RepeatMode::One` or `All` loops it.
+    pub fn loop_mode(mut self, repeat: RepeatMode) -> Self {
+        self.loop_mode = Some(repeat);
+        self
+    }
+
+    /// Start playing once opened instead of waiting for `play()`
+    pub fn autoplay(mut self, autoplay: bool) -> Self {
+        self.autoplay = autoplay;
+        self
+    }
+
+    /// Open the file with these options
+    pub fn build(self, ctx: Context) -> Result<VideoPlayer, PlayerError> {
+        let mut player = VideoPlayer::open_configured(&self.path, ctx, self.config)?;
+        if let Some(volume) = self.volume {
+            player.set_volume(volume);
+        }
+        if self.muted {
+            player.set_muted(true);
+        }
+        if let Some(speed) = self.speed {
+            player.set_speed(speed);
+        }
+        if let Some(repeat) = self.loop_mode {
+            if player.playlist().is_empty() {
+                let mut playlist = Playlist::new(vec![self.path]);
+                playlist.set_repeat(repeat);
+                player.set_playlist(playlist)?;
+            } else {
+                player.playlist_mut().set_repeat(repeat);
+            }
+        }
+        if let Some(position) = self.start_at {
+            player.seek(position);
+        }
+        if self.autoplay {
+            player.play();
+        }
+        Ok(player)
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use crossbeam_channel::Sender;
+use ffmpeg_next::frame::Video as VideoFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::{codec, Packet};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use super::decoder::stream_rotation;
+use super::events::PlayerEvent;
+use super::{sequence, snapshot, thumbnail};
+
+/// Start a background thread that saves `count` frames around `center`,
+/// `step` frames apart, as PNG files in `directory`. The result is reported
+/// as a `PlayerEvent`.
+pub fn start_burst_thread(
+    source: PathBuf,
+    directory: PathBuf,
+    center: Duration,
+    count: usize,
+    step: usize,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || {
+        let event = match capture_burst(&source, &directory, center, count, step) {
+            Ok(paths) => PlayerEvent::BurstFinished(paths),
+            Err(e) => PlayerEvent::BurstFailed(format!("{:#}", e)),
+        };
+        let _ = event_sender.try_send(event);
+    });
+}
+
+fn capture_burst(
+    source: &Path,
+    directory: &Path,
+    center: Duration,
+    count: usize,
+    step: usize,
+) -> Result<Vec<PathBuf>> {
+    let step = step.max(1);
+    let mut input = sequence::open_input(source, 0.0)
+        .with_context(|| format!("Failed to open {}", source.display()))?;
+    let stream = input
+        .streams()
+        .best(Type::Video)
+        .context("No video stream found")?;
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+    let rotation = stream_rotation(&stream);
+    let rate = stream.avg_frame_rate();
+    let frame_interval = if rate.numerator() > 0 && rate.denominator() > 0 {
+        1.0 / f64::from(rate)
+    } else {
+        1.0 / 30.0
+    };
+    let mut decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()?;
+
+    // Half the burst comes before the current frame
+    let span = (count / 2 * step) as f64 * frame_interval;
+    let start = (center.as_secs_f64() - span).max(0.0);
+    let start_ts = (start * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+    if start_ts > 0 {
+        let _ = input.seek(start_ts, ..start_ts);
+    }
+
+    let stem = source
+        .file_stem()
+        .map_or_else(|| "frame".into(), |stem| stem.to_string_lossy());
+    let mut saved = Vec::with_capacity(count);
+    let mut packet = Packet::empty();
+    let mut decoded = VideoFrame::empty();
+    // Frames since the first one of the burst, to take every `step`th
+    let mut index = 0;
+    let mut at_eof = false;
+    while saved.len() < count && !at_eof {
+        match packet.read(&mut input) {
+            Ok(()) => {
+                if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+                    continue;
+                }
+            }
+            Err(ffmpeg_next::Error::Eof) => {
+                at_eof = true;
+                let _ = decoder.send_eof();
+            }
+            Err(e) => return Err(e).context("Failed to read packet"),
+        }
+
+        while saved.len() < count && decoder.receive_frame(&mut decoded).is_ok() {
+            let pts = decoded.timestamp().unwrap_or(0) as f64 * time_base;
+            // Decoding starts at the keyframe before the burst
+            if pts <= start - frame_interval {
+                continue;
+            }
+            if index % step == 0 {
+                let image = thumbnail::to_image(
+                    &decoded,
+                    [decoded.width(), decoded.height()],
+                    rotation,
+                    source,
+                )?;
+                let path =
+                    directory.join(format!("{}_{:08}.png", stem, (pts * 1000.0).round() as u64));
+                snapshot::save_png(&image, &path)?;
+                saved.push(path);
+            }
+            index += 1;
+        }
+    }
+    Ok(saved)
+}
@@ -0,0 +1,392 @@
+use ffmpeg_next::ffi;
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::util::interrupt;
+use std::collections::HashMap;
+use std::ffi::{c_void, CString};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use super::sequence;
+
+/// Unit the cache stores and evicts
+const BLOCK_SIZE: u64 = 64 * 1024;
+/// Buffer between FFmpeg's demuxer and the cache
+const IO_BUFFER_SIZE: usize = 32 * 1024;
+
+const SEEK_SET: c_int = 0;
+const SEEK_CUR: c_int = 1;
+const SEEK_END: c_int = 2;
+
+/// Disk cache for http(s) files, so seeking back does not download again.
+/// Live streams and servers without range requests are read directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkCache {
+    /// Bytes kept in the cache file; the oldest blocks are overwritten first.
+    /// 0 disables the cache.
+    pub size: u64,
+    /// Bytes downloaded in one go when a read misses the cache
+    pub readahead: u64,
+}
+
+impl Default for NetworkCache {
+    fn default() -> Self {
+        Self {
+            size: 128 * 1024 * 1024,
+            readahead: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// Network cache counters, in `PlaybackStats::cache`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads served from the cache file
+    pub hits: u64,
+    /// Reads that had to download
+    pub misses: u64,
+    /// Bytes downloaded into the cache
+    pub bytes_fetched: u64,
+}
+
+/// Counters shared between the cache on the decoder thread and the player
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bytes_fetched: AtomicU64,
+}
+
+impl CacheCounters {
+    pub fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bytes_fetched: self.bytes_fetched.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub type SharedCacheCounters = Arc<CacheCounters>;
+
+/// Demuxer input, possibly reading through the network cache.
+/// Dereferences to the FFmpeg input.
+pub struct MediaInput {
+    // Dropped before the cache it reads from
+    input: Input,
+    _cache: Option<CacheIo>,
+}
+
+impl Deref for MediaInput {
+    type Target = Input;
+
+    fn deref(&self) -> &Input {
+        &self.input
+    }
+}
+
+impl DerefMut for MediaInput {
+    fn deref_mut(&mut self) -> &mut Input {
+        &mut self.input
+    }
+}
+
+/// Open media for playback. Seekable http(s) files of known size are read
+/// through the cache; everything else opens as usual. Once `interrupt`
+/// returns true, a slow open or read gives up.
+pub fn open_media(
+    path: &Path,
+    image_frame_rate: f64,
+    cache: NetworkCache,
+    counters: &SharedCacheCounters,
+    interrupt: impl Fn() -> bool + Clone + Send + 'static,
+) -> Result<MediaInput, ffmpeg_next::Error> {
+    let url = path.to_str().filter(|url| {
+        cache.size > 0 && (url.starts_with("http://") || url.starts_with("https://"))
+    });
+    if let Some(url) = url {
+        let callback = interrupt::new(Box::new(interrupt.clone())).interrupt;
+        if let Some(cached) = open_cached(url, cache, counters.clone(), &callback)? {
+            return Ok(cached);
+        }
+    }
+    Ok(MediaInput {
+        input: sequence::open_input_interruptible(path, image_frame_rate, interrupt)?,
+        _cache: None,
+    })
+}
+
+/// Open `url` with the cache in front, or None if the source cannot be cached.
+/// `interrupt` abandons the open, and later reads, when it returns true.
+fn open_cached(
+    url: &str,
+    cache: NetworkCache,
+    counters: SharedCacheCounters,
+    interrupt: &ffi::AVIOInterruptCB,
+) -> Result<Option<MediaInput>, ffmpeg_next::Error> {
+    let c_url = CString::new(url).map_err(|_| ffmpeg_next::Error::InvalidData)?;
+    unsafe {
+        let mut source = ptr::null_mut();
+        let ret = ffi::avio_open2(
+            &mut source,
+            c_url.as_ptr(),
+            ffi::AVIO_FLAG_READ as c_int,
+            interrupt,
+            ptr::null_mut(),
+        );
+        if ret < 0 {
+            return Err(ffmpeg_next::Error::from(ret));
+        }
+        let size = ffi::avio_size(source);
+        if size <= 0 || (*source).seekable == 0 {
+            ffi::avio_closep(&mut source);
+            return Ok(None);
+        }
+        let Ok(cache) = BlockCache::new(source, size as u64, cache, counters) else {
+            ffi::avio_closep(&mut source);
+            return Ok(None);
+        };
+
+        let io = CacheIo::new(Box::new(cache));
+        let mut context = ffi::avformat_alloc_context();
+        (*context).pb = io.context;
+        (*context).flags |= ffi::AVFMT_FLAG_CUSTOM_IO as c_int;
+        (*context).interrupt_callback = *interrupt;
+        // On failure FFmpeg frees the format context, but not our I/O context
+        let ret =
+            ffi::avformat_open_input(&mut context, c_url.as_ptr(), ptr::null(), ptr::null_mut());
+        if ret < 0 {
+            return Err(ffmpeg_next::Error::from(ret));
+        }
+        let ret = ffi::avformat_find_stream_info(context, ptr::null_mut());
+        if ret < 0 {
+            ffi::avformat_close_input(&mut context);
+            return Err(ffmpeg_next::Error::from(ret));
+        }
+        Ok(Some(MediaInput {
+            input: Input::wrap(context),
+            _cache: Some(io),
+        }))
+    }
+}
+
+/// Custom FFmpeg I/O context reading through a `BlockCache`
+struct CacheIo {
+    context: *mut ffi::AVIOContext,
+    cache: *mut BlockCache,
+}
+
+impl CacheIo {
+    unsafe fn new(cache: Box<BlockCache>) -> Self {
+        let cache = Box::into_raw(cache);
+        let buffer = ffi::av_malloc(IO_BUFFER_SIZE) as *mut u8;
+        let context = ffi::avio_alloc_context(
+            buffer,
+            IO_BUFFER_SIZE as c_int,
+            0,
+            cache as *mut c_void,
+            Some(read_packet),
+            None,
+            Some(seek),
+        );
+        Self { context, cache }
+    }
+}
+
+impl Drop for CacheIo {
+    fn drop(&mut self) {
+        unsafe {
+            // FFmpeg may have replaced the buffer, so free the current one
+            ffi::av_freep(&mut (*self.context).buffer as *mut *mut u8 as *mut c_void);
+            ffi::avio_context_free(&mut self.context);
+            // Close the cache file before deleting it
+            let cache = Box::from_raw(self.cache);
+            let file_path = cache.file_path.clone();
+            drop(cache);
+            let _ = std::fs::remove_file(file_path);
+        }
+    }
+}
+
+unsafe extern "C" fn read_packet(opaque: *mut c_void, buf: *mut u8, buf_size: c_int) -> c_int {
+    let cache = &mut *(opaque as *mut BlockCache);
+    let buf = std::slice::from_raw_parts_mut(buf, buf_size.max(0) as usize);
+    match cache.read(buf) {
+        Ok(0) => ffi::AVERROR_EOF,
+        Ok(read) => read as c_int,
+        Err(code) => code,
+    }
+}
+
+unsafe extern "C" fn seek(opaque: *mut c_void, offset: i64, whence: c_int) -> i64 {
+    let cache = &mut *(opaque as *mut BlockCache);
+    let whence = whence & !(ffi::AVSEEK_FORCE as c_int);
+    let position = match whence {
+        w if w == ffi::AVSEEK_SIZE as c_int => return cache.size as i64,
+        SEEK_SET => offset,
+        SEEK_CUR => cache.position as i64 + offset,
+        SEEK_END => cache.size as i64 + offset,
+        _ => return -1,
+    };
+    if position < 0 {
+        return -1;
+    }
+    cache.position = position as u64;
+    position
+}
+
+/// Blocks of a remote file kept in a ring-buffered temporary file
+struct BlockCache {
+    source: *mut ffi::AVIOContext,
+    /// Read position of `source`, to skip needless seeks while reading forward
+    source_position: u64,
+    size: u64,
+    position: u64,
+    file: File,
+    file_path: PathBuf,
+    /// Block index to ring slot and length
+    blocks: HashMap<u64, (u64, usize)>,
+    /// Block held by each ring slot
+    slots: Vec<Option<u64>>,
+    next_slot: usize,
+    readahead_blocks: u64,
+    scratch: Vec<u8>,
+    counters: SharedCacheCounters,
+}
+
+impl BlockCache {
+    fn new(
+        source: *mut ffi::AVIOContext,
+        size: u64,
+        options: NetworkCache,
+        counters: SharedCacheCounters,
+    ) -> std::io::Result<Self> {
+        static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+        let file_path = std::env::temp_dir().join(format!(
+            "egui_video_cache_{}_{}",
+            std::process::id(),
+            NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&file_path)?;
+
+        let readahead_blocks = options.readahead.div_ceil(BLOCK_SIZE).max(1);
+        // Room for at least one readahead, so a fetch never evicts its own blocks
+        let slots = (options.size / BLOCK_SIZE).max(readahead_blocks + 1);
+        Ok(Self {
+            source,
+            source_position: 0,
+            size,
+            position: 0,
+            file,
+            file_path,
+            blocks: HashMap::new(),
+            slots: vec![None; slots as usize],
+            next_slot: 0,
+            readahead_blocks,
+            scratch: vec![0; BLOCK_SIZE as usize],
+            counters,
+        })
+    }
+
+    /// Read from the current position, downloading the block on a miss.
+    /// Errors are FFmpeg error codes.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, c_int> {
+        if self.position >= self.size || buf.is_empty() {
+            return Ok(0);
+        }
+        let block = self.position / BLOCK_SIZE;
+        if self.blocks.contains_key(&block) {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            self.fetch(block)?;
+        }
+
+        let (slot, len) = self.blocks[&block];
+        let offset = self.position % BLOCK_SIZE;
+        let count = buf.len().min(len.saturating_sub(offset as usize));
+        if count == 0 {
+            return Ok(0);
+        }
+        self.file
+            .seek(SeekFrom::Start(slot * BLOCK_SIZE + offset))
+            .and_then(|_| self.file.read_exact(&mut buf[..count]))
+            .map_err(|_| ffi::AVERROR_EXTERNAL)?;
+        self.position += count as u64;
+        Ok(count)
+    }
+
+    /// Download `block` and the readahead after it, stopping at cached blocks
+    fn fetch(&mut self, block: u64) -> Result<(), c_int> {
+        let blocks = self.size.div_ceil(BLOCK_SIZE);
+        let end = (block + self.readahead_blocks).min(blocks);
+        for index in block..end {
+            if index > block && self.blocks.contains_key(&index) {
+                break;
+            }
+            let start = index * BLOCK_SIZE;
+            if self.source_position != start {
+                let ret = unsafe { ffi::avio_seek(self.source, start as i64, SEEK_SET) };
+                if ret < 0 {
+                    return Err(ret as c_int);
+                }
+                self.source_position = start;
+            }
+
+            let mut len = 0;
+            while len < self.scratch.len() {
+                let ret = unsafe {
+                    ffi::avio_read(
+                        self.source,
+                        self.scratch[len..].as_mut_ptr(),
+                        (self.scratch.len() - len) as c_int,
+                    )
+                };
+                if ret == ffi::AVERROR_EOF || ret == 0 {
+                    break;
+                }
+                if ret < 0 {
+                    return Err(ret);
+                }
+                len += ret as usize;
+            }
+            self.source_position += len as u64;
+            self.counters
+                .bytes_fetched
+                .fetch_add(len as u64, Ordering::Relaxed);
+            self.store(index, len).map_err(|_| ffi::AVERROR_EXTERNAL)?;
+            if len < self.scratch.len() {
+                break; // End of the file
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the downloaded block into the next ring slot, evicting its old block
+    fn store(&mut self, block: u64, len: usize) -> std::io::Result<()> {
+        let slot = self.next_slot;
+        self.next_slot = (self.next_slot + 1) % self.slots.len();
+        if let Some(evicted) = self.slots[slot].replace(block) {
+            self.blocks.remove(&evicted);
+        }
+        self.file.seek(SeekFrom::Start(slot as u64 * BLOCK_SIZE))?;
+        self.file.write_all(&self.scratch[..len])?;
+        self.blocks.insert(block, (slot as u64, len));
+        Ok(())
+    }
+}
+
+impl Drop for BlockCache {
+    fn drop(&mut self) {
+        unsafe { ffi::avio_closep(&mut self.source) };
+    }
+}
@@ -0,0 +1,60 @@
+use ffmpeg_next::format::context::Input;
+use std::time::Duration;
+
+/// A named part of the media: a chapter of the container, or a track found by
+/// `VideoPlayer::split_tracks` in files without chapters
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start: Duration,
+    pub end: Duration,
+}
+
+/// Collect the chapters of an input in order, titled "Chapter N" when untitled
+pub fn read_chapters(input: &Input) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = input
+        .chapters()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let time_base = f64::from(chapter.time_base());
+            let time = |ts: i64| Duration::from_secs_f64((ts as f64 * time_base).max(0.0));
+            Chapter {
+                title: chapter
+                    .metadata()
+                    .get("title")
+                    .filter(|title| !title.is_empty())
+                    .map_or_else(|| format!("Chapter {}", i + 1), str::to_owned),
+                start: time(chapter.start()),
+                end: time(chapter.end()),
+            }
+        })
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Tracks between the given split points, up to `duration`. Splits too close
+/// to the end, such as the middle of trailing silence, are left out.
+pub fn tracks(splits: &[Duration], duration: Duration, min_length: Duration) -> Vec<Chapter> {
+    let mut starts = vec![Duration::ZERO];
+    starts.extend(
+        splits
+            .iter()
+            .copied()
+            .filter(|&split| split + min_length <= duration),
+    );
+    if starts.len() < 2 {
+        return Vec::new();
+    }
+    let ends = starts.iter().skip(1).copied().chain([duration]);
+    starts
+        .iter()
+        .zip(ends)
+        .enumerate()
+        .map(|(i, (&start, end))| Chapter {
+            title: format!("Track {}", i + 1),
+            start,
+            end,
+        })
+        .collect()
+}
@@ -1,16 +1,37 @@
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Audio clock for A/V synchronization.
-/// Uses audio playback position as the master clock.
+/// Uses audio playback position as the master clock. The output advances it
+/// once per block of samples; readers interpolate with wall time in between.
+/// Media without audio runs it on wall time alone, scaled by the playback speed,
+/// as does video master sync.
 #[derive(Clone)]
 pub struct AudioClock {
-    /// Current playback position in microseconds
+    /// Playback position at the last update in microseconds
     position_us: Arc<AtomicU64>,
+    /// Time of the last update, in microseconds since `epoch`
+    updated_us: Arc<AtomicU64>,
+    /// Length of the last advance in microseconds; interpolation never runs past it,
+    /// so the position stays monotonic and stops during underruns
+    last_advance_us: Arc<AtomicU64>,
+    epoch: Instant,
     /// Whether playback is paused
     paused: Arc<AtomicBool>,
     /// Flag to clear audio buffer (set on seek)
     clear_buffer: Arc<AtomicBool>,
+    /// Delay between samples leaving the buffer and being heard, in microseconds
+    output_latency_us: Arc<AtomicI64>,
+    /// Advance with wall time instead of played samples, for media without audio
+    wall_clock: Arc<AtomicBool>,
+    /// Advance with wall time even though audio plays, so video is the master
+    video_master: Arc<AtomicBool>,
+    /// Playback speed applied to wall time, as f32 bits
+    rate: Arc<AtomicU32>,
+    /// Blocks of samples the output has asked for, whether or not audio was queued.
+    /// Stops counting when the device stops playing.
+    pulls: Arc<AtomicU64>,
     /// Sample rate of audio stream
     sample_rate: u32,
     /// Number of audio channels
@@ -21,8 +42,16 @@ impl AudioClock {
     pub fn new(sample_rate: u32, channels: u16) -> Self {
         Self {
             position_us: Arc::new(AtomicU64::new(0)),
+            updated_us: Arc::new(AtomicU64::new(0)),
+            last_advance_us: Arc::new(AtomicU64::new(0)),
+            epoch: Instant::now(),
             paused: Arc::new(AtomicBool::new(true)),
             clear_buffer: Arc::new(AtomicBool::new(false)),
+            output_latency_us: Arc::new(AtomicI64::new(0)),
+            wall_clock: Arc::new(AtomicBool::new(false)),
+            video_master: Arc::new(AtomicBool::new(false)),
+            rate: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            pulls: Arc::new(AtomicU64::new(0)),
             sample_rate,
             channels,
         }
@@ -30,15 +59,62 @@ impl AudioClock {
 
     /// Get current playback position in seconds
     pub fn position(&self) -> f64 {
-        self.position_us.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        let mut us = self.position_us.load(Ordering::Relaxed);
+        if !self.paused.load(Ordering::Relaxed) {
+            let since_update = self
+                .now_us()
+                .saturating_sub(self.updated_us.load(Ordering::Relaxed));
+            if self.on_wall_time() {
+                let rate = f32::from_bits(self.rate.load(Ordering::Relaxed));
+                us += (since_update as f64 * f64::from(rate)) as u64;
+            } else {
+                us += since_update.min(self.last_advance_us.load(Ordering::Relaxed));
+            }
+        }
+        us as f64 / 1_000_000.0
+    }
+
+    fn on_wall_time(&self) -> bool {
+        self.wall_clock.load(Ordering::Relaxed) || self.video_master.load(Ordering::Relaxed)
+    }
+
+    fn now_us(&self) -> u64 {
+        self.epoch.elapsed().as_micros() as u64
+    }
+
+    /// Restart interpolation from the stored position
+    fn mark_updated(&self, advance_us: u64) {
+        self.updated_us.store(self.now_us(), Ordering::Relaxed);
+        self.last_advance_us.store(advance_us, Ordering::Relaxed);
+    }
+
+    /// Position currently being heard, in seconds. Video is synced to this,
+    /// lagging the decoded position by the output latency.
+    pub fn presentation_position(&self) -> f64 {
+        let latency = self.output_latency_us.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        (self.position() - latency).max(0.0)
+    }
+
+    /// Set the audio output latency in milliseconds; positive values delay the video
+    pub fn set_output_latency(&self, ms: i64) {
+        self.output_latency_us
+            .store(ms.saturating_mul(1000), Ordering::Relaxed);
     }
 
     /// Set playback position in seconds (used during seek)
-    /// Also sets the clear_buffer flag to discard old audio samples
+    /// Also sets the clear_buffer flag to discard the block being played
     pub fn set_position(&self, seconds: f64) {
+        tracing::debug!(seconds, "clock set");
+        self.sync_position(seconds);
+        self.clear_buffer.store(true, Ordering::Relaxed);
+    }
+
+    /// Set the position without touching the audio, e.g. to the PTS of the frame
+    /// a seek landed on, where the queued audio already starts
+    pub fn sync_position(&self, seconds: f64) {
         let us = (seconds * 1_000_000.0) as u64;
         self.position_us.store(us, Ordering::Relaxed);
-        self.clear_buffer.store(true, Ordering::Relaxed);
+        self.mark_updated(0);
     }
 
     /// Check and clear the buffer clear flag (returns true if buffer should be cleared)
@@ -46,28 +122,72 @@ impl AudioClock {
         self.clear_buffer.swap(false, Ordering::Relaxed)
     }
 
-    /// Advance clock by given number of samples consumed
+    /// Advance clock by given number of samples consumed. Ignored on wall time.
     pub fn advance_samples(&self, samples: u64) {
-        if !self.paused.load(Ordering::Relaxed) {
+        if !self.paused.load(Ordering::Relaxed) && !self.on_wall_time() {
             let us_per_sample = 1_000_000.0 / (self.sample_rate as f64 * self.channels as f64);
             let delta_us = (samples as f64 * us_per_sample) as u64;
             self.position_us.fetch_add(delta_us, Ordering::Relaxed);
+            self.mark_updated(delta_us);
         }
     }
 
-    #[allow(dead_code)]
+    /// Count a block of samples taken by the output
+    pub fn mark_pulled(&self) {
+        self.pulls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Blocks of samples taken by the output so far
+    pub fn pulls(&self) -> u64 {
+        self.pulls.load(Ordering::Relaxed)
+    }
+
     pub fn is_paused(&self) -> bool {
         self.paused.load(Ordering::Relaxed)
     }
 
     pub fn pause(&self) {
+        self.settle();
         self.paused.store(true, Ordering::Relaxed);
     }
 
     pub fn resume(&self) {
+        // Interpolate from the first block played after resuming
+        self.mark_updated(0);
         self.paused.store(false, Ordering::Relaxed);
     }
 
+    /// Run on wall time, as no samples will advance the clock
+    pub fn set_wall_clock(&self, enabled: bool) {
+        tracing::debug!(enabled, "wall clock");
+        self.settle();
+        self.wall_clock.store(enabled, Ordering::Relaxed);
+        self.mark_updated(0);
+    }
+
+    /// Run on wall time while audio plays, leaving it unsynced
+    pub fn set_video_master(&self, enabled: bool) {
+        tracing::debug!(enabled, "video master");
+        self.settle();
+        self.video_master.store(enabled, Ordering::Relaxed);
+        self.mark_updated(0);
+    }
+
+    /// Set the playback speed the wall clock runs at
+    pub fn set_rate(&self, rate: f32) {
+        self.settle();
+        self.rate.store(rate.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Store the wall-clock position reached so far, before its speed or state changes
+    fn settle(&self) {
+        if self.on_wall_time() && !self.paused.load(Ordering::Relaxed) {
+            self.position_us
+                .store((self.position() * 1_000_000.0) as u64, Ordering::Relaxed);
+            self.mark_updated(0);
+        }
+    }
+
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
@@ -0,0 +1,95 @@
+use ffmpeg_next::color::{Range, Space};
+use ffmpeg_next::ffi;
+use ffmpeg_next::software::scaling::Context as ScalerContext;
+
+/// YUV to RGB conversion matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMatrix {
+    /// SD content (ITU-R BT.601)
+    Bt601,
+    /// HD content (ITU-R BT.709)
+    Bt709,
+    /// UHD content (ITU-R BT.2020)
+    Bt2020,
+}
+
+/// Quantization range of the source YUV samples
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorRange {
+    /// 16-235 (TV / MPEG range)
+    Limited,
+    /// 0-255 (PC / JPEG range)
+    Full,
+}
+
+/// How the decoder picks the YUV to RGB conversion
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorManagement {
+    /// Use the colorspace and range signalled by the stream.
+    /// Unspecified streams are treated as BT.709 for HD and BT.601 for SD.
+    #[default]
+    Auto,
+    /// Ignore the stream and always use the given matrix and range
+    Override {
+        matrix: ColorMatrix,
+        range: ColorRange,
+    },
+}
+
+impl ColorManagement {
+    /// Resolve the matrix and range to use for a frame
+    pub(crate) fn resolve(
+        self,
+        space: Space,
+        range: Range,
+        height: u32,
+    ) -> (ColorMatrix, ColorRange) {
+        match self {
+            Self::Override { matrix, range } => (matrix, range),
+            Self::Auto => {
+                let matrix = match space {
+                    Space::BT709 => ColorMatrix::Bt709,
+                    Space::BT470BG | Space::SMPTE170M | Space::FCC => ColorMatrix::Bt601,
+                    Space::BT2020NCL | Space::BT2020CL => ColorMatrix::Bt2020,
+                    _ if height >= 720 => ColorMatrix::Bt709,
+                    _ => ColorMatrix::Bt601,
+                };
+                let range = match range {
+                    Range::JPEG => ColorRange::Full,
+                    _ => ColorRange::Limited,
+                };
+                (matrix, range)
+            }
+        }
+    }
+}
+
+/// Configure the scaler's YUV to RGB coefficients and input range.
+/// Output is always full-range RGB.
+pub fn apply_colorspace(scaler: &mut ScalerContext, matrix: ColorMatrix, range: ColorRange) {
+    let colorspace = match matrix {
+        ColorMatrix::Bt601 => ffi::SWS_CS_ITU601,
+        ColorMatrix::Bt709 => ffi::SWS_CS_ITU709,
+        ColorMatrix::Bt2020 => ffi::SWS_CS_BT2020,
+    };
+    let src_range = match range {
+        ColorRange::Limited => 0,
+        ColorRange::Full => 1,
+    };
+
+    // Safety: the scaler pointer is valid for the lifetime of `scaler` and
+    // sws_getCoefficients returns a pointer to a static table.
+    unsafe {
+        let coefficients = ffi::sws_getCoefficients(colorspace as i32);
+        ffi::sws_setColorspaceDetails(
+            scaler.as_mut_ptr(),
+            coefficients,
+            src_range,
+            coefficients,
+            1,       // Full-range RGB output
+            0,       // Brightness
+            1 << 16, // Contrast (16.16 fixed point)
+            1 << 16, // Saturation (16.16 fixed point)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use egui::{ColorImage, TextureFilter};
+
+use super::budget::MemoryBudget;
+use super::decoder::{DecoderOptions, RecoveryPolicy};
+use super::frame_cache::DEFAULT_FRAME_CACHE_BYTES;
+use super::output::AudioBackend;
+use super::settings::Settings;
+use super::video::SyncPolicy;
+use super::{DisplayMode, StopBehavior};
+
+/// Options applied when opening a `VideoPlayer`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerConfig {
+    /// Decoder error recovery policy
+    pub recovery: RecoveryPolicy,
+    /// Filtergraphs and other decoder options
+    pub decoder: DecoderOptions,
+    /// Initial display mode
+    pub display_mode: DisplayMode,
+    /// What `stop()` leaves on screen
+    pub stop_behavior: StopBehavior,
+    /// Texture sampling when the frame is scaled (use Nearest for pixel art)
+    pub texture_filter: TextureFilter,
+    /// Frame drop/hold thresholds, drift resync and which clock video follows
+    pub sync: SyncPolicy,
+    /// Memory shared with other players for queued frames (the global budget by default)
+    pub memory_budget: MemoryBudget,
+    /// Debug name of the video texture (a unique name is generated if None)
+    pub texture_name: Option<String>,
+    /// Preferences applied on open, overriding `display_mode` when set
+    pub settings: Option<Settings>,
+    /// Shown until the first frame is decoded when the media has no cover art
+    /// (black if None)
+    pub placeholder: Option<ColorImage>,
+    /// Library the audio is played through
+    pub audio_backend: AudioBackend,
+    /// Memory for recently shown frames, which stepping back over while
+    /// paused shows without decoding (bytes, 0 disables)
+    pub frame_cache_bytes: usize,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            recovery: RecoveryPolicy::default(),
+            decoder: DecoderOptions::default(),
+            display_mode: DisplayMode::FitToWindow,
+            stop_behavior: StopBehavior::default(),
+            texture_filter: TextureFilter::Linear,
+            sync: SyncPolicy::default(),
+            memory_budget: MemoryBudget::global(),
+            texture_name: None,
+            settings: None,
+            placeholder: None,
+            audio_backend: AudioBackend::default(),
+            frame_cache_bytes: DEFAULT_FRAME_CACHE_BYTES,
+        }
+    }
+}
@@ -1,25 +1,41 @@
-use anyhow::{anyhow, Context, Result};
-use crossbeam_channel::{Receiver, Sender, TryRecvError, TrySendError};
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{bounded, select, Receiver, Sender, TryRecvError, TrySendError};
 use egui::Color32;
+use ffmpeg_next::codec::packet::side_data;
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::format::stream::Stream;
 use ffmpeg_next::format::Pixel;
-use ffmpeg_next::frame::{Audio as AudioFrame, Video as VideoFrame};
+use ffmpeg_next::frame::Video as VideoFrame;
 use ffmpeg_next::media::Type;
-use ffmpeg_next::software::resampling::Context as ResamplerContext;
-use ffmpeg_next::software::scaling::{Context as ScalerContext, Flags};
-use ffmpeg_next::util::channel_layout::ChannelLayout;
-use ffmpeg_next::util::format::sample::Sample;
-use ffmpeg_next::{codec, Packet, Rational};
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use ffmpeg_next::{codec, Discard, Packet, Rational};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
-use super::circular_buffer::CircularBuffer;
+use super::attachments::{read_attachments, Attachment};
+use super::budget::{FrameBudget, FrameLease};
+use super::cache::{open_media, MediaInput, NetworkCache, SharedCacheCounters};
+use super::chapters::{read_chapters, Chapter};
 use super::clock::AudioClock;
-
-// Compile-time verification that Color32 can be safely transmuted from [u8; 4]
-const _: () = assert!(std::mem::size_of::<Color32>() == 4);
-const _: () = assert!(std::mem::align_of::<Color32>() == 1);
+use super::color::ColorManagement;
+use super::effect::SharedVideoEffects;
+use super::error::PlayerError;
+use super::events::PlayerEvent;
+use super::frame_metadata::FrameMetadata;
+use super::record::Recorder;
+use super::ring_buffer::AudioProducer;
+use super::sequence;
+use super::settings::language_matches;
+use super::streams::{read_streams, StreamInfo};
+use super::tap::SharedAudioTap;
+use super::telemetry::{parse_klv_packet, TelemetrySample};
+use super::video::{SeekMode, DEFAULT_FRAME_INTERVAL};
+use super::worker::{
+    AudioWorker, DecodeSync, VideoWorker, WorkerMessage, AUDIO_PACKET_QUEUE, VIDEO_PACKET_QUEUE,
+};
+use crate::diagnostics::{Resource, ResourceGuard};
 
 /// A decoded video frame ready for display
 pub struct DecodedVideoFrame {
@@ -27,14 +43,143 @@ pub struct DecodedVideoFrame {
     pub width: u32,
     pub height: u32,
     pub pts: f64, // seconds
+    /// Source pixels carry transparency (pixels are premultiplied)
+    pub has_alpha: bool,
+    /// Threads the video codec decodes with
+    pub decoder_threads: usize,
+    /// Seek generation the frame was decoded in
+    pub(crate) generation: u64,
+    /// Timecode, HDR metadata and telemetry carried with the frame
+    pub side_data: FrameMetadata,
+    /// Share of the memory budget held while the frame is queued
+    pub(crate) lease: Option<FrameLease>,
+}
+
+impl DecodedVideoFrame {
+    /// Convert a scaled RGBA frame, turning it upright by `rotation` degrees clockwise
+    pub(crate) fn new(
+        rgba_frame: &VideoFrame,
+        alpha: bool,
+        rotation: u32,
+        pts: f64,
+        decoder_threads: usize,
+        generation: u64,
+    ) -> Self {
+        let (pixels, [width, height]) = rotate_pixels(
+            frame_pixels(rgba_frame, alpha),
+            [rgba_frame.width(), rgba_frame.height()],
+            rotation,
+        );
+        Self {
+            pixels,
+            width,
+            height,
+            pts,
+            has_alpha: alpha,
+            decoder_threads,
+            generation,
+            side_data: FrameMetadata::default(),
+            lease: None,
+        }
+    }
 }
 
 /// Commands sent to the decoder thread
 pub enum DecoderCommand {
-    Seek(f64),
+    /// Position in seconds, precision, and the generation to tag the new frames with
+    Seek(f64, SeekMode, u64),
     Pause,
     Resume,
+    /// Decode ahead until the queues are full, leaving the clock stopped
+    Prebuffer,
     Stop,
+    SetColorManagement(ColorManagement),
+    /// Shift the audio pitch by this many semitones
+    SetPitch(f32),
+    /// Decode video (true) or discard video packets for audio-only playback
+    SetVideoEnabled(bool),
+    /// Switch to another file, keeping the thread and channels
+    Open(PathBuf, DecoderOptions),
+    /// Start copying the input's packets into a file
+    StartRecording(PathBuf),
+    /// Finish the recording started with `StartRecording`
+    StopRecording,
+}
+
+/// Number of `Open` commands the player has sent, raised just before each.
+/// An open that a newer request has superseded is abandoned midway, even
+/// while FFmpeg waits on a slow network source, so only the latest wins.
+#[derive(Clone, Default)]
+pub struct OpenRequests(Arc<AtomicU64>);
+
+impl OpenRequests {
+    /// Note a request; call before sending its `DecoderCommand::Open`.
+    /// Returns its number, which tags the `MediaInfo` reported once it is open.
+    pub fn add(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Release) + 1
+    }
+
+    fn count(&self) -> u64 {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// The open the decoder thread is working on: the media it started with, or
+/// the `Open` command with this number. Reads as cancelled once the player
+/// asked for something newer or stopped the decoder.
+#[derive(Clone)]
+struct OpenTicket {
+    requests: OpenRequests,
+    number: u64,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl OpenTicket {
+    fn is_cancelled(&self) -> bool {
+        self.stop_flag.load(Ordering::Relaxed) || self.requests.count() > self.number
+    }
+
+    /// FFmpeg interrupt callback, which also ends blocking reads later on
+    fn interrupt(&self) -> impl Fn() -> bool + Clone + Send + 'static {
+        let ticket = self.clone();
+        move || ticket.is_cancelled()
+    }
+}
+
+/// Policy for recovering from decoder errors.
+/// Transient errors are retried with exponential backoff before the decoder gives up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecoveryPolicy {
+    /// Consecutive read errors tolerated before the decoder stops
+    pub max_retries: u32,
+    /// Delay before the first retry (doubled on each further attempt)
+    pub initial_backoff: Duration,
+    /// Upper bound for the retry delay
+    pub max_backoff: Duration,
+    /// Reopen network sources after a read failure and resume at the last position
+    pub auto_reconnect: bool,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            auto_reconnect: true,
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    /// Delay before the given retry attempt (1-based)
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
 }
 
 /// Media info extracted from the file
@@ -42,33 +187,66 @@ pub struct MediaInfo {
     pub width: u32,
     pub height: u32,
     pub duration: f64,
-    pub sample_rate: u32,
-    pub channels: u16,
+    pub frame_rate: f64,
+    /// A single image, shown as a paused one-frame video
+    pub still_image: bool,
+    /// Fonts, cover art and other files embedded in the container
+    pub attachments: Vec<Attachment>,
+    /// False for pipes, which play from start to end only
+    pub seekable: bool,
+    /// Title tag of the container
+    pub title: Option<String>,
+    /// Codec details of every stream
+    pub streams: Vec<StreamInfo>,
+    /// Overall bits per second, if the container states it
+    pub bit_rate: Option<u64>,
+    /// Chapters of the container in order
+    pub chapters: Vec<Chapter>,
 }
 
-/// Open a media file and extract info without starting decoding
-pub fn probe_media(path: &Path) -> Result<MediaInfo> {
-    let input = ffmpeg_next::format::input(path).context("Failed to open input file")?;
+/// Open a media file and extract info without starting decoding.
+/// Pipes are not opened, as that would consume their data; their size and
+/// length are unknown until the decoder reads them.
+pub fn probe_media(path: &Path, options: &DecoderOptions) -> Result<MediaInfo> {
+    if is_pipe(path) {
+        return Ok(MediaInfo {
+            width: 0,
+            height: 0,
+            duration: 0.0,
+            frame_rate: 0.0,
+            still_image: false,
+            attachments: Vec::new(),
+            seekable: false,
+            title: None,
+            streams: Vec::new(),
+            bit_rate: None,
+            chapters: Vec::new(),
+        });
+    }
+
+    let input = sequence::open_input(path, options.image_frame_rate)
+        .map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+    media_info(&input, path)
+}
 
+/// Read the info of an opened input
+fn media_info(input: &Input, path: &Path) -> Result<MediaInfo> {
     let video_stream = input
         .streams()
         .best(Type::Video)
-        .ok_or_else(|| anyhow!("No video stream found"))?;
+        .ok_or(PlayerError::NoVideoStream)?;
 
     let video_decoder = codec::Context::from_parameters(video_stream.parameters())?
         .decoder()
         .video()?;
 
-    let audio_stream = input.streams().best(Type::Audio);
+    let frame_rate = stream_frame_rate(&video_stream);
 
-    let (sample_rate, channels) = if let Some(audio) = audio_stream {
-        let audio_decoder = codec::Context::from_parameters(audio.parameters())?
-            .decoder()
-            .audio()?;
-        (audio_decoder.rate(), audio_decoder.channels() as u16)
-    } else {
-        (44100, 2) // Default if no audio
-    };
+    // Portrait phone video is stored sideways and displayed rotated
+    let (mut width, mut height) = (video_decoder.width(), video_decoder.height());
+    if stream_rotation(&video_stream) % 180 == 90 {
+        std::mem::swap(&mut width, &mut height);
+    }
 
     let duration = if input.duration() > 0 {
         input.duration() as f64 / ffmpeg_next::ffi::AV_TIME_BASE as f64
@@ -77,308 +255,1023 @@ pub fn probe_media(path: &Path) -> Result<MediaInfo> {
     };
 
     Ok(MediaInfo {
-        width: video_decoder.width(),
-        height: video_decoder.height(),
+        width,
+        height,
         duration,
-        sample_rate,
-        channels,
+        frame_rate,
+        still_image: sequence::is_image_file(path),
+        attachments: read_attachments(input),
+        seekable: !is_pipe(path),
+        title: input
+            .metadata()
+            .get("title")
+            .map(str::to_owned)
+            .filter(|title| !title.is_empty()),
+        streams: read_streams(input),
+        bit_rate: u64::try_from(input.bit_rate())
+            .ok()
+            .filter(|&rate| rate > 0),
+        chapters: read_chapters(input),
     })
 }
 
+/// Whether frames in this pixel format carry an alpha channel
+/// (four components like YUVA or RGBA, or gray with alpha)
+pub(crate) fn has_alpha(format: Pixel) -> bool {
+    format
+        .descriptor()
+        .is_some_and(|d| matches!(d.nb_components(), 2 | 4))
+}
+
+/// Copy an RGBA frame into the pixel buffer handed to the texture.
+/// This is the only copy between FFmpeg and the GPU: egui backends upload
+/// `Color32` images as bytes without converting them.
+/// Transparent frames are premultiplied, as `Color32` expects.
+pub(crate) fn frame_pixels(rgba_frame: &VideoFrame, alpha: bool) -> Vec<Color32> {
+    plane_pixels(
+        rgba_frame.data(0),
+        rgba_frame.stride(0),
+        [rgba_frame.width() as usize, rgba_frame.height() as usize],
+        alpha,
+    )
+}
+
+/// Copy an RGBA plane whose rows start every `stride` bytes
+fn plane_pixels(
+    data: &[u8],
+    stride: usize,
+    [width, height]: [usize; 2],
+    alpha: bool,
+) -> Vec<Color32> {
+    // Color32 is Pod with the byte layout of RGBA, so bytemuck casts rows without unsafe
+    let mut pixels: Vec<Color32> = if stride == width * 4 {
+        // Tightly packed: the whole plane in one copy
+        bytemuck::cast_slice(&data[..width * height * 4]).to_vec()
+    } else {
+        // Rows padded past width * 4 bytes (odd widths, aligned linesizes): copy each row
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in data.chunks(stride).take(height) {
+            pixels.extend_from_slice(bytemuck::cast_slice(&row[..width * 4]));
+        }
+        pixels
+    };
+    if alpha {
+        for pixel in &mut pixels {
+            let [r, g, b, a] = pixel.to_array();
+            *pixel = Color32::from_rgba_unmultiplied(r, g, b, a);
+        }
+    }
+    pixels
+}
+
+/// Turn an image by `rotation` degrees clockwise (0, 90, 180 or 270),
+/// returning the pixels and the new size
+pub(crate) fn rotate_pixels(
+    pixels: Vec<Color32>,
+    [width, height]: [u32; 2],
+    rotation: u32,
+) -> (Vec<Color32>, [u32; 2]) {
+    let (w, h) = (width as usize, height as usize);
+    match rotation {
+        90 => {
+            // Row y of the result is column y of the source, read bottom to top
+            let rotated = (0..w)
+                .flat_map(|y| (0..h).map(move |x| (h - 1 - x) * w + y))
+                .map(|i| pixels[i])
+                .collect();
+            (rotated, [height, width])
+        }
+        180 => (pixels.into_iter().rev().collect(), [width, height]),
+        270 => {
+            let rotated = (0..w)
+                .flat_map(|y| (0..h).map(move |x| x * w + (w - 1 - y)))
+                .map(|i| pixels[i])
+                .collect();
+            (rotated, [height, width])
+        }
+        _ => (pixels, [width, height]),
+    }
+}
+
+/// Clockwise rotation (0, 90, 180 or 270 degrees) that shows a video stream
+/// upright, from its display matrix. Matches FFmpeg's autorotation.
+pub(crate) fn stream_rotation(stream: &Stream) -> u32 {
+    let Some(side_data) = stream
+        .side_data()
+        .find(|data| data.kind() == side_data::Type::DisplayMatrix)
+    else {
+        return 0;
+    };
+    // 3x3 matrix of 32-bit integers, the rotation part in 16.16 fixed point
+    let matrix: Vec<f64> = side_data
+        .data()
+        .chunks_exact(4)
+        .take(9)
+        .map(|bytes| f64::from(i32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+        .collect();
+    if matrix.len() < 9 {
+        return 0;
+    }
+    let scale_x = matrix[0].hypot(matrix[3]);
+    let scale_y = matrix[1].hypot(matrix[4]);
+    if scale_x == 0.0 || scale_y == 0.0 {
+        return 0;
+    }
+    let degrees = (matrix[1] / scale_y)
+        .atan2(matrix[0] / scale_x)
+        .to_degrees();
+    // Snap to the nearest quarter turn
+    ((degrees / 90.0).round().rem_euclid(4.0) as u32) * 90
+}
+
+/// Frame rate of a video stream, or 0.0 if unknown.
+/// Prefers the average frame rate and falls back to the container's guess.
+fn stream_frame_rate(stream: &Stream) -> f64 {
+    [stream.avg_frame_rate(), stream.rate()]
+        .into_iter()
+        .find(|rate| rate.numerator() > 0 && rate.denominator() > 0)
+        .map_or(0.0, f64::from)
+}
+
+/// Pick the first audio stream matching the language preferences,
+/// falling back to the container's default track
+fn select_audio_stream<'a>(input: &'a Input, languages: &[String]) -> Option<Stream<'a>> {
+    languages
+        .iter()
+        .find_map(|preference| {
+            input.streams().find(|stream| {
+                stream.parameters().medium() == Type::Audio
+                    && stream
+                        .metadata()
+                        .get("language")
+                        .is_some_and(|tag| language_matches(tag, preference))
+            })
+        })
+        .or_else(|| input.streams().best(Type::Audio))
+}
+
+/// Advanced decoder options
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecoderOptions {
+    /// FFmpeg video filtergraph applied to decoded frames, e.g. "hflip,eq=contrast=1.2"
+    pub video_filter: Option<String>,
+    /// FFmpeg audio filtergraph applied to decoded audio, e.g. "volume=2.0".
+    /// Filters that change playback speed are not compensated in A/V sync.
+    pub audio_filter: Option<String>,
+    /// Preferred audio languages in order (ISO 639 codes such as "eng" or "en").
+    /// The container's default audio track is used when none match.
+    pub audio_languages: Vec<String>,
+    /// Video codec threads; 0 lets FFmpeg pick one per CPU core.
+    /// Set 1 to keep decoding on a single core on constrained hardware.
+    pub threads: usize,
+    /// Scheduling priority of the decoder thread, its stream workers and codec threads
+    pub thread_priority: ThreadPriority,
+    /// Frame rate for image sequences (a `%04d` pattern or a folder of numbered
+    /// images); 0.0 plays them at 25 fps
+    pub image_frame_rate: f64,
+    /// Disk cache and readahead for http(s) files
+    pub network_cache: NetworkCache,
+}
+
+/// Scheduling priority for decoding threads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// Leave scheduling to the OS
+    #[default]
+    Normal,
+    /// Yield to other work, e.g. on embedded systems sharing the CPU
+    Low,
+    /// Favor decoding to avoid dropped frames at high resolutions.
+    /// Usually needs elevated permissions (CAP_SYS_NICE on Linux).
+    High,
+}
+
+impl ThreadPriority {
+    /// Apply the priority to the calling thread. Threads it spawns afterwards inherit it.
+    fn apply(self) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            let nice = match self {
+                Self::Normal => 0,
+                Self::Low => 10,
+                Self::High => -5,
+            };
+            // On Linux, PRIO_PROCESS with id 0 targets only the calling thread
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } != 0 {
+                anyhow::bail!(
+                    "Failed to set thread priority: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            Ok(())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            if self == Self::Normal {
+                Ok(())
+            } else {
+                anyhow::bail!("Thread priority is not supported on this platform")
+            }
+        }
+    }
+}
+
 /// Start the decoder thread
+#[allow(clippy::too_many_arguments)]
 pub fn start_decoder_thread(
     path: &Path,
     video_sender: Sender<DecodedVideoFrame>,
-    audio_buffer: Arc<CircularBuffer<f32>>,
+    mut audio_producer: AudioProducer,
     command_receiver: Receiver<DecoderCommand>,
     clock: AudioClock,
     stop_flag: Arc<AtomicBool>,
-    error_sender: Sender<String>,
+    error_sender: Sender<PlayerError>,
+    info_sender: Sender<(u64, MediaInfo)>,
+    event_sender: Sender<PlayerEvent>,
+    telemetry_sender: Sender<TelemetrySample>,
+    audio_tap: SharedAudioTap,
+    video_effects: SharedVideoEffects,
+    cache_counters: SharedCacheCounters,
+    frame_budget: FrameBudget,
+    recovery: RecoveryPolicy,
+    mut options: DecoderOptions,
+    open_requests: OpenRequests,
 ) -> Result<JoinHandle<()>> {
-    let path = path.to_path_buf();
+    let mut path = path.to_path_buf();
 
     let handle = thread::spawn(move || {
-        if let Err(e) = decode_loop(
-            &path,
-            video_sender,
-            &audio_buffer,
-            command_receiver,
-            clock,
+        let _guard = ResourceGuard::new(Resource::DecoderThread);
+        // Outlives media switches so seek generations keep increasing
+        let sync = DecodeSync::new();
+        let mut ticket = OpenTicket {
+            number: open_requests.count(),
+            requests: open_requests,
             stop_flag,
-        ) {
-            let _ = error_sender.send(format!("Decoder error: {}", e));
+        };
+        loop {
+            match decode_loop(
+                &path,
+                &video_sender,
+                &mut audio_producer,
+                &command_receiver,
+                &clock,
+                &ticket,
+                &info_sender,
+                &event_sender,
+                &telemetry_sender,
+                &audio_tap,
+                &video_effects,
+                &cache_counters,
+                &frame_budget,
+                recovery,
+                &options,
+                &sync,
+            ) {
+                // Switch media without tearing down the thread
+                Ok(Some((next, next_options))) => {
+                    path = next;
+                    options = next_options;
+                    ticket.number += 1;
+                }
+                Ok(None) => break,
+                // An open interrupted by a stop is no error
+                Err(_) if ticket.stop_flag.load(Ordering::Relaxed) => break,
+                Err(e) => {
+                    if ticket.is_cancelled() {
+                        // Superseded by a newer file; its Open command is on the way
+                        tracing::debug!(path = %path.display(), "open superseded");
+                    } else {
+                        let _ = error_sender.send(PlayerError::from(e));
+                    }
+                    // Stay ready for the player to open something else
+                    match next_open(&command_receiver) {
+                        Some((next, next_options)) => {
+                            path = next;
+                            options = next_options;
+                            ticket.number += 1;
+                        }
+                        None => break,
+                    }
+                }
+            }
         }
     });
 
     Ok(handle)
 }
 
+/// Wait for the next `Open` command, dropping the commands meant for the
+/// media before it. None if the player stopped or went away.
+fn next_open(command_receiver: &Receiver<DecoderCommand>) -> Option<(PathBuf, DecoderOptions)> {
+    loop {
+        match command_receiver.recv().ok()? {
+            DecoderCommand::Open(path, options) => return Some((path, options)),
+            DecoderCommand::Stop => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Whether the path refers to a network stream rather than a local file
+pub fn is_network_source(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|s| s.contains("://") && !s.starts_with("file://"))
+}
+
+/// Whether the path is a pipe (`-` or `pipe:N`), which can be read only once
+pub fn is_pipe(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|s| s == "-" || s.starts_with("pipe:"))
+}
+
+/// Tell the demuxer to drop (or deliver again) a stream's packets
+fn set_stream_discard(input: &mut Input, index: usize, enabled: bool) {
+    if let Some(mut stream) = input.stream_mut(index) {
+        let discard = if enabled {
+            Discard::Default
+        } else {
+            Discard::All
+        };
+        // Safety: the stream pointer is valid while `input` is borrowed,
+        // and FFmpeg reads `discard` on the next packet read
+        unsafe {
+            (*stream.as_mut_ptr()).discard = discard.into();
+        }
+    }
+}
+
+/// Open a recording of `input`, reporting the outcome as an event
+fn start_recording(
+    path: &Path,
+    input: &Input,
+    event_sender: &Sender<PlayerEvent>,
+) -> Option<Recorder> {
+    match Recorder::new(path, input) {
+        Ok(recorder) => {
+            let _ = event_sender.try_send(PlayerEvent::RecordingStarted(path.to_path_buf()));
+            Some(recorder)
+        }
+        Err(e) => {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Failed to start recording: {:#}",
+                e
+            )));
+            None
+        }
+    }
+}
+
+/// Finalize the active recording, if any
+fn stop_recording(recorder: &mut Option<Recorder>, event_sender: &Sender<PlayerEvent>) {
+    let Some(active) = recorder.take() else {
+        return;
+    };
+    let event = match active.finish() {
+        Ok(path) => PlayerEvent::RecordingFinished(path),
+        Err(e) => PlayerEvent::Warning(format!("{:#}", e)),
+    };
+    let _ = event_sender.try_send(event);
+}
+
+/// Sleep for the given duration in small slices so a stop request is not delayed.
+/// Returns false if the stop flag was raised while waiting.
+fn sleep_unless_stopped(duration: Duration, stop_flag: &AtomicBool) -> bool {
+    let deadline = std::time::Instant::now() + duration;
+    while std::time::Instant::now() < deadline {
+        if stop_flag.load(Ordering::Relaxed) {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    !stop_flag.load(Ordering::Relaxed)
+}
+
+/// Where demuxing goes after a command
+enum Flow {
+    Continue,
+    /// Stop demuxing, switching to the given media if any
+    Exit(Option<(PathBuf, DecoderOptions)>),
+}
+
+/// Reads packets on the decoder thread and hands them to the per-stream workers
+struct Demuxer<'a> {
+    input: MediaInput,
+    network_cache: NetworkCache,
+    cache_counters: &'a SharedCacheCounters,
+    command_receiver: &'a Receiver<DecoderCommand>,
+    event_sender: &'a Sender<PlayerEvent>,
+    clock: &'a AudioClock,
+    stop_flag: &'a AtomicBool,
+    /// Cancelled once the player asks for other media
+    ticket: &'a OpenTicket,
+    sync: &'a DecodeSync,
+    video_stream_index: usize,
+    audio_stream_index: Option<usize>,
+    frame_interval: f64,
+    video_packets: Sender<WorkerMessage>,
+    audio_packets: Option<Sender<WorkerMessage>>,
+    /// Signalled by a worker that exits, which only happens early on an error
+    worker_exit: Receiver<()>,
+    paused: bool,
+    pending_seek: Option<(f64, SeekMode, u64)>,
+    /// Command received while parked, handled at the top of the loop
+    parked_command: Option<DecoderCommand>,
+    /// Audio-only mode discards video packets in the demuxer
+    video_enabled: bool,
+    /// Remux of the input packets, while recording
+    recorder: Option<Recorder>,
+    /// GoPro metadata track, whose packets are attached to the frames they cover
+    gpmf_stream: Option<(usize, Rational)>,
+}
+
+impl Demuxer<'_> {
+    fn handle_command(&mut self, command: DecoderCommand) -> Flow {
+        match command {
+            DecoderCommand::Stop => return Flow::Exit(None),
+            DecoderCommand::Pause => {
+                self.paused = true;
+                self.clock.pause();
+            }
+            DecoderCommand::Resume => {
+                self.paused = false;
+                self.clock.resume();
+            }
+            DecoderCommand::Prebuffer => self.paused = false,
+            DecoderCommand::Seek(target, mode, generation) => {
+                self.pending_seek = Some((target, mode, generation));
+            }
+            DecoderCommand::SetColorManagement(mode) => self.sync.set_color_management(mode),
+            DecoderCommand::SetPitch(semitones) => self.sync.set_pitch(semitones),
+            DecoderCommand::SetVideoEnabled(enabled) => {
+                self.video_enabled = enabled;
+                set_stream_discard(&mut self.input, self.video_stream_index, enabled);
+                if !enabled {
+                    self.sync.cancel_preview(); // No frame will come to end the preview
+                }
+            }
+            DecoderCommand::Open(next, options) => return Flow::Exit(Some((next, options))),
+            DecoderCommand::StartRecording(target) => {
+                self.recorder = start_recording(&target, &self.input, self.event_sender);
+            }
+            DecoderCommand::StopRecording => stop_recording(&mut self.recorder, self.event_sender),
+        }
+        Flow::Continue
+    }
+
+    /// Handle all pending commands, starting with one received while parked
+    fn poll_commands(&mut self) -> Flow {
+        loop {
+            let command = match self.parked_command.take() {
+                Some(command) => command,
+                None => match self.command_receiver.try_recv() {
+                    Ok(command) => command,
+                    Err(TryRecvError::Empty) => return Flow::Continue,
+                    Err(TryRecvError::Disconnected) => return Flow::Exit(None),
+                },
+            };
+            if let Flow::Exit(next) = self.handle_command(command) {
+                return Flow::Exit(next);
+            }
+        }
+    }
+
+    /// Wait for the next command. Returns false if the player or a worker is gone.
+    fn park(&mut self) -> bool {
+        select! {
+            recv(self.command_receiver) -> command => match command {
+                Ok(command) => {
+                    self.parked_command = Some(command);
+                    true
+                }
+                Err(_) => false,
+            },
+            recv(self.worker_exit) -> _ => false,
+        }
+    }
+
+    /// Hand a message to the video (true) or audio worker. While its queue is full,
+    /// commands are handled so seeks and stops are not held up; the message is
+    /// dropped if a seek or disabling video makes it obsolete.
+    fn send(&mut self, video: bool, mut message: WorkerMessage) -> Flow {
+        loop {
+            let sender = match (video, &self.audio_packets) {
+                (true, _) => &self.video_packets,
+                (false, Some(sender)) => sender,
+                (false, None) => return Flow::Continue,
+            };
+            match sender.try_send(message) {
+                Ok(()) => return Flow::Continue,
+                Err(TrySendError::Disconnected(_)) => return Flow::Exit(None),
+                Err(TrySendError::Full(m)) => message = m,
+            }
+
+            if self.paused && !self.sync.preview_pending() {
+                // The workers stop draining once the paused player stops taking frames - park
+                if !self.park() {
+                    return Flow::Exit(None);
+                }
+            } else {
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            if let Flow::Exit(next) = self.poll_commands() {
+                return Flow::Exit(next);
+            }
+            if self.pending_seek.is_some() || (video && !self.video_enabled) {
+                return Flow::Continue;
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        path: &Path,
+        klv_stream: Option<(usize, Rational)>,
+        telemetry_sender: &Sender<TelemetrySample>,
+        recovery: RecoveryPolicy,
+    ) -> Result<Option<(PathBuf, DecoderOptions)>> {
+        let mut at_eof = false;
+
+        // Error recovery state
+        let network = is_network_source(path);
+        let mut consecutive_errors = 0u32;
+
+        loop {
+            // Check for stop
+            if self.stop_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+
+            if let Flow::Exit(next) = self.poll_commands() {
+                return Ok(next);
+            }
+
+            // Handle pending seek
+            if let Some((target, mode, generation)) = self.pending_seek.take() {
+                let _span = tracing::debug_span!("seek", target, ?mode, generation).entered();
+                let target_ts = (target * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+                let sought = self.input.seek(target_ts, ..target_ts).is_ok();
+                tracing::debug!(sought, "demuxer seek");
+                if sought {
+                    self.clock.set_position(target);
+                    self.sync.set_last_pts(target);
+                    at_eof = false; // Clear EOF - we can read packets again
+                }
+                // Adopted even if the seek failed, as the player now expects it.
+                // The workers flush their codecs when the first packet of it arrives.
+                let preview = (sought && self.video_enabled)
+                    .then(|| mode.earliest_pts(target, self.frame_interval));
+                self.sync
+                    .seek(generation, preview, sought.then_some(target));
+            }
+
+            // Park until the next command if paused or at EOF (wait for seek)
+            if (self.paused && !self.sync.preview_pending()) || at_eof {
+                if !self.park() {
+                    return Ok(None);
+                }
+                continue;
+            }
+
+            // Read next packet
+            let mut packet = Packet::empty();
+            match packet.read(&mut self.input) {
+                Ok(()) => {
+                    consecutive_errors = 0;
+                    let stream_index = packet.stream();
+
+                    if let Some(ref mut active) = self.recorder {
+                        if let Err(e) = active.write(&packet) {
+                            let _ = self.event_sender.try_send(PlayerEvent::Warning(format!(
+                                "Recording stopped: {:#}",
+                                e
+                            )));
+                            self.recorder = None;
+                        }
+                    }
+
+                    // Decode telemetry (cheap enough to stay on this thread)
+                    if let Some((klv_idx, klv_time_base)) = klv_stream {
+                        if stream_index == klv_idx {
+                            if let Some(data) = packet.data() {
+                                // Asynchronous KLV carries no PTS - key it to the latest video frame
+                                let pts_seconds =
+                                    packet.pts().map_or(self.sync.last_pts(), |pts| {
+                                        pts as f64 * f64::from(klv_time_base)
+                                    });
+                                for sample in parse_klv_packet(data, pts_seconds) {
+                                    let _ = telemetry_sender.try_send(sample);
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some((gpmf_idx, gpmf_time_base)) = self.gpmf_stream {
+                        if stream_index == gpmf_idx {
+                            if let (Some(data), Some(pts)) = (packet.data(), packet.pts()) {
+                                self.sync
+                                    .push_gpmf(pts as f64 * f64::from(gpmf_time_base), data);
+                            }
+                        }
+                    }
+
+                    let video = stream_index == self.video_stream_index;
+                    if (video && self.video_enabled)
+                        || Some(stream_index) == self.audio_stream_index
+                    {
+                        let generation = self.sync.generation();
+                        if let Flow::Exit(next) =
+                            self.send(video, WorkerMessage::Packet(generation, packet))
+                        {
+                            return Ok(next);
+                        }
+                    }
+                }
+                Err(ffmpeg_next::Error::Eof) => {
+                    // End of file - wait for seek or stop command
+                    at_eof = true;
+                    continue;
+                }
+                // A read interrupted for newer media; its Open command ends the loop
+                Err(_) if self.ticket.is_cancelled() => {
+                    if self.stop_flag.load(Ordering::Relaxed) {
+                        return Ok(None);
+                    }
+                    thread::yield_now();
+                    continue;
+                }
+                Err(e) => {
+                    consecutive_errors += 1;
+                    if consecutive_errors > recovery.max_retries {
+                        let message = format!(
+                            "Giving up after {} consecutive read errors: {}",
+                            recovery.max_retries, e
+                        );
+                        return Err(if network {
+                            PlayerError::Network {
+                                message,
+                                retryable: true,
+                            }
+                        } else {
+                            PlayerError::DecoderStalled(message)
+                        }
+                        .into());
+                    }
+
+                    if !sleep_unless_stopped(recovery.backoff(consecutive_errors), self.stop_flag) {
+                        return Ok(None);
+                    }
+
+                    if network && recovery.auto_reconnect {
+                        // Reopen the stream and resume where we left off
+                        let _ = self.event_sender.try_send(PlayerEvent::Reconnecting {
+                            attempt: consecutive_errors,
+                        });
+                        match open_media(
+                            path,
+                            0.0,
+                            self.network_cache,
+                            self.cache_counters,
+                            self.ticket.interrupt(),
+                        ) {
+                            Ok(reopened) => {
+                                self.input = reopened;
+                                let last_pts = self.sync.last_pts();
+                                let resume_ts =
+                                    (last_pts * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+                                let _ = self.input.seek(resume_ts, ..resume_ts);
+                                for video in [true, false] {
+                                    if let Flow::Exit(next) = self.send(video, WorkerMessage::Flush)
+                                    {
+                                        return Ok(next);
+                                    }
+                                }
+                                let _ = self.event_sender.try_send(PlayerEvent::Reconnected {
+                                    position: Duration::from_secs_f64(last_pts.max(0.0)),
+                                });
+                            }
+                            Err(e) => {
+                                let _ = self.event_sender.try_send(PlayerEvent::Warning(format!(
+                                    "Reconnect failed: {}",
+                                    e
+                                )));
+                            }
+                        }
+                    } else {
+                        // Skip corrupted packets
+                        let _ = self.event_sender.try_send(PlayerEvent::Warning(format!(
+                            "Failed to read packet: {}",
+                            e
+                        )));
+                    }
+                    continue;
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn decode_loop(
     path: &Path,
-    video_sender: Sender<DecodedVideoFrame>,
-    audio_buffer: &Arc<CircularBuffer<f32>>,
-    command_receiver: Receiver<DecoderCommand>,
-    clock: AudioClock,
-    stop_flag: Arc<AtomicBool>,
-) -> Result<()> {
-    let mut input = ffmpeg_next::format::input(path)?;
+    video_sender: &Sender<DecodedVideoFrame>,
+    audio_producer: &mut AudioProducer,
+    command_receiver: &Receiver<DecoderCommand>,
+    clock: &AudioClock,
+    ticket: &OpenTicket,
+    info_sender: &Sender<(u64, MediaInfo)>,
+    event_sender: &Sender<PlayerEvent>,
+    telemetry_sender: &Sender<TelemetrySample>,
+    audio_tap: &SharedAudioTap,
+    video_effects: &SharedVideoEffects,
+    cache_counters: &SharedCacheCounters,
+    frame_budget: &FrameBudget,
+    recovery: RecoveryPolicy,
+    options: &DecoderOptions,
+    sync: &DecodeSync,
+) -> Result<Option<(PathBuf, DecoderOptions)>> {
+    let stop_flag: &AtomicBool = &ticket.stop_flag;
+    let open_span = tracing::info_span!("open", path = %path.display()).entered();
+    let input = open_media(
+        path,
+        options.image_frame_rate,
+        options.network_cache,
+        cache_counters,
+        ticket.interrupt(),
+    )
+    .map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+
+    // The media the thread started with was probed before it; tell the
+    // player about each file it was asked to open instead
+    if ticket.number > 0 {
+        let _ = info_sender.send((ticket.number, media_info(&input, path)?));
+    }
 
     // Find streams
     let video_stream_index = input
         .streams()
         .best(Type::Video)
-        .ok_or_else(|| anyhow!("No video stream"))?
+        .ok_or(PlayerError::NoVideoStream)?
         .index();
 
-    let audio_stream_index = input.streams().best(Type::Audio).map(|s| s.index());
+    let audio_stream_index =
+        select_audio_stream(&input, &options.audio_languages).map(|s| s.index());
+    // Without audio nothing plays samples to advance the clock
+    clock.set_wall_clock(audio_stream_index.is_none());
+
+    // Embedded KLV metadata (MISB ST 0601) found in UAS footage
+    let klv_stream = input
+        .streams()
+        .find(|s| s.parameters().id() == codec::Id::SMPTE_KLV)
+        .map(|s| (s.index(), s.time_base()));
+    // GPMF telemetry track of GoPro cameras
+    let gpmf_stream = input
+        .streams()
+        .find(|s| {
+            s.metadata()
+                .get("handler_name")
+                .is_some_and(|name| name.contains("GoPro MET"))
+        })
+        .map(|s| (s.index(), s.time_base()));
 
     // Get stream info before creating decoders
     let video_stream = input.stream(video_stream_index).unwrap();
     let video_time_base = video_stream.time_base();
+    let rotation = stream_rotation(&video_stream);
+    let frame_interval = match stream_frame_rate(&video_stream) {
+        rate if rate > 0.0 => 1.0 / rate,
+        _ => DEFAULT_FRAME_INTERVAL,
+    };
     let video_params = video_stream.parameters();
 
-    let (_audio_time_base, audio_params) = if let Some(idx) = audio_stream_index {
+    let (audio_time_base, audio_params) = if let Some(idx) = audio_stream_index {
         let stream = input.stream(idx).unwrap();
         (stream.time_base(), Some(stream.parameters()))
     } else {
         (Rational::new(1, 1), None)
     };
 
+    // Codec threads and the stream workers inherit the decoder thread's priority
+    if let Err(e) = options.thread_priority.apply() {
+        if options.thread_priority != ThreadPriority::Normal {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!("{:#}", e)));
+        }
+    }
+
     // Create decoders
-    let mut video_decoder = codec::Context::from_parameters(video_params)?
-        .decoder()
-        .video()?;
+    let mut video_context = codec::Context::from_parameters(video_params)?;
+    unsafe {
+        (*video_context.as_mut_ptr()).thread_count = options.threads.try_into().unwrap_or(0);
+    }
+    let video_decoder = video_context.decoder().video()?;
+    // FFmpeg resolves 0 to the actual count when the codec opens
+    let decoder_threads = unsafe { (*video_decoder.as_ptr()).thread_count }.max(1) as usize;
 
-    let mut audio_decoder = if let Some(params) = audio_params {
+    let audio_decoder = if let Some(params) = audio_params {
         Some(codec::Context::from_parameters(params)?.decoder().audio()?)
     } else {
         None
     };
 
-    // Create scaler for video (to RGBA)
-    let mut scaler = ScalerContext::get(
-        video_decoder.format(),
-        video_decoder.width(),
-        video_decoder.height(),
-        Pixel::RGBA,
-        video_decoder.width(),
-        video_decoder.height(),
-        Flags::BILINEAR,
-    )?;
-
-    // Create resampler for audio (to f32 stereo)
-    let mut resampler = if let Some(ref decoder) = audio_decoder {
-        Some(ResamplerContext::get(
-            decoder.format(),
-            decoder.channel_layout(),
-            decoder.rate(),
-            Sample::F32(ffmpeg_next::util::format::sample::Type::Packed),
-            ChannelLayout::STEREO,
-            clock.sample_rate(),
-        )?)
-    } else {
-        None
+    let video_worker = VideoWorker {
+        decoder: video_decoder,
+        time_base: video_time_base,
+        rotation,
+        decoder_threads,
+        filter: options.video_filter.clone(),
+        frame_budget: frame_budget.clone(),
     };
+    let audio_worker = audio_decoder.map(|decoder| AudioWorker {
+        decoder,
+        time_base: audio_time_base,
+        filter: options.audio_filter.clone(),
+    });
+    tracing::info!(
+        video_stream = video_stream_index,
+        audio_stream = ?audio_stream_index,
+        decoder_threads,
+        "media opened"
+    );
+    drop(open_span);
 
-    let mut video_frame = VideoFrame::empty();
-    let mut audio_frame = AudioFrame::empty();
-    let mut rgba_frame = VideoFrame::empty();
+    // Demux here and decode each stream on its own thread, so a high-bitrate
+    // video stream does not hold up audio and scaling overlaps with decoding
+    sync.set_finished(false);
+    thread::scope(|scope| {
+        let (exit_sender, worker_exit) = bounded(2);
 
-    let mut paused = true;
-    let mut pending_seek: Option<f64> = None;
-    let mut at_eof = false;
+        let (video_packets, packets) = bounded(VIDEO_PACKET_QUEUE);
+        let video_exit = exit_sender.clone();
+        let video_handle = scope.spawn(move || {
+            let _guard = ResourceGuard::new(Resource::DecodeWorker);
+            let result = video_worker.run(
+                packets,
+                video_sender,
+                video_effects,
+                sync,
+                stop_flag,
+                event_sender,
+            );
+            let _ = video_exit.send(());
+            result
+        });
 
-    // Main decode loop - use manual packet reading instead of iterator
-    loop {
-        // Check for stop
-        if stop_flag.load(Ordering::Relaxed) {
-            break;
-        }
-
-        // Handle commands (process all pending commands)
-        loop {
-            match command_receiver.try_recv() {
-                Ok(DecoderCommand::Stop) => return Ok(()),
-                Ok(DecoderCommand::Pause) => {
-                    paused = true;
-                    clock.pause();
-                }
-                Ok(DecoderCommand::Resume) => {
-                    paused = false;
-                    clock.resume();
-                }
-                Ok(DecoderCommand::Seek(target)) => {
-                    pending_seek = Some(target);
-                }
-                Err(TryRecvError::Empty) => break,
-                Err(TryRecvError::Disconnected) => return Ok(()),
+        let (audio_packets, audio_handle) = match audio_worker {
+            Some(worker) => {
+                let (sender, packets) = bounded(AUDIO_PACKET_QUEUE);
+                let handle = scope.spawn(move || {
+                    let _guard = ResourceGuard::new(Resource::DecodeWorker);
+                    let result = worker.run(
+                        packets,
+                        audio_producer,
+                        clock,
+                        audio_tap,
+                        sync,
+                        stop_flag,
+                        event_sender,
+                    );
+                    let _ = exit_sender.send(());
+                    result
+                });
+                (Some(sender), Some(handle))
             }
-        }
+            None => (None, None),
+        };
 
-        // Handle pending seek
-        if let Some(target) = pending_seek.take() {
-            let target_ts = (target * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
-            if input.seek(target_ts, ..target_ts).is_ok() {
-                // Flush decoders
-                video_decoder.flush();
-                if let Some(ref mut dec) = audio_decoder {
-                    dec.flush();
-                }
-                clock.set_position(target);
-                at_eof = false; // Clear EOF - we can read packets again
-            }
-        }
+        let mut demuxer = Demuxer {
+            input,
+            network_cache: options.network_cache,
+            cache_counters,
+            command_receiver,
+            event_sender,
+            clock,
+            stop_flag,
+            ticket,
+            sync,
+            video_stream_index,
+            audio_stream_index,
+            frame_interval,
+            video_packets,
+            audio_packets,
+            worker_exit,
+            paused: true,
+            pending_seek: None,
+            parked_command: None,
+            video_enabled: true,
+            recorder: None,
+            gpmf_stream,
+        };
+        let result = demuxer.run(path, klv_stream, telemetry_sender, recovery);
 
-        // Skip packet reading if paused or at EOF (wait for seek)
-        if paused || at_eof {
-            thread::sleep(std::time::Duration::from_millis(10));
-            continue;
+        // Closing the packet queues lets the workers finish
+        sync.set_finished(true);
+        drop(demuxer);
+        let workers = [Some(video_handle), audio_handle]
+            .into_iter()
+            .flatten()
+            .try_for_each(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Decode worker panicked")))
+            });
+        match result {
+            // A worker that failed ended demuxing; report why
+            Ok(None) => workers.map(|()| None),
+            other => other,
         }
+    })
+}
 
-        // Read next packet
-        let mut packet = Packet::empty();
-        match packet.read(&mut input) {
-            Ok(()) => {
-                let stream_index = packet.stream();
-
-                // Decode video
-                if stream_index == video_stream_index {
-                    video_decoder.send_packet(&packet)?;
-
-                    'frame_loop: while video_decoder.receive_frame(&mut video_frame).is_ok() {
-                        // Scale to RGBA
-                        scaler.run(&video_frame, &mut rgba_frame)?;
-
-                        // Calculate PTS in seconds
-                        let pts = video_frame.pts().unwrap_or(0);
-                        let pts_seconds = pts as f64 * f64::from(video_time_base);
-
-                        // Convert RGBA bytes to Color32 via transmute (zero-copy reinterpret)
-                        // Safe because: Color32 is repr(C) with same layout as [u8; 4] in RGBA order
-                        let pixels: Vec<Color32> = unsafe {
-                            let mut rgba = rgba_frame.data(0).to_vec();
-                            let len = rgba.len() / 4;
-                            let cap = rgba.capacity() / 4;
-                            let ptr = rgba.as_mut_ptr() as *mut Color32;
-                            std::mem::forget(rgba);
-                            Vec::from_raw_parts(ptr, len, cap)
-                        };
-
-                        let mut frame = DecodedVideoFrame {
-                            pixels,
-                            width: rgba_frame.width(),
-                            height: rgba_frame.height(),
-                            pts: pts_seconds,
-                        };
-
-                        // Non-blocking send with command polling
-                        loop {
-                            // Check for commands first - seek/stop take priority
-                            match command_receiver.try_recv() {
-                                Ok(DecoderCommand::Stop) => return Ok(()),
-                                Ok(DecoderCommand::Pause) => {
-                                    paused = true;
-                                    clock.pause();
-                                }
-                                Ok(DecoderCommand::Resume) => {
-                                    paused = false;
-                                    clock.resume();
-                                }
-                                Ok(DecoderCommand::Seek(target)) => {
-                                    // Seek requested - abandon this frame and process seek
-                                    pending_seek = Some(target);
-                                    break 'frame_loop;
-                                }
-                                Err(TryRecvError::Empty) => {}
-                                Err(TryRecvError::Disconnected) => return Ok(()),
-                            }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                            // Try to send the frame
-                            match video_sender.try_send(frame) {
-                                Ok(()) => break, // Frame sent successfully
-                                Err(TrySendError::Full(f)) => {
-                                    frame = f; // Channel full, retry after brief sleep
-                                    thread::sleep(std::time::Duration::from_millis(1));
-                                }
-                                Err(TrySendError::Disconnected(_)) => return Ok(()),
-                            }
-                        }
-                    }
-                }
+    /// Distinct color for each pixel position of frames up to 4095x4095
+    fn pattern(x: usize, y: usize) -> [u8; 4] {
+        [x as u8, y as u8, ((x >> 8) | (y >> 8) << 4) as u8, 255]
+    }
 
-                // Decode audio
-                if let Some(audio_idx) = audio_stream_index {
-                    if stream_index == audio_idx {
-                        if let Some(ref mut decoder) = audio_decoder {
-                            decoder.send_packet(&packet)?;
-
-                            while decoder.receive_frame(&mut audio_frame).is_ok() {
-                                if let Some(ref mut resampler) = resampler {
-                                    // Resample to f32 stereo
-                                    let mut resampled = AudioFrame::empty();
-                                    if resampler.run(&audio_frame, &mut resampled).is_ok() {
-                                        // Get samples as f32
-                                        let data = resampled.data(0);
-                                        let samples: &[f32] = unsafe {
-                                            std::slice::from_raw_parts(
-                                                data.as_ptr() as *const f32,
-                                                data.len() / 4,
-                                            )
-                                        };
-
-                                        // Write to circular buffer (never blocks, overwrites oldest if full)
-                                        audio_buffer.push_slice(samples);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    /// RGBA plane with rows `stride` bytes apart, padding filled with garbage
+    fn plane(width: usize, height: usize, stride: usize) -> Vec<u8> {
+        let mut data = vec![0xab; stride * height];
+        for y in 0..height {
+            for x in 0..width {
+                let offset = y * stride + x * 4;
+                data[offset..offset + 4].copy_from_slice(&pattern(x, y));
             }
-            Err(ffmpeg_next::Error::Eof) => {
-                // End of file - wait for seek or stop command
-                at_eof = true;
-                continue;
-            }
-            Err(_) => {
-                // Skip corrupted packets
-                continue;
+        }
+        data
+    }
+
+    fn assert_pattern(pixels: &[Color32], width: usize, height: usize) {
+        assert_eq!(pixels.len(), width * height);
+        for y in 0..height {
+            for x in 0..width {
+                assert_eq!(
+                    pixels[y * width + x].to_array(),
+                    pattern(x, y),
+                    "({x}, {y})"
+                );
             }
         }
     }
 
-    // Flush decoders
-    video_decoder.send_eof()?;
-    while video_decoder.receive_frame(&mut video_frame).is_ok() {
-        scaler.run(&video_frame, &mut rgba_frame)?;
-        let pts = video_frame.pts().unwrap_or(0);
-        let pts_seconds = pts as f64 * f64::from(video_time_base);
-
-        let pixels: Vec<Color32> = unsafe {
-            let mut rgba = rgba_frame.data(0).to_vec();
-            let len = rgba.len() / 4;
-            let cap = rgba.capacity() / 4;
-            let ptr = rgba.as_mut_ptr() as *mut Color32;
-            std::mem::forget(rgba);
-            Vec::from_raw_parts(ptr, len, cap)
-        };
+    #[test]
+    fn padded_stride_skips_row_padding() {
+        let (width, height) = (1919, 1079);
+        let pixels = plane_pixels(&plane(width, height, 7680), 7680, [width, height], false);
+        assert_pattern(&pixels, width, height);
+    }
 
-        let frame = DecodedVideoFrame {
-            pixels,
-            width: rgba_frame.width(),
-            height: rgba_frame.height(),
-            pts: pts_seconds,
-        };
+    #[test]
+    fn tightly_packed_odd_size() {
+        let (width, height) = (1919, 1079);
+        let stride = width * 4;
+        let pixels = plane_pixels(
+            &plane(width, height, stride),
+            stride,
+            [width, height],
+            false,
+        );
+        assert_pattern(&pixels, width, height);
+    }
 
-        let _ = video_sender.send(frame);
+    /// 3x5 image whose pixels are numbered 0 to 14 in reading order
+    fn numbered() -> Vec<Color32> {
+        (0..15).map(Color32::from_gray).collect()
     }
 
-    if let Some(ref mut decoder) = audio_decoder {
-        decoder.send_eof()?;
-        while decoder.receive_frame(&mut audio_frame).is_ok() {
-            // Process remaining audio...
-        }
+    fn numbers(pixels: &[Color32]) -> Vec<u8> {
+        pixels.iter().map(|pixel| pixel.r()).collect()
+    }
+
+    #[test]
+    fn rotate_90() {
+        let (pixels, size) = rotate_pixels(numbered(), [3, 5], 90);
+        assert_eq!(size, [5, 3]);
+        assert_eq!(
+            numbers(&pixels),
+            [12, 9, 6, 3, 0, 13, 10, 7, 4, 1, 14, 11, 8, 5, 2]
+        );
+    }
+
+    #[test]
+    fn rotate_180() {
+        let (pixels, size) = rotate_pixels(numbered(), [3, 5], 180);
+        assert_eq!(size, [3, 5]);
+        assert_eq!(
+            numbers(&pixels),
+            [14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]
+        );
     }
 
-    Ok(())
+    #[test]
+    fn rotate_270() {
+        let (pixels, size) = rotate_pixels(numbered(), [3, 5], 270);
+        assert_eq!(size, [5, 3]);
+        assert_eq!(
+            numbers(&pixels),
+            [2, 5, 8, 11, 14, 1, 4, 7, 10, 13, 0, 3, 6, 9, 12]
+        );
+    }
 }
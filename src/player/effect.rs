@@ -0,0 +1,24 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+use super::decoder::DecodedVideoFrame;
+
+/// Per-frame video processing in Rust, for watermarks, anonymization blurs
+/// or analysis overlays without writing FFmpeg filters.
+///
+/// Frames arrive upright and after any filtergraph, as premultiplied RGBA.
+/// Modify the pixels in place, or replace them along with the size.
+/// Runs on the video decoding thread, so slow effects lower the frame rate.
+pub trait VideoEffect: Send {
+    fn process(&mut self, frame: &mut DecodedVideoFrame);
+}
+
+/// Effect chain shared between the player and the decoder thread, applied in order
+pub type SharedVideoEffects = Arc<Mutex<Vec<Box<dyn VideoEffect>>>>;
+
+/// Run the effect chain over a frame
+pub fn apply_effects(effects: &SharedVideoEffects, frame: &mut DecodedVideoFrame) {
+    for effect in effects.lock().iter_mut() {
+        effect.process(frame);
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::path::Path;
+
+use super::decoder::is_network_source;
+
+/// Errors reported by the player, grouped by how an application might recover
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerError {
+    /// A file could not be read or written
+    Io(String),
+    /// No decoder is available for the container or codec
+    UnsupportedCodec(String),
+    /// The media has no video stream
+    NoVideoStream,
+    /// The decoder gave up after repeated read errors
+    DecoderStalled(String),
+    /// The audio output device could not be opened
+    AudioDevice(String),
+    /// A network source failed; retryable errors may succeed on another attempt
+    Network { message: String, retryable: bool },
+    /// The input is read once, like a pipe, so it cannot seek or be reopened
+    NotSeekable,
+    /// Any other failure, such as a bad filtergraph or an empty playlist slot
+    Other(String),
+}
+
+impl PlayerError {
+    /// Classify an FFmpeg error raised while opening or reading `path`
+    pub(crate) fn from_ffmpeg(error: ffmpeg_next::Error, path: &Path) -> Self {
+        let message = format!("{}: {}", path.display(), error);
+        Self::classify(error, message, is_network_source(path))
+    }
+
+    fn classify(error: ffmpeg_next::Error, message: String, network: bool) -> Self {
+        use ffmpeg_next::Error as E;
+
+        match error {
+            E::DecoderNotFound | E::DemuxerNotFound => Self::UnsupportedCodec(message),
+            E::StreamNotFound => Self::NoVideoStream,
+            _ if network => Self::Network {
+                message,
+                // Client errors will fail the same way again
+                retryable: !matches!(
+                    error,
+                    E::ProtocolNotFound
+                        | E::InvalidData
+                        | E::HttpBadRequest
+                        | E::HttpUnauthorized
+                        | E::HttpForbidden
+                        | E::HttpNotFound
+                        | E::HttpOther4xx
+                ),
+            },
+            E::Other { .. } => Self::Io(message),
+            _ => Self::Other(message),
+        }
+    }
+
+    /// Whether trying the same operation again may succeed
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Network {
+                retryable: true,
+                ..
+            }
+        )
+    }
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {}", message),
+            Self::UnsupportedCodec(message) => write!(f, "Unsupported format: {}", message),
+            Self::NoVideoStream => write!(f, "No video stream found"),
+            Self::DecoderStalled(message) => write!(f, "Decoder stalled: {}", message),
+            Self::AudioDevice(message) => write!(f, "Audio device error: {}", message),
+            Self::Network { message, .. } => write!(f, "Network error: {}", message),
+            Self::NotSeekable => write!(f, "Input is not seekable"),
+            Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+/// Internal code uses anyhow; recover the structured error at the API boundary
+impl From<anyhow::Error> for PlayerError {
+    fn from(error: anyhow::Error) -> Self {
+        if let Some(e) = error.downcast_ref::<PlayerError>() {
+            e.clone()
+        } else if error.downcast_ref::<std::io::Error>().is_some() {
+            Self::Io(format!("{:#}", error))
+        } else if let Some(&e) = error.downcast_ref::<ffmpeg_next::Error>() {
+            Self::classify(e, format!("{:#}", error), false)
+        } else {
+            Self::Other(format!("{:#}", error))
+        }
+    }
+}
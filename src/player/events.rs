@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::output::AudioOutputFormat;
+
+/// Notifications emitted by the player while it runs.
+/// Poll with `VideoPlayer::poll_event()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerEvent {
+    /// A recoverable decoder problem was skipped or retried
+    Warning(String),
+    /// Connection to a network source was lost; reconnect attempt in progress
+    Reconnecting { attempt: u32 },
+    /// Reconnected to a network source and resumed at the given position
+    Reconnected { position: Duration },
+    /// A new video frame was uploaded to the texture
+    FramePresented { pts: Duration, frame_number: u64 },
+    /// The watched file changed on disk and was reopened
+    MediaReloaded(PathBuf),
+    /// Playback moved on to the playlist item at the given index
+    PlaylistAdvanced { index: usize },
+    /// Audio export progress (0.0 to 1.0)
+    ExportProgress(f32),
+    /// Audio export completed and was written to the given file
+    ExportFinished(PathBuf),
+    /// Audio export failed
+    ExportFailed(String),
+    /// A burst capture finished, saving the given frames in order
+    BurstFinished(Vec<PathBuf>),
+    /// A burst capture failed
+    BurstFailed(String),
+    /// Recording of the input started, writing to the given file
+    RecordingStarted(PathBuf),
+    /// Recording was finalized and saved to the given file
+    RecordingFinished(PathBuf),
+    /// Over the last second of playback too many frames were dropped, or
+    /// frames were shown too far from the audio. Suggests enabling hardware
+    /// decoding or shrinking the video; sent at most every few seconds.
+    PerformanceWarning {
+        /// Frames dropped in the last second
+        dropped_frames: u32,
+        /// Average offset of shown frames from the audio; positive when video is early
+        drift_ms: f64,
+    },
+    /// The audio device could not be opened as f32 stereo at its own rate;
+    /// audio plays in the given format instead
+    AudioOutputFallback {
+        format: AudioOutputFormat,
+        reason: String,
+    },
+    /// The audio device stopped playing, e.g. because it was unplugged. The
+    /// output is reopened on the current default device and playback resumes
+    /// where it stopped.
+    AudioDeviceLost,
+}
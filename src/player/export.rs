@@ -0,0 +1,297 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::Sender;
+use ffmpeg_next::codec::Capabilities;
+use ffmpeg_next::format::context::Output;
+use ffmpeg_next::frame::Audio as AudioFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::{codec, encoder, Packet, Rational};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use super::events::PlayerEvent;
+use super::filter::FrameFilter;
+
+/// Bit rate used for MP3 exports
+const MP3_BIT_RATE: usize = 192_000;
+
+/// Output format for `VideoPlayer::export_audio`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioFormat {
+    /// MPEG-1 Layer III (lossy)
+    Mp3,
+    /// Free Lossless Audio Codec
+    Flac,
+    /// Uncompressed 16-bit PCM
+    Wav,
+}
+
+impl AudioFormat {
+    /// File extension for this format (without the dot)
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Flac => "flac",
+            Self::Wav => "wav",
+        }
+    }
+
+    /// Pick the format matching a file extension
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "mp3" => Some(Self::Mp3),
+            "flac" => Some(Self::Flac),
+            "wav" => Some(Self::Wav),
+            _ => None,
+        }
+    }
+
+    fn codec_id(self) -> codec::Id {
+        match self {
+            Self::Mp3 => codec::Id::MP3,
+            Self::Flac => codec::Id::FLAC,
+            Self::Wav => codec::Id::PCM_S16LE,
+        }
+    }
+}
+
+/// Start a background thread that encodes the audio track of `source` into `output`.
+/// Progress and the result are reported as `PlayerEvent`s.
+pub fn start_export_thread(
+    source: PathBuf,
+    output: PathBuf,
+    format: AudioFormat,
+    range: Range<Duration>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || {
+        let event = match export_audio(&source, &output, format, range, &event_sender) {
+            Ok(()) => PlayerEvent::ExportFinished(output),
+            Err(e) => PlayerEvent::ExportFailed(format!("{:#}", e)),
+        };
+        // Blocks while the queue is full so the result is never dropped;
+        // fails only once the player is gone
+        let _ = event_sender.send(event);
+    });
+}
+
+fn export_audio(
+    source: &Path,
+    output: &Path,
+    format: AudioFormat,
+    range: Range<Duration>,
+    event_sender: &Sender<PlayerEvent>,
+) -> Result<()> {
+    let mut input = ffmpeg_next::format::input(source).context("Failed to open input file")?;
+
+    let stream = input
+        .streams()
+        .best(Type::Audio)
+        .ok_or_else(|| anyhow!("No audio stream found"))?;
+    let stream_index = stream.index();
+    let input_time_base = stream.time_base();
+    let decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .audio()?;
+
+    // Clamp the range to the media so progress reaches 100%
+    let start = range.start.as_secs_f64();
+    let mut end = range.end.as_secs_f64();
+    if input.duration() > 0 {
+        end = end.min(input.duration() as f64 / ffmpeg_next::ffi::AV_TIME_BASE as f64);
+    }
+    if end <= start {
+        return Err(anyhow!("Export range is empty"));
+    }
+
+    let mut octx = ffmpeg_next::format::output(output).context("Failed to create output file")?;
+    let codec = encoder::find(format.codec_id())
+        .ok_or_else(|| anyhow!("No {} encoder available", format.extension()))?
+        .audio()?;
+
+    // Match the source as closely as the encoder allows
+    let source_rate = decoder.rate() as i32;
+    let rate = codec
+        .rates()
+        .and_then(|rates| rates.min_by_key(|rate| (rate - source_rate).abs()))
+        .unwrap_or(source_rate);
+    let channel_layout = codec
+        .channel_layouts()
+        .map_or(ChannelLayout::STEREO, |layouts| {
+            layouts.best(i32::from(decoder.channels()))
+        });
+    let sample_format = codec
+        .formats()
+        .and_then(|mut formats| formats.next())
+        .ok_or_else(|| anyhow!("Encoder reports no sample formats"))?;
+
+    let output_time_base = Rational::new(1, rate);
+    let encoder = {
+        let mut stream = octx.add_stream(codec)?;
+        let mut encoder = codec::Context::from_parameters(stream.parameters())?
+            .encoder()
+            .audio()?;
+        encoder.set_rate(rate);
+        encoder.set_channel_layout(channel_layout);
+        encoder.set_format(sample_format);
+        if format == AudioFormat::Mp3 {
+            encoder.set_bit_rate(MP3_BIT_RATE);
+        }
+        encoder.set_time_base(output_time_base);
+        stream.set_time_base(output_time_base);
+
+        let encoder = encoder.open_as(codec)?;
+        stream.set_parameters(&encoder);
+        encoder
+    };
+    octx.write_header()
+        .context("Failed to write output header")?;
+    // The muxer may pick its own time base when writing the header
+    let stream_time_base = octx.stream(0).map_or(output_time_base, |s| s.time_base());
+
+    // Trim to the range and convert to the encoder's format
+    let mut filter = FrameFilter::audio(
+        &format!("atrim=start={}:end={}", start, end),
+        &decoder,
+        input_time_base,
+        |sink| {
+            sink.set_sample_format(encoder.format());
+            sink.set_channel_layout(encoder.channel_layout());
+            sink.set_sample_rate(encoder.rate());
+        },
+    )?;
+    if !codec
+        .capabilities()
+        .contains(Capabilities::VARIABLE_FRAME_SIZE)
+    {
+        filter.set_frame_size(encoder.frame_size());
+    }
+
+    let mut exporter = Exporter {
+        decoder,
+        filter,
+        encoder,
+        input_time_base,
+        output_time_base,
+        stream_time_base,
+        samples_written: 0,
+        start,
+        end,
+        last_percent: None,
+        event_sender,
+    };
+
+    if start > 0.0 {
+        let target_ts = (start * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+        input.seek(target_ts, ..target_ts)?;
+    }
+
+    let mut packet = Packet::empty();
+    loop {
+        match packet.read(&mut input) {
+            Ok(()) => {}
+            Err(ffmpeg_next::Error::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        }
+        if packet.stream() != stream_index {
+            continue;
+        }
+        // Past the end of the range - nothing more to export
+        if packet
+            .pts()
+            .is_some_and(|pts| pts as f64 * f64::from(input_time_base) > end)
+        {
+            break;
+        }
+        // Skip corrupt packets rather than failing the whole export
+        if exporter.decoder.send_packet(&packet).is_ok() {
+            exporter.drain_decoder(&mut octx)?;
+        }
+    }
+
+    // Flush decoder, filter and encoder
+    exporter.decoder.send_eof()?;
+    exporter.drain_decoder(&mut octx)?;
+    exporter.filter.flush()?;
+    exporter.drain_filter(&mut octx)?;
+    exporter.encoder.send_eof()?;
+    exporter.drain_encoder(&mut octx)?;
+
+    octx.write_trailer()
+        .context("Failed to finalize output file")?;
+    Ok(())
+}
+
+/// Decoder, filter and encoder state for one export
+struct Exporter<'a> {
+    decoder: ffmpeg_next::decoder::Audio,
+    filter: FrameFilter,
+    encoder: encoder::audio::Encoder,
+    input_time_base: Rational,
+    output_time_base: Rational,
+    stream_time_base: Rational,
+    samples_written: i64,
+    start: f64,
+    end: f64,
+    last_percent: Option<u32>,
+    event_sender: &'a Sender<PlayerEvent>,
+}
+
+impl Exporter<'_> {
+    fn drain_decoder(&mut self, octx: &mut Output) -> Result<()> {
+        let mut decoded = AudioFrame::empty();
+        while self.decoder.receive_frame(&mut decoded).is_ok() {
+            let timestamp = decoded.timestamp();
+            decoded.set_pts(timestamp);
+            if let Some(ts) = timestamp {
+                self.report_progress(ts as f64 * f64::from(self.input_time_base));
+            }
+            self.filter.push(&decoded)?;
+            self.drain_filter(octx)?;
+        }
+        Ok(())
+    }
+
+    fn drain_filter(&mut self, octx: &mut Output) -> Result<()> {
+        let mut filtered = AudioFrame::empty();
+        while self.filter.pull(&mut filtered) {
+            // Timestamps restart at zero in the output file
+            filtered.set_pts(Some(self.samples_written));
+            self.samples_written += filtered.samples() as i64;
+            self.encoder.send_frame(&filtered)?;
+            self.drain_encoder(octx)?;
+        }
+        Ok(())
+    }
+
+    fn drain_encoder(&mut self, octx: &mut Output) -> Result<()> {
+        let mut encoded = Packet::empty();
+        while self.encoder.receive_packet(&mut encoded).is_ok() {
+            encoded.set_stream(0);
+            encoded.rescale_ts(self.output_time_base, self.stream_time_base);
+            encoded.write_interleaved(octx)?;
+        }
+        Ok(())
+    }
+
+    /// Send a progress event for every whole percent. Updates are skipped
+    /// while the event queue is over half full, leaving room for the result.
+    fn report_progress(&mut self, position: f64) {
+        let progress = ((position - self.start) / (self.end - self.start)).clamp(0.0, 1.0);
+        let percent = (progress * 100.0) as u32;
+        let crowded = self
+            .event_sender
+            .capacity()
+            .is_some_and(|capacity| self.event_sender.len() > capacity / 2);
+        if self.last_percent != Some(percent) && !crowded {
+            self.last_percent = Some(percent);
+            let _ = self
+                .event_sender
+                .try_send(PlayerEvent::ExportProgress(progress as f32));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Context, Result};
+use ffmpeg_next::decoder;
+use ffmpeg_next::filter::{self, Graph};
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::{Frame, Rational};
+
+/// How intermediate frames are made when playing slower than normal speed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InterpolationMode {
+    /// Hold each frame longer
+    #[default]
+    Off,
+    /// Cross-fade between neighbouring frames (cheap)
+    Blend,
+    /// Estimate motion to synthesize new frames (smooth, CPU heavy)
+    MotionCompensated,
+}
+
+impl InterpolationMode {
+    /// Filter that raises the frame rate so the slowed video still plays at `frame_rate`
+    pub(crate) fn filter(self, frame_rate: f64, speed: f32) -> Option<String> {
+        let mode = match self {
+            Self::Off => return None,
+            Self::Blend => "blend",
+            Self::MotionCompensated => "mci",
+        };
+        if speed >= 1.0 || frame_rate <= 0.0 {
+            return None;
+        }
+        Some(format!(
+            "minterpolate=fps={:.3}:mi_mode={}",
+            frame_rate / f64::from(speed),
+            mode
+        ))
+    }
+}
+
+/// Filter shifting the pitch of audio at `rate` by `semitones` without changing its
+/// tempo: play it faster or slower, then stretch it back to the original length
+pub(crate) fn pitch_filter(rate: u32, semitones: f32) -> Option<String> {
+    if semitones.abs() < 0.01 || rate == 0 {
+        return None;
+    }
+    let factor = 2f64.powf(f64::from(semitones) / 12.0);
+    Some(format!(
+        "asetrate={},aresample={},atempo={:.6}",
+        (f64::from(rate) * factor).round(),
+        rate,
+        1.0 / factor
+    ))
+}
+
+/// An FFmpeg filtergraph fed with decoded frames, e.g. "hflip,eq=contrast=1.2"
+pub struct FrameFilter {
+    _graph: Graph, // Owns the filter contexts below
+    input: filter::Context,
+    output: filter::Context,
+}
+
+impl FrameFilter {
+    /// Build a video filtergraph for frames from `decoder`
+    pub fn video(spec: &str, decoder: &decoder::Video, time_base: Rational) -> Result<Self> {
+        let aspect = decoder.aspect_ratio();
+        let aspect = if aspect.numerator() > 0 {
+            aspect
+        } else {
+            Rational::new(1, 1)
+        };
+        let args = format!(
+            "video_size={}x{}:pix_fmt={}:time_base={}:pixel_aspect={}",
+            decoder.width(),
+            decoder.height(),
+            decoder.format().descriptor().map_or("", |d| d.name()),
+            time_base,
+            aspect
+        );
+        Self::new("buffer", "buffersink", &args, spec, |_| {})
+    }
+
+    /// Build an audio filtergraph for frames from `decoder`.
+    /// `configure_sink` sets the output format the graph converts to.
+    pub fn audio(
+        spec: &str,
+        decoder: &decoder::Audio,
+        time_base: Rational,
+        configure_sink: impl FnOnce(&mut filter::Context),
+    ) -> Result<Self> {
+        let args = format!(
+            "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
+            time_base,
+            decoder.rate(),
+            decoder.format().name(),
+            channel_layout(decoder).bits()
+        );
+        Self::new("abuffer", "abuffersink", &args, spec, configure_sink)
+    }
+
+    fn new(
+        source: &str,
+        sink: &str,
+        args: &str,
+        spec: &str,
+        configure_sink: impl FnOnce(&mut filter::Context),
+    ) -> Result<Self> {
+        let mut graph = Graph::new();
+        let find = |name| filter::find(name).ok_or_else(|| anyhow!("{} filter not found", name));
+        let input = graph.add(&find(source)?, "in", args)?;
+        let mut output = graph.add(&find(sink)?, "out", "")?;
+        configure_sink(&mut output);
+
+        graph
+            .output("in", 0)?
+            .input("out", 0)?
+            .parse(spec)
+            .with_context(|| format!("Invalid filtergraph \"{}\"", spec))?;
+        graph.validate()?;
+
+        Ok(Self {
+            _graph: graph,
+            input,
+            output,
+        })
+    }
+
+    /// Feed a decoded frame into the graph
+    pub fn push(&mut self, frame: &Frame) -> Result<()> {
+        self.input.source().add(frame)?;
+        Ok(())
+    }
+
+    /// Signal end of stream so buffered frames can be pulled
+    pub fn flush(&mut self) -> Result<()> {
+        self.input.source().flush()?;
+        Ok(())
+    }
+
+    /// Take the next filtered frame, if one is ready
+    pub fn pull(&mut self, frame: &mut Frame) -> bool {
+        self.output.sink().frame(frame).is_ok()
+    }
+
+    /// Emit audio frames of exactly `samples` samples (for fixed frame size encoders)
+    pub fn set_frame_size(&mut self, samples: u32) {
+        self.output.sink().set_frame_size(samples);
+    }
+
+    /// Time base of the filtered frames
+    pub fn time_base(&mut self) -> Rational {
+        self.output.sink().time_base()
+    }
+}
+
+/// The decoder's channel layout, or the default layout for its channel count if unspecified
+pub fn channel_layout(decoder: &decoder::Audio) -> ChannelLayout {
+    let layout = decoder.channel_layout();
+    if layout.bits() == 0 {
+        ChannelLayout::default(i32::from(decoder.channels()))
+    } else {
+        layout
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use super::sequence;
+
+/// Extensions of video files picked up when browsing a folder
+const VIDEO_EXTENSIONS: [&str; 12] = [
+    "mp4", "m4v", "mkv", "webm", "avi", "mov", "wmv", "flv", "ts", "mts", "m2ts", "mpg",
+];
+
+/// Playlist and HLS extensions, accepted without reading the file
+const PLAYLIST_EXTENSIONS: [&str; 3] = ["m3u", "m3u8", "pls"];
+
+/// Whether the path is a video file by its extension
+fn is_video_file(path: &Path) -> bool {
+    has_extension(path, &VIDEO_EXTENSIONS)
+}
+
+/// Whether the player can likely open the path: a video, image, image folder
+/// or playlist. Checks extensions only, so it is cheap enough for drag hover.
+pub fn is_supported_media(path: &Path) -> bool {
+    is_video_file(path)
+        || has_extension(path, &PLAYLIST_EXTENSIONS)
+        || sequence::is_image_file(path)
+        || sequence::is_image_sequence(path)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// The video or image file before (`step` -1) or after (`step` 1) `path` in its
+/// folder, by name. Works even if `path` itself was deleted in the meantime.
+pub fn sibling_file(path: &Path, step: isize) -> Option<PathBuf> {
+    let mut siblings: Vec<PathBuf> = std::fs::read_dir(path.parent()?)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|sibling| {
+            sibling.is_file() && (is_video_file(sibling) || sequence::is_image_file(sibling))
+        })
+        .collect();
+    siblings.sort();
+
+    let index = match siblings.binary_search_by(|sibling| sibling.as_path().cmp(path)) {
+        Ok(index) => index.checked_add_signed(step)?,
+        // Between two files: the one after is at the insertion point
+        Err(index) if step > 0 => index + (step as usize - 1),
+        Err(index) => index.checked_add_signed(step)?,
+    };
+    siblings.get(index).cloned()
+}
@@ -0,0 +1,91 @@
+use egui::ColorImage;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use super::frame_metadata::FrameMetadata;
+
+/// Memory kept for recently shown frames by default (bytes)
+pub const DEFAULT_FRAME_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// A frame as it was shown
+pub(crate) struct CachedFrame {
+    pub pts: f64,
+    pub image: Arc<ColorImage>,
+    pub metadata: FrameMetadata,
+}
+
+/// The most recently shown run of consecutive frames, in PTS order and
+/// within a memory cap, so stepping back over them needs no decoding
+pub(crate) struct FrameCache {
+    frames: VecDeque<CachedFrame>,
+    bytes: usize,
+    capacity: usize,
+}
+
+impl FrameCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            bytes: 0,
+            capacity,
+        }
+    }
+
+    /// Change the memory cap, dropping the oldest frames past it. 0 disables the cache.
+    pub fn set_capacity(&mut self, bytes: usize) {
+        self.capacity = bytes;
+        self.trim();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Add a frame just shown. A frame before the last one, or more than
+    /// `max_gap` seconds after it, starts a new run, as the frames in between
+    /// were never shown. Frames already cached are left as they are.
+    pub fn push(&mut self, frame: CachedFrame, max_gap: f64) {
+        if self.capacity == 0 || self.frames.iter().any(|cached| cached.pts == frame.pts) {
+            return;
+        }
+        let follows = self
+            .frames
+            .back()
+            .is_some_and(|last| frame.pts > last.pts && frame.pts - last.pts <= max_gap);
+        if !follows {
+            self.clear();
+        }
+        self.bytes += size_of(&frame.image);
+        self.frames.push_back(frame);
+        self.trim();
+    }
+
+    /// The frame shown at `target`: the last one at or before it, as long as
+    /// the next frame is cached too, or `interval` past the last one
+    pub fn frame_at(&self, target: f64, interval: f64) -> Option<&CachedFrame> {
+        let index = self.frames.iter().rposition(|frame| frame.pts <= target)?;
+        let end = match self.frames.get(index + 1) {
+            Some(next) => next.pts,
+            None => self.frames[index].pts + interval,
+        };
+        (target < end).then(|| &self.frames[index])
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.bytes = 0;
+    }
+
+    fn trim(&mut self) {
+        while self.bytes > self.capacity {
+            let Some(frame) = self.frames.pop_front() else {
+                break;
+            };
+            self.bytes -= size_of(&frame.image);
+        }
+    }
+}
+
+fn size_of(image: &ColorImage) -> usize {
+    image.pixels.len() * 4
+}
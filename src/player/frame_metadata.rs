@@ -0,0 +1,144 @@
+use ffmpeg_next::frame::side_data::Type;
+use ffmpeg_next::frame::Frame;
+use std::sync::Arc;
+
+/// Side data carried by a decoded video frame, e.g. for dashcam and action
+/// camera overlays. Fields the frame does not carry are None.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameMetadata {
+    /// SMPTE timecode from SEI messages or the GOP header, e.g. "01:00:00:00"
+    /// (";" before the frames when drop-frame)
+    pub timecode: Option<String>,
+    /// HDR10 mastering display color volume
+    pub mastering_display: Option<MasteringDisplay>,
+    /// HDR10 content light level as (MaxCLL, MaxFALL) in cd/m²
+    pub content_light_level: Option<(u32, u32)>,
+    /// Unregistered user data SEI: a 16-byte UUID followed by the payload
+    pub sei_unregistered: Option<Vec<u8>>,
+    /// GoPro GPMF telemetry (GPS, accelerometer, gyroscope) covering the frame,
+    /// as the raw KLV payload of the camera's metadata track
+    pub gpmf: Option<Arc<[u8]>>,
+}
+
+impl FrameMetadata {
+    /// Check if the frame carried none of the side data
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Mastering display metadata (SMPTE ST 2086)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MasteringDisplay {
+    /// CIE 1931 xy chromaticity of the red, green and blue primaries
+    pub primaries: Option<[[f64; 2]; 3]>,
+    /// CIE 1931 xy chromaticity of the white point
+    pub white_point: Option<[f64; 2]>,
+    /// Minimum and maximum luminance in cd/m²
+    pub luminance: Option<(f64, f64)>,
+}
+
+/// Read the side data of a decoded frame
+pub(crate) fn read_frame_metadata(frame: &Frame) -> FrameMetadata {
+    let timecode = frame
+        .side_data(Type::S12M_TIMECODE)
+        .and_then(|data| smpte_timecode(data.data()))
+        .or_else(|| {
+            frame
+                .side_data(Type::GOPTimecode)
+                .and_then(|data| gop_timecode(data.data()))
+        });
+    FrameMetadata {
+        timecode,
+        mastering_display: frame
+            .side_data(Type::MasteringDisplayMetadata)
+            .and_then(|data| mastering_display(data.data())),
+        content_light_level: frame
+            .side_data(Type::ContentLightLevel)
+            .and_then(|data| content_light_level(data.data())),
+        sei_unregistered: frame
+            .side_data(Type::SEI_UNREGISTERED)
+            .map(|data| data.data().to_vec()),
+        gpmf: None,
+    }
+}
+
+/// Native-endian 32-bit words of a side data struct
+fn words(data: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    data.chunks_exact(4)
+        .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+}
+
+fn bcd(value: u32) -> u32 {
+    (value >> 4) * 10 + (value & 0xf)
+}
+
+fn format_timecode(hours: u32, minutes: u32, seconds: u32, frames: u32, drop: bool) -> String {
+    let separator = if drop { ';' } else { ':' };
+    format!(
+        "{:02}:{:02}:{:02}{}{:02}",
+        hours, minutes, seconds, separator, frames
+    )
+}
+
+/// First timecode of an S12M side data array: a count followed by up to three
+/// binary coded SMPTE ST 12-1 timecodes
+fn smpte_timecode(data: &[u8]) -> Option<String> {
+    let mut words = words(data);
+    if words.next()? == 0 {
+        return None;
+    }
+    let tc = words.next()?;
+    Some(format_timecode(
+        bcd(tc & 0x3f),
+        bcd(tc >> 8 & 0x7f),
+        bcd(tc >> 16 & 0x7f),
+        bcd(tc >> 24 & 0x3f),
+        tc & 1 << 30 != 0,
+    ))
+}
+
+/// MPEG-1/2 GOP header timecode, stored as a 64-bit integer of 25 bits
+fn gop_timecode(data: &[u8]) -> Option<String> {
+    let bytes: [u8; 8] = data.get(..8)?.try_into().ok()?;
+    let tc = i64::from_ne_bytes(bytes) as u32;
+    Some(format_timecode(
+        tc >> 19 & 0x1f,
+        tc >> 13 & 0x3f,
+        tc >> 6 & 0x3f,
+        tc & 0x3f,
+        tc >> 24 & 1 != 0,
+    ))
+}
+
+/// AVMasteringDisplayMetadata: ten rationals, then the has_primaries and
+/// has_luminance flags
+fn mastering_display(data: &[u8]) -> Option<MasteringDisplay> {
+    let words: Vec<u32> = words(data).collect();
+    if words.len() < 22 {
+        return None;
+    }
+    let rational = |index: usize| {
+        let den = words[index * 2 + 1] as i32;
+        (den != 0).then(|| f64::from(words[index * 2] as i32) / f64::from(den))
+    };
+    let point = |index: usize| Some([rational(index)?, rational(index + 1)?]);
+    let has_primaries = words[20] != 0;
+    let has_luminance = words[21] != 0;
+    Some(MasteringDisplay {
+        primaries: has_primaries
+            .then(|| Some([point(0)?, point(2)?, point(4)?]))
+            .flatten(),
+        white_point: has_primaries.then(|| point(6)).flatten(),
+        luminance: has_luminance
+            .then(|| Some((rational(8)?, rational(9)?)))
+            .flatten(),
+    })
+}
+
+/// AVContentLightMetadata: MaxCLL and MaxFALL
+fn content_light_level(data: &[u8]) -> Option<(u32, u32)> {
+    let mut words = words(data);
+    Some((words.next()?, words.next()?))
+}
@@ -0,0 +1,161 @@
+use egui::Context;
+use std::time::Duration;
+
+use super::VideoPlayer;
+
+/// Followers drifting further than this from the master are re-seeked (seconds)
+const MAX_DRIFT: f64 = 0.25;
+
+/// Several players driven off one master clock, e.g. multi-camera recordings.
+///
+/// The first player added is the master; its audio clock decides which frame
+/// every other player displays, so all videos stay frame-locked.
+#[derive(Default)]
+pub struct PlayerGroup {
+    players: Vec<VideoPlayer>,
+}
+
+impl PlayerGroup {
+    /// Create an empty group
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a player to the group, returning its index.
+    /// The new player is paused and moved to the master position.
+    pub fn add(&mut self, mut player: VideoPlayer) -> usize {
+        if let Some(master) = self.players.first() {
+            player.pause();
+            player.seek(master.position());
+            if master.is_playing() {
+                player.play();
+            }
+        }
+        self.players.push(player);
+        self.players.len() - 1
+    }
+
+    /// Remove a player from the group. Removing index 0 promotes the next player to master.
+    pub fn remove(&mut self, index: usize) -> Option<VideoPlayer> {
+        (index < self.players.len()).then(|| self.players.remove(index))
+    }
+
+    /// Get all players, master first
+    #[must_use]
+    pub fn players(&self) -> &[VideoPlayer] {
+        &self.players
+    }
+
+    /// Get a player by index
+    #[must_use]
+    pub fn player(&self, index: usize) -> Option<&VideoPlayer> {
+        self.players.get(index)
+    }
+
+    /// Get a mutable player by index
+    pub fn player_mut(&mut self, index: usize) -> Option<&mut VideoPlayer> {
+        self.players.get_mut(index)
+    }
+
+    /// Number of players in the group
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Check if the group has no players
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Start or resume playback of all players
+    pub fn play(&mut self) {
+        for player in &mut self.players {
+            player.play();
+        }
+    }
+
+    /// Pause all players
+    pub fn pause(&mut self) {
+        for player in &mut self.players {
+            player.pause();
+        }
+    }
+
+    /// Stop all players and seek to beginning
+    pub fn stop(&mut self) {
+        for player in &mut self.players {
+            player.stop();
+        }
+    }
+
+    /// Seek all players to the same position
+    pub fn seek(&mut self, position: Duration) {
+        for player in &mut self.players {
+            player.seek(position);
+        }
+    }
+
+    /// Mute or unmute a single player's audio
+    pub fn set_muted(&mut self, index: usize, muted: bool) {
+        if let Some(player) = self.players.get_mut(index) {
+            player.set_muted(muted);
+        }
+    }
+
+    /// Check if the group is playing (follows the master)
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.players.first().is_some_and(VideoPlayer::is_playing)
+    }
+
+    /// Check if any player is still seeking
+    #[must_use]
+    pub fn is_seeking(&self) -> bool {
+        self.players.iter().any(VideoPlayer::is_seeking)
+    }
+
+    /// Get the master playback position
+    #[must_use]
+    pub fn position(&self) -> Duration {
+        self.players
+            .first()
+            .map_or(Duration::ZERO, VideoPlayer::position)
+    }
+
+    /// Get the master duration
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.players
+            .first()
+            .map_or(Duration::ZERO, VideoPlayer::duration)
+    }
+
+    /// Update all players (call each frame)
+    pub fn update(&mut self, ctx: &Context) {
+        let Some((master, followers)) = self.players.split_first_mut() else {
+            return;
+        };
+
+        master.update(ctx);
+        let master_position = master.position();
+        let master_time = master_position.as_secs_f64();
+        // Show follower frames in step with the master's latency-compensated video
+        let video_time = (master_time - master.output_latency() as f64 / 1000.0).max(0.0);
+
+        for follower in followers {
+            if !master.is_playing() && follower.is_playing() {
+                // Master reached the end or was paused directly
+                follower.pause();
+            } else if follower.is_playing() && !follower.is_seeking() && !master.is_seeking() {
+                // Followers play their own audio, so correct accumulated drift
+                let drift = (follower.position().as_secs_f64() - master_time).abs();
+                if drift > MAX_DRIFT {
+                    follower.seek(master_position);
+                }
+            }
+            follower.update_synced(ctx, Some(video_time));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+/// Seeks this close together count as one, e.g. a timeline drag and the
+/// exact seek that settles it
+const MERGE_WINDOW: Duration = Duration::from_millis(1000);
+/// Oldest positions are forgotten beyond this many
+const MAX_ENTRIES: usize = 100;
+
+/// Positions seeked away from, for undoing and redoing seeks (seconds)
+#[derive(Default)]
+pub struct SeekHistory {
+    undo: Vec<f64>,
+    redo: Vec<f64>,
+    last_seek: Option<Instant>,
+}
+
+impl SeekHistory {
+    /// Remember `from` before a seek leaves it. Starting a new seek drops the
+    /// redo positions.
+    pub fn record(&mut self, from: f64) {
+        let merged = self
+            .last_seek
+            .is_some_and(|time| time.elapsed() < MERGE_WINDOW);
+        self.last_seek = Some(Instant::now());
+        if merged {
+            return;
+        }
+        if self.undo.len() == MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+        self.undo.push(from);
+        self.redo.clear();
+    }
+
+    /// Take the position before the last seek, remembering `current` for redo
+    pub fn undo(&mut self, current: f64) -> Option<f64> {
+        let position = self.undo.pop()?;
+        self.redo.push(current);
+        self.last_seek = None;
+        Some(position)
+    }
+
+    /// Take the position the last undo left, remembering `current` for undo
+    pub fn redo(&mut self, current: f64) -> Option<f64> {
+        let position = self.redo.pop()?;
+        self.undo.push(current);
+        self.last_seek = None;
+        Some(position)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+/// How long a peak stays marked before it starts to fall
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+/// Fall of a held peak once the hold is over (linear level per second)
+const PEAK_FALL: f32 = 0.5;
+/// How long a clip stays flagged, so the indicator is seen
+const CLIP_HOLD: Duration = Duration::from_secs(2);
+/// Samples at or above this level after volume are clipped by the output
+const FULL_SCALE: f32 = 1.0;
+
+/// Levels of the audio being played, as linear amplitudes where 1.0 is full
+/// scale. Stereo: mono audio shows on both channels, and only the first two of
+/// a multichannel layout are measured.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioLevels {
+    /// RMS level of the last ~85 ms, after volume
+    pub rms: [f32; 2],
+    /// Highest sample of the last ~85 ms, after volume
+    pub peak: [f32; 2],
+    /// Highest recent peak, held for 1.5 s before falling
+    pub peak_hold: [f32; 2],
+    /// A sample reached full scale in the last two seconds
+    pub clipping: [bool; 2],
+}
+
+impl AudioLevels {
+    /// Convert a linear level to decibels relative to full scale
+    #[must_use]
+    pub fn to_db(level: f32) -> f32 {
+        20.0 * level.max(1e-6).log10()
+    }
+
+    /// Check if either channel clipped recently
+    #[must_use]
+    pub fn is_clipping(&self) -> bool {
+        self.clipping[0] || self.clipping[1]
+    }
+}
+
+/// Peak hold and clip state carried between measurements
+pub(crate) struct LevelMeter {
+    levels: AudioLevels,
+    held_at: [Instant; 2],
+    clipped_at: [Option<Instant>; 2],
+    measured: Instant,
+}
+
+impl LevelMeter {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            levels: AudioLevels::default(),
+            held_at: [now; 2],
+            clipped_at: [None; 2],
+            measured: now,
+        }
+    }
+
+    #[must_use]
+    pub fn levels(&self) -> AudioLevels {
+        self.levels
+    }
+
+    /// Measure the recently played `samples` (interleaved) as heard at `gain`.
+    /// Pass no samples while nothing plays so the levels fall to silence.
+    pub fn measure(&mut self, samples: &[f32], channels: u16, gain: f32) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.measured).as_secs_f32();
+        self.measured = now;
+
+        let channels = usize::from(channels.max(1));
+        let mut sum = [0.0f32; 2];
+        let mut peak = [0.0f32; 2];
+        let mut frames = 0;
+        for frame in samples.chunks_exact(channels) {
+            for (side, &sample) in frame.iter().take(2).enumerate() {
+                let level = (sample * gain).abs();
+                sum[side] += level * level;
+                peak[side] = peak[side].max(level);
+            }
+            frames += 1;
+        }
+        if channels == 1 {
+            sum[1] = sum[0];
+            peak[1] = peak[0];
+        }
+
+        for side in 0..2 {
+            self.levels.rms[side] = if frames > 0 {
+                (sum[side] / frames as f32).sqrt()
+            } else {
+                0.0
+            };
+            self.levels.peak[side] = peak[side];
+
+            let held = &mut self.levels.peak_hold[side];
+            if peak[side] >= *held {
+                *held = peak[side];
+                self.held_at[side] = now;
+            } else if now.duration_since(self.held_at[side]) > PEAK_HOLD {
+                *held = (*held - PEAK_FALL * elapsed).max(peak[side]);
+            }
+
+            if peak[side] >= FULL_SCALE {
+                self.clipped_at[side] = Some(now);
+            }
+            self.levels.clipping[side] =
+                self.clipped_at[side].is_some_and(|time| now.duration_since(time) < CLIP_HOLD);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::diagnostics::{Resource, ResourceGuard};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+use super::audio::AudioSource;
+use super::output::{AudioOutput, AudioOutputFormat, SnippetOutput};
+
+/// Fills device buffers from the player's source and scrub snippets, for
+/// backends that hand out a buffer to fill instead of pulling a rodio source
+#[derive(Clone, Default)]
+pub(crate) struct Mixer(Arc<Mutex<MixerState>>);
+
+struct MixerState {
+    source: Option<AudioSource>,
+    playing: bool,
+    volume: f32,
+    speed: f32,
+    /// Stereo source frames either side of the output position, and the
+    /// position between them (0.0 to 1.0)
+    previous: [f32; 2],
+    next: [f32; 2],
+    phase: f32,
+    snippet: Vec<f32>,
+    snippet_position: usize,
+    snippet_volume: f32,
+}
+
+impl Default for MixerState {
+    fn default() -> Self {
+        Self {
+            source: None,
+            playing: false,
+            volume: 1.0,
+            speed: 1.0,
+            previous: [0.0; 2],
+            next: [0.0; 2],
+            phase: 1.0,
+            snippet: Vec::new(),
+            snippet_position: 0,
+            snippet_volume: 1.0,
+        }
+    }
+}
+
+impl MixerState {
+    /// Next stereo output frame. Paused sources are not read, so their clock holds.
+    fn next_frame(&mut self) -> [f32; 2] {
+        let mut frame = [0.0; 2];
+        if let (true, Some(source)) = (self.playing, self.source.as_mut()) {
+            // Linear interpolation between source frames resamples for the speed
+            while self.phase >= 1.0 {
+                self.previous = self.next;
+                self.next = [source.next().unwrap_or(0.0), source.next().unwrap_or(0.0)];
+                self.phase -= 1.0;
+            }
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let (from, to) = (self.previous[channel], self.next[channel]);
+                *sample = (from + (to - from) * self.phase) * self.volume;
+            }
+            self.phase += self.speed;
+        }
+        if let Some(snippet) = self
+            .snippet
+            .get(self.snippet_position..self.snippet_position + 2)
+        {
+            frame[0] += snippet[0] * self.snippet_volume;
+            frame[1] += snippet[1] * self.snippet_volume;
+            self.snippet_position += 2;
+        }
+        frame
+    }
+}
+
+impl Mixer {
+    /// Fill an interleaved buffer of `channels` channels. Stereo goes to the
+    /// first two channels, or is averaged on mono devices.
+    pub fn fill(&self, out: &mut [f32], channels: u16) {
+        let mut state = self.0.lock();
+        for frame in out.chunks_mut(usize::from(channels.max(1))) {
+            let [left, right] = state.next_frame();
+            match frame {
+                [mono] => *mono = (left + right) * 0.5,
+                [first, second, rest @ ..] => {
+                    *first = left;
+                    *second = right;
+                    rest.fill(0.0);
+                }
+                [] => {}
+            }
+        }
+    }
+}
+
+impl SnippetOutput for Mixer {
+    fn play(&mut self, samples: Vec<f32>, volume: f32) {
+        let mut state = self.0.lock();
+        state.snippet = samples;
+        state.snippet_position = 0;
+        state.snippet_volume = volume;
+    }
+}
+
+/// A device filled by a `Mixer`, open as long as this lives
+pub(crate) struct MixerOutput<D> {
+    _device: D,
+    _guard: ResourceGuard,
+    mixer: Mixer,
+    format: AudioOutputFormat,
+}
+
+impl<D> MixerOutput<D> {
+    pub fn new(device: D, mixer: Mixer, format: AudioOutputFormat) -> Self {
+        Self {
+            _device: device,
+            _guard: ResourceGuard::new(Resource::AudioOutput),
+            mixer,
+            format,
+        }
+    }
+}
+
+impl<D> AudioOutput for MixerOutput<D> {
+    fn format(&self) -> AudioOutputFormat {
+        self.format
+    }
+
+    fn set_source(&mut self, source: AudioSource) {
+        self.mixer.0.lock().source = Some(source);
+    }
+
+    fn play(&self) {
+        self.mixer.0.lock().playing = true;
+    }
+
+    fn pause(&self) {
+        self.mixer.0.lock().playing = false;
+    }
+
+    fn set_volume(&self, volume: f32) {
+        self.mixer.0.lock().volume = volume;
+    }
+
+    fn set_speed(&self, speed: f32) {
+        self.mixer.0.lock().speed = speed;
+    }
+
+    fn snippets(&self) -> Box<dyn SnippetOutput> {
+        Box::new(self.mixer.clone())
+    }
+}
@@ -1,18 +1,90 @@
+mod analysis;
+#[cfg(feature = "libass")]
+mod ass;
+mod attachments;
 mod audio;
-mod circular_buffer;
+mod budget;
+mod builder;
+mod burst;
+mod cache;
+mod chapters;
 mod clock;
+mod color;
+mod config;
 mod decoder;
+mod effect;
+mod error;
+mod events;
+mod export;
+mod filter;
+mod folder;
+mod frame_cache;
+mod frame_metadata;
+mod group;
+mod history;
+mod levels;
+#[cfg(any(feature = "audio-cpal", feature = "audio-sdl"))]
+mod mixer;
+mod output;
+mod overlay;
+mod pipeline;
+mod playlist;
+mod record;
+#[cfg(feature = "remote")]
+mod remote;
+mod ring_buffer;
+mod scrub;
+mod sequence;
+mod settings;
+mod silence;
+mod sink;
+mod snapshot;
+mod stats;
+mod streams;
+mod subtitles;
+mod surface;
+mod tap;
+mod telemetry;
+pub mod thumbnail;
+mod thumbnail_track;
 mod video;
+mod visualizer;
+mod watch;
+mod waveform;
+mod worker;
 
-use anyhow::Result;
-use crossbeam_channel::{bounded, Receiver, Sender};
-use egui::{ColorImage, Context, TextureHandle, TextureOptions};
-use rodio::{OutputStream, OutputStreamHandle, Sink};
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender, TryRecvError};
+use egui::{
+    Color32, ColorImage, Context, FontData, FontFamily, Rect, TextureFilter, TextureHandle,
+    TextureId, TextureOptions,
+};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Seconds before the end of an item at which the next playlist item is preloaded
+const PRELOAD_AHEAD: f64 = 5.0;
+/// Default length of the volume ramps on play, pause and seek
+const DEFAULT_FADE_MS: u32 = 10;
+/// egui font family holding the fonts attached to the open media
+const ATTACHED_FONT_FAMILY: &str = "egui_video_attachments";
+/// Seconds past a cue's start that still count as on it, since seeks land on
+/// the frame at or after the start
+const SUBTITLE_SEEK_MARGIN: f64 = 0.1;
+/// Seconds into a cue within which going to the previous cue skips this one
+const PREVIOUS_SUBTITLE_GRACE: f64 = 1.0;
+/// How long the audio output may take no samples while playing before its
+/// device counts as lost
+const OUTPUT_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+/// Quiet parts are skipped or sped through up to this long before they end (seconds)
+const SILENCE_LEAD: f64 = 0.15;
+/// Default small and large jumps of the rewind and fast-forward controls
+const DEFAULT_SEEK_INCREMENTS: (Duration, Duration) =
+    (Duration::from_secs(5), Duration::from_secs(30));
 
 /// Volume level (0.0 to 1.0)
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -35,310 +107,3328 @@ impl Volume {
     }
 }
 
-use audio::AudioSource;
-use circular_buffer::CircularBuffer;
+use crate::diagnostics::{Resource, ResourceGuard, TrackedTexture};
+use crate::ui::controls::format_time;
+use crate::ui::telemetry::format_sample;
+use audio::{AudioBalance, AudioFade, AudioSource, FADE_RANGE_MS};
+use budget::FrameBudget;
+use cache::SharedCacheCounters;
 use clock::AudioClock;
-use decoder::{probe_media, start_decoder_thread, DecoderCommand};
-use video::VideoFrameQueue;
+use decoder::{DecoderCommand, MediaInfo, OpenRequests};
+use effect::SharedVideoEffects;
+use frame_cache::{CachedFrame, FrameCache};
+use history::SeekHistory;
+use levels::LevelMeter;
+use output::{open_output, AudioOutput};
+use overlay::{BurnIn, Corner};
+use pipeline::{retire_decoder, Pipeline, Standby};
+use scrub::ScrubPlayer;
+use serde::{Deserialize, Serialize};
+use settings::find_subtitle_file;
+use stats::PerformanceMonitor;
+use surface::{shuttle_deflection, shuttle_speed, ReverseShuttle};
+use tap::SharedAudioTap;
+use video::{VideoFrameQueue, DEFAULT_FRAME_INTERVAL};
+use visualizer::SampleHistory;
+use watch::{FileWatch, WATCH_INTERVAL};
+
+pub use attachments::Attachment;
+pub use budget::MemoryBudget;
+pub use builder::VideoPlayerBuilder;
+pub use cache::{CacheStats, NetworkCache};
+pub use chapters::Chapter;
+pub use color::{ColorManagement, ColorMatrix, ColorRange};
+pub use config::PlayerConfig;
+pub use decoder::{DecodedVideoFrame, DecoderOptions, RecoveryPolicy, ThreadPriority};
+pub use effect::VideoEffect;
+pub use error::PlayerError;
+pub use events::PlayerEvent;
+pub use export::AudioFormat;
+pub use filter::InterpolationMode;
+pub use folder::is_supported_media;
+pub use frame_metadata::{FrameMetadata, MasteringDisplay};
+pub use group::PlayerGroup;
+pub use levels::AudioLevels;
+pub use output::{AudioBackend, AudioOutputFormat};
+pub use overlay::OverlayFlags;
+pub use playlist::{ItemMetadata, Playlist, PlaylistItem, RepeatMode};
+#[cfg(feature = "remote")]
+pub use remote::RemoteServer;
+pub use scrub::ScrubAudio;
+pub use settings::Settings;
+pub use silence::{SilenceAction, SilenceSkip};
+pub use sink::FrameSink;
+pub use stats::{FrameIntervals, PlaybackStats};
+pub use streams::{StreamInfo, StreamKind};
+pub use subtitles::SubtitleCue;
+#[cfg(feature = "gamepad")]
+pub use surface::GamepadSurface;
+pub use surface::{ControlSurface, MediaKey, MediaKeySender, MediaKeys, SurfaceAction};
+pub use tap::AudioTap;
+pub use telemetry::TelemetrySample;
+pub use thumbnail_track::ThumbnailTrack;
+pub use video::{FramePacing, SeekMode, SyncPolicy};
+pub use visualizer::{Oscilloscope, Spectrogram, SpectrumBars, Visualizer};
+pub use waveform::Waveform;
 
 /// Display mode for video rendering
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DisplayMode {
     FitToWindow,
+    /// One video pixel per logical point (100% logical), so the video grows
+    /// with the UI scale and is resampled on hi-DPI displays
     NativeSize,
+    /// One video pixel per physical screen pixel (100% physical), sharp on
+    /// hi-DPI displays
+    NativePhysical,
+    /// Largest whole multiple of the native size that fits, for crisp pixel art
+    IntegerScale,
+}
+
+/// What is shown after `stop()`, which rewinds to the start
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum StopBehavior {
+    /// The first frame, as after opening
+    #[default]
+    ShowFirstFrame,
+    /// The frame shown when stopped, until playback restarts or a seek
+    ShowLastFrame,
+    /// Nothing: the texture shrinks to a single black pixel, releasing its
+    /// memory, for embeds that hide the player after stop
+    Clear,
+}
+
+/// Zoom and pan of the video in fit-to-window and integer scaling modes
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewTransform {
+    /// Magnification over the display mode's size, from 1.0 to `MAX_ZOOM`
+    pub zoom: f32,
+    /// Offset of the view center from the frame center, as a fraction of the zoomed frame
+    pub pan: [f32; 2],
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: [0.0, 0.0],
+        }
+    }
+}
+
+impl ViewTransform {
+    pub const MAX_ZOOM: f32 = 8.0;
+
+    /// Limit the zoom range and keep the view inside the frame
+    #[must_use]
+    pub fn clamped(self) -> Self {
+        let zoom = self.zoom.clamp(1.0, Self::MAX_ZOOM);
+        let limit = (1.0 - 1.0 / zoom) / 2.0;
+        Self {
+            zoom,
+            pan: self.pan.map(|pan| pan.clamp(-limit, limit)),
+        }
+    }
+
+    /// Whether the video is shown unzoomed
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How positions are shown on the player's time labels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TimeDisplayMode {
+    /// HH:MM:SS
+    #[default]
+    Standard,
+    /// HH:MM:SS.mmm
+    WithMillis,
+    /// SMPTE HH:MM:SS:FF, drop-frame (HH:MM:SS;FF) at 29.97 and 59.94 fps
+    Smpte,
+    /// Frame number from the start
+    FrameNumber,
 }
 
 /// Player state
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PlayerState {
     Stopped,
     Playing,
     Paused,
 }
 
+/// Why the decoder was asked to open a file, which decides how much of its
+/// info the player takes on once it arrives
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OpenKind {
+    /// Another file, from `replace_media`
+    Replace,
+    /// The same file with new options, from `reopen_decoder`
+    Reopen,
+}
+
 /// Main video player struct
 pub struct VideoPlayer {
     // State
     state: PlayerState,
     display_mode: DisplayMode,
+    view: ViewTransform,
+    time_display_mode: TimeDisplayMode,
+    texture_filter: TextureFilter,
+    color_management: ColorManagement,
     seeking: bool,
     seek_target: f64,
+    seek_mode: SeekMode,
+    seek_history: SeekHistory,
+    /// Target of relative jumps made while a seek was in flight, sent as one
+    /// seek once it lands
+    queued_jump: Option<f64>,
+    seek_increments: (Duration, Duration),
 
     // Media info
+    path: PathBuf,
     width: u32,
     height: u32,
     duration: f64,
+    // Part of the file treated as the whole media (seconds); positions,
+    // durations and seeks in the public API are relative to its start
+    range_start: f64,
+    range_end: Option<f64>,
+    frame_rate: f64,
+    still_image: bool,
+    seekable: bool,
+    attachments: Vec<Attachment>,
+    streams: Vec<StreamInfo>,
+    bit_rate: Option<u64>,
+    chapters: Vec<Chapter>,
+    attached_fonts_pending: bool,
+    subtitle_font: Option<FontFamily>,
 
     // Threading
+    recovery: RecoveryPolicy,
+    decoder_options: DecoderOptions,
+    deinterlace: bool,
+    frame_budget: FrameBudget,
     decoder_handle: Option<JoinHandle<()>>,
     command_sender: Sender<DecoderCommand>,
     stop_flag: Arc<AtomicBool>,
+    open_requests: OpenRequests,
+    /// Info reported by the decoder for the files it opens
+    info_receiver: Receiver<(u64, MediaInfo)>,
+    /// The open whose info is awaited; earlier ones were superseded
+    pending_open: Option<(u64, OpenKind)>,
 
     // Audio
-    _output_stream: OutputStream, // Keep alive
-    _stream_handle: OutputStreamHandle,
-    sink: Sink,
+    audio_backend: AudioBackend,
+    audio_output: Box<dyn AudioOutput>,
+    /// Output pulls last seen, and when they last changed
+    output_watch: (u64, Instant),
+    audio_device_lost: bool,
     clock: AudioClock,
+    fade: AudioFade,
+    balance: AudioBalance,
+    fade_out_at_end: bool,
+    audio_tap: SharedAudioTap,
+    video_effects: SharedVideoEffects,
+    volume: Volume,
+    muted: bool,
+    output_latency_ms: i64,
+    speed: f32,
+    pitch: f32,
+    // Control surface shuttle: the speed to return to after shuttling
+    // forwards, and the keyframe stepping state when shuttling backwards
+    shuttle_base_speed: Option<f32>,
+    reverse_shuttle: Option<ReverseShuttle>,
+    interpolation: InterpolationMode,
+    scrub_audio: ScrubAudio,
+    scrub_player: Option<ScrubPlayer>,
 
     // Video
     frame_queue: VideoFrameQueue,
-    texture: Option<TextureHandle>,
+    sync_policy: SyncPolicy,
+    performance: PerformanceMonitor,
+    texture: Option<TrackedTexture>,
+    /// Receives frames in place of the texture when set
+    frame_sink: Option<Box<dyn FrameSink>>,
+    current_frame: Option<Arc<ColorImage>>,
+    /// Recently shown frames, for stepping back without decoding
+    frame_cache: FrameCache,
+    /// A cached frame is shown, and the decoder is still where it was before
+    showing_cached: bool,
+    /// Side data of the frame presented last
+    frame_metadata: Option<FrameMetadata>,
+    /// Shown before the first frame of media without cover art
+    placeholder: Option<Arc<ColorImage>>,
+    /// Frame frozen for comparison, with its texture and position
+    reference: Option<(TrackedTexture, Arc<ColorImage>, Duration)>,
+    has_alpha: bool,
+    decoder_threads: usize,
+    checkerboard: bool,
+    stop_behavior: StopBehavior,
+    /// Keep the frame on screen rather than presenting the one a seek lands on,
+    /// after stopping with `ShowLastFrame` or `Clear`
+    hold_frame: bool,
+    /// Timestamp and number of the frame in the texture, as sent in `FramePresented`
+    presented: Option<(Duration, u64)>,
+    timecode_overlay: bool,
+    pixel_inspector: bool,
+    video_enabled: bool,
+    // Audio visualization shown while video is off, rendered at its own rate
+    sample_history: SampleHistory,
+    /// Peak hold and clip state, while level metering is on
+    level_meter: Option<LevelMeter>,
+    visualizer: Option<Box<dyn Visualizer>>,
+    visualizer_image: ColorImage,
+    visualizer_interval: Duration,
+    visualizer_rendered: Option<Instant>,
+
+    // Playlist and the prebuffered next item
+    playlist: Playlist,
+    standby: Option<Standby>,
+    // Item metadata probed in the background, and the paths already queued for it
+    metadata_sender: Sender<(PathBuf, ItemMetadata)>,
+    metadata_receiver: Receiver<(PathBuf, ItemMetadata)>,
+    metadata_requested: HashSet<PathBuf>,
+
+    // External subtitles (sorted by start) and live timing correction. The
+    // secondary track is shown alongside, e.g. a translation.
+    subtitles: Vec<SubtitleCue>,
+    /// The primary subtitles again when they are ASS/SSA, rendered with their styling
+    #[cfg(feature = "libass")]
+    ass_subtitles: Option<ass::AssSubtitles>,
+    secondary_subtitles: Vec<SubtitleCue>,
+    subtitle_delay_ms: i64,
+    subtitle_scale: f32,
+    subtitle_languages: Vec<String>,
+    prefer_forced_subtitles: bool,
+
+    // Audio waveform for the timeline, generated on request
+    waveform: Option<Waveform>,
+    waveform_receiver: Option<Receiver<Waveform>>,
+    // Thumbnail sprite sheet for hover previews, generated on request with
+    // its spacing and cache directory
+    thumbnails: Option<ThumbnailTrack>,
+    thumbnail_receiver: Option<Receiver<ThumbnailTrack>>,
+    thumbnail_texture: Option<TrackedTexture>,
+    thumbnail_request: Option<(Duration, Option<PathBuf>)>,
+
+    // Scene cuts (sorted), found by an opt-in background pass
+    scene_changes: Vec<Duration>,
+    scene_receiver: Option<Receiver<Duration>>,
+    scene_cancel: Arc<AtomicBool>,
+    track_splits: Vec<Duration>,
+    track_receiver: Option<Receiver<Duration>>,
+    track_cancel: Arc<AtomicBool>,
+    // Quiet parts skipped or sped through, found in the background
+    silence_skip: Option<SilenceSkip>,
+    silent_ranges: Vec<Range<Duration>>,
+    silence_receiver: Option<Receiver<Range<Duration>>>,
+    silence_cancel: Arc<AtomicBool>,
+    /// Speed to return to after a sped-up quiet part
+    silence_base_speed: Option<f32>,
+
+    // File watching
+    file_watch: Option<FileWatch>,
+
+    // Telemetry (sorted by timestamp)
+    telemetry_receiver: Receiver<TelemetrySample>,
+    cache_counters: SharedCacheCounters,
+    telemetry: Vec<TelemetrySample>,
 
     // Error reporting
-    error_receiver: Receiver<String>,
+    error_receiver: Receiver<PlayerError>,
+    event_sender: Sender<PlayerEvent>,
+    event_receiver: Receiver<PlayerEvent>,
+
+    // Diagnostics (released when the player is dropped)
+    _resource: ResourceGuard,
 }
 
 impl VideoPlayer {
     /// Open a video file and prepare for playback
-    pub fn open(path: &Path, ctx: Context) -> Result<Self> {
-        // Probe media file
-        let info = probe_media(path)?;
+    pub fn open(path: &Path, ctx: Context) -> Result<Self, PlayerError> {
+        Self::builder(path).build(ctx)
+    }
 
-        // Create audio clock
-        let clock = AudioClock::new(info.sample_rate, info.channels);
+    /// Start setting options for opening a video file, e.g.
+    /// `VideoPlayer::builder(&path).start_at(t).autoplay(true).build(ctx)`
+    pub fn builder(path: &Path) -> VideoPlayerBuilder {
+        VideoPlayerBuilder::new(path)
+    }
+
+    /// Play media piped to standard input, e.g. `ffmpeg ... -f matroska - | app -`.
+    /// Pipes play from start to end only; see `is_seekable`.
+    pub fn open_stdin(ctx: Context) -> Result<Self, PlayerError> {
+        Self::open(Path::new(sequence::STDIN_URL), ctx)
+    }
+
+    /// Open a video file with a custom decoder error recovery policy
+    #[deprecated(note = "use `VideoPlayer::builder(path).recovery(recovery).build(ctx)`")]
+    pub fn open_with_recovery(
+        path: &Path,
+        ctx: Context,
+        recovery: RecoveryPolicy,
+    ) -> Result<Self, PlayerError> {
+        Self::builder(path).recovery(recovery).build(ctx)
+    }
+
+    /// Open a video file with custom player options
+    #[deprecated(note = "use `VideoPlayer::builder(path).config(config).build(ctx)`")]
+    pub fn open_with_config(
+        path: &Path,
+        ctx: Context,
+        config: PlayerConfig,
+    ) -> Result<Self, PlayerError> {
+        Self::builder(path).config(config).build(ctx)
+    }
+
+    /// Open a video file with the options of a builder.
+    /// M3U/M3U8/PLS playlist files open their first entry and queue the rest,
+    /// and `-` or `pipe:N` read from a pipe.
+    pub(crate) fn open_configured(
+        path: &Path,
+        ctx: Context,
+        config: PlayerConfig,
+    ) -> Result<Self, PlayerError> {
+        if playlist::is_playlist_file(path) {
+            let playlist = Playlist::load(path)?;
+            let first = playlist.current().unwrap_or(path).to_path_buf();
+            let mut player = Self::open_configured(&first, ctx, config)?;
+            player.playlist = playlist;
+            return Ok(player);
+        }
+
+        // Create event channel
+        let (event_sender, event_receiver) = bounded(64);
 
-        // Create audio output
-        let (output_stream, stream_handle) = OutputStream::try_default()?;
-        let sink = Sink::try_new(&stream_handle)?;
+        // Channel for playlist item metadata probed in the background
+        let (metadata_sender, metadata_receiver) = unbounded();
 
-        // Create circular buffer for audio (about 1 second of buffer)
-        let buffer_size = info.sample_rate as usize * info.channels as usize * 2;
-        let audio_buffer = CircularBuffer::new(buffer_size);
+        // Shared slot for an application audio tap
+        let audio_tap = SharedAudioTap::default();
 
-        // Create audio source and add to sink
-        let audio_source = AudioSource::new(audio_buffer.clone(), clock.clone());
-        sink.append(audio_source);
-        sink.pause(); // Start paused
+        // Application video effects, run on each decoded frame
+        let video_effects = SharedVideoEffects::default();
 
-        // Create video frame channel
-        let (video_sender, video_receiver) = bounded(30);
-        let frame_queue = VideoFrameQueue::new(video_receiver, 30);
+        // Track preferences from the settings profile choose the streams to decode
+        let mut decoder_options = config.decoder;
+        let mut deinterlace = false;
+        if let Some(ref settings) = config.settings {
+            decoder_options.audio_languages = settings.audio_languages.clone();
+            deinterlace = settings.deinterlace;
+        }
 
-        // Create command channel
-        let (command_sender, command_receiver) = bounded(16);
+        // Open the audio device first, so decoding targets its rate
+        let (mut audio_output, fallback) = open_output(config.audio_backend)?;
+        if let Some(reason) = fallback {
+            let _ = event_sender.try_send(PlayerEvent::AudioOutputFallback {
+                format: audio_output.format(),
+                reason,
+            });
+        }
 
-        // Create error channel
-        let (error_sender, error_receiver) = bounded(4);
+        // Queued frames count against the budget shared with other players
+        let frame_budget = config.memory_budget.join();
 
-        // Start decoder thread
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        let decoder_handle = start_decoder_thread(
+        // Probe the file and start its decoder thread
+        let pipeline = Pipeline::start(
             path,
-            video_sender,
-            audio_buffer,
-            command_receiver,
-            clock.clone(),
-            stop_flag.clone(),
-            error_sender,
+            audio_output.format().sample_rate,
+            audio_tap.clone(),
+            video_effects.clone(),
+            event_sender.clone(),
+            config.recovery,
+            effective_decoder_options(&decoder_options, deinterlace, None),
+            frame_budget.clone(),
         )?;
+        let info = &pipeline.info;
 
-        // Create initial texture
-        let texture = ctx.load_texture(
-            "video_frame",
-            ColorImage::new([info.width as usize, info.height as usize], egui::Color32::BLACK),
-            TextureOptions::LINEAR,
-        );
+        // Create audio source and start the output on it, paused
+        let fade = AudioFade::new(DEFAULT_FADE_MS);
+        let balance = AudioBalance::default();
+        let sample_history = SampleHistory::default();
+        let audio_source = AudioSource::new(
+            pipeline.audio_consumer,
+            pipeline.clock.clone(),
+            fade.clone(),
+        )
+        .with_balance(balance.clone())
+        .with_history(sample_history.clone());
+        audio_output.set_source(audio_source);
+
+        pipeline.clock.set_video_master(!config.sync.audio_master);
+        let frame_queue = VideoFrameQueue::new(pipeline.video_receiver, 30, config.sync.pacing);
+
+        // Create initial texture, named uniquely so several players can share a Context
+        let texture_name = config.texture_name.unwrap_or_else(|| {
+            static NEXT_TEXTURE: AtomicU64 = AtomicU64::new(0);
+            format!(
+                "video_frame_{}",
+                NEXT_TEXTURE.fetch_add(1, Ordering::Relaxed)
+            )
+        });
+        let texture = TrackedTexture::new(ctx.load_texture(
+            texture_name,
+            ColorImage::new(
+                [info.width.max(1) as usize, info.height.max(1) as usize],
+                egui::Color32::BLACK,
+            ),
+            texture_options(config.texture_filter),
+        ));
 
         let mut player = Self {
             state: PlayerState::Stopped,
-            display_mode: DisplayMode::FitToWindow,
+            display_mode: config.display_mode,
+            view: ViewTransform::default(),
+            time_display_mode: TimeDisplayMode::Standard,
+            texture_filter: config.texture_filter,
+            color_management: ColorManagement::Auto,
             seeking: false,
             seek_target: 0.0,
+            seek_mode: SeekMode::Exact,
+            seek_history: SeekHistory::default(),
+            queued_jump: None,
+            seek_increments: DEFAULT_SEEK_INCREMENTS,
+            path: path.to_path_buf(),
             width: info.width,
             height: info.height,
             duration: info.duration,
-            decoder_handle: Some(decoder_handle),
-            command_sender,
-            stop_flag,
-            _output_stream: output_stream,
-            _stream_handle: stream_handle,
-            sink,
-            clock,
+            range_start: 0.0,
+            range_end: None,
+            frame_rate: info.frame_rate,
+            still_image: info.still_image,
+            seekable: info.seekable,
+            attachments: info.attachments.clone(),
+            streams: info.streams.clone(),
+            bit_rate: info.bit_rate,
+            chapters: info.chapters.clone(),
+            attached_fonts_pending: true,
+            subtitle_font: None,
+            recovery: config.recovery,
+            decoder_options,
+            deinterlace,
+            frame_budget,
+            decoder_handle: Some(pipeline.decoder_handle),
+            command_sender: pipeline.command_sender,
+            stop_flag: pipeline.stop_flag,
+            open_requests: pipeline.open_requests,
+            info_receiver: pipeline.info_receiver,
+            pending_open: None,
+            audio_backend: config.audio_backend,
+            audio_output,
+            output_watch: (0, Instant::now()),
+            audio_device_lost: false,
+            clock: pipeline.clock,
+            fade,
+            balance,
+            fade_out_at_end: false,
+            audio_tap,
+            video_effects,
+            volume: Volume(1.0),
+            muted: false,
+            output_latency_ms: 0,
+            speed: 1.0,
+            pitch: 0.0,
+            shuttle_base_speed: None,
+            reverse_shuttle: None,
+            interpolation: InterpolationMode::Off,
+            scrub_audio: ScrubAudio::Off,
+            scrub_player: None,
             frame_queue,
+            sync_policy: config.sync,
+            performance: PerformanceMonitor::default(),
             texture: Some(texture),
-            error_receiver,
+            frame_sink: None,
+            current_frame: None,
+            frame_cache: FrameCache::new(config.frame_cache_bytes),
+            showing_cached: false,
+            frame_metadata: None,
+            reference: None,
+            placeholder: config.placeholder.map(Arc::new),
+            has_alpha: false,
+            decoder_threads: 0,
+            checkerboard: false,
+            stop_behavior: config.stop_behavior,
+            hold_frame: false,
+            presented: None,
+            timecode_overlay: false,
+            pixel_inspector: false,
+            video_enabled: true,
+            sample_history,
+            level_meter: None,
+            visualizer: None,
+            visualizer_image: ColorImage::default(),
+            visualizer_interval: Duration::ZERO,
+            visualizer_rendered: None,
+            playlist: Playlist::default(),
+            standby: None,
+            metadata_sender,
+            metadata_receiver,
+            metadata_requested: HashSet::new(),
+            subtitles: Vec::new(),
+            #[cfg(feature = "libass")]
+            ass_subtitles: None,
+            secondary_subtitles: Vec::new(),
+            subtitle_delay_ms: 0,
+            subtitle_scale: 1.0,
+            subtitle_languages: Vec::new(),
+            prefer_forced_subtitles: false,
+            waveform: None,
+            waveform_receiver: None,
+            thumbnails: None,
+            thumbnail_receiver: None,
+            thumbnail_texture: None,
+            thumbnail_request: None,
+            scene_changes: Vec::new(),
+            scene_receiver: None,
+            scene_cancel: Arc::new(AtomicBool::new(false)),
+            track_splits: Vec::new(),
+            track_receiver: None,
+            track_cancel: Arc::new(AtomicBool::new(false)),
+            silence_skip: None,
+            silent_ranges: Vec::new(),
+            silence_receiver: None,
+            silence_cancel: Arc::new(AtomicBool::new(false)),
+            silence_base_speed: None,
+            file_watch: None,
+            telemetry_receiver: pipeline.telemetry_receiver,
+            cache_counters: pipeline.cache_counters,
+            telemetry: Vec::new(),
+            error_receiver: pipeline.error_receiver,
+            event_sender,
+            event_receiver,
+            _resource: ResourceGuard::new(Resource::Player),
         };
 
-        // Resume decoder temporarily to get first frame, then seek to show it
-        let _ = player.command_sender.send(DecoderCommand::Resume);
-        player.seek(Duration::ZERO);
+        if let Some(ref settings) = config.settings {
+            player.apply_live_settings(settings);
+            player.load_preferred_subtitles();
+        }
+
+        // Cover art stands in until the paused decoder sends the first frame
+        player.show_poster();
+        if player.seekable {
+            player.start_seek(0.0, SeekMode::Exact);
+        }
 
         Ok(player)
     }
 
+    /// Switch to another file, reusing the audio output, texture and decoder thread.
+    /// The new file's cover art or the placeholder image, else the last frame,
+    /// stays visible until the first frame of the new file is ready.
+    /// The file is opened on the decoder thread, so the UI does not wait for it.
+    /// Its size, duration and streams are taken on once it is open; failures
+    /// come from `error()`. The latest call wins: the decoder abandons a file it
+    /// is still opening, e.g. a slow network stream, and nothing of it reaches
+    /// the output.
+    pub fn replace_media(&mut self, path: &Path) -> Result<(), PlayerError> {
+        if playlist::is_playlist_file(path) {
+            return self.set_playlist(Playlist::load(path)?);
+        }
+
+        self.path = path.to_path_buf();
+        let request = self.send_open(path, self.effective_decoder_options());
+        self.pending_open = Some((request, OpenKind::Replace));
+        self.view = ViewTransform::default();
+        self.seek_history.clear();
+        self.reverse_shuttle = None;
+        self.range_start = 0.0;
+        self.range_end = None;
+        self.clear_subtitles();
+        self.secondary_subtitles.clear();
+        self.load_preferred_subtitles();
+        self.telemetry.clear();
+        while self.telemetry_receiver.try_recv().is_ok() {}
+        self.standby = None;
+        self.frame_cache.clear();
+
+        // The new decoder starts paused - show its first frame, then carry on playing
+        self.start_seek(0.0, SeekMode::Exact);
+        self.restore_decoder_state();
+        if self.state == PlayerState::Playing {
+            let _ = self.command_sender.send(DecoderCommand::Resume);
+        }
+
+        Ok(())
+    }
+
+    /// Have the decoder thread switch to `path`, abandoning any open still in progress.
+    /// Returns the number its info is reported with.
+    fn send_open(&self, path: &Path, options: DecoderOptions) -> u64 {
+        let request = self.open_requests.add();
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::Open(path.to_path_buf(), options));
+        request
+    }
+
+    /// Take on the info of the file the decoder opened last, once it arrives
+    fn receive_media_info(&mut self) {
+        while let Ok((request, info)) = self.info_receiver.try_recv() {
+            match self.pending_open {
+                Some((pending, kind)) if pending == request => {
+                    self.pending_open = None;
+                    self.apply_media_info(info, kind);
+                }
+                // Superseded by a newer open
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_media_info(&mut self, info: MediaInfo, kind: OpenKind) {
+        // Filters such as interpolation depend on the new frame rate
+        let filter = self.interpolation.filter(self.frame_rate, self.speed);
+        if kind == OpenKind::Replace || self.current_frame.is_none() {
+            // Otherwise the shown frame keeps its size until the next one lands
+            self.width = info.width;
+            self.height = info.height;
+        }
+        self.duration = info.duration;
+        self.frame_rate = info.frame_rate;
+        self.still_image = info.still_image;
+        self.seekable = info.seekable;
+        self.streams = info.streams;
+        self.bit_rate = info.bit_rate;
+        self.chapters = info.chapters;
+        self.set_attachments(info.attachments);
+
+        if kind == OpenKind::Replace {
+            if self.still_image {
+                self.pause();
+            }
+            if self.video_enabled {
+                self.show_poster();
+            }
+            self.refresh_waveform();
+            self.refresh_thumbnails();
+            self.refresh_scene_changes();
+            self.refresh_track_splits();
+            self.refresh_silent_ranges();
+            if self.file_watch.is_some() {
+                self.watch_file(true);
+            }
+        }
+
+        if self.interpolation.filter(self.frame_rate, self.speed) != filter {
+            if let Err(e) = self.reopen_decoder() {
+                self.warn(format!("Failed to restart decoding: {}", e));
+            }
+        }
+    }
+
+    /// Set the queue of files to play. If its current item is not the open file, it is opened.
+    pub fn set_playlist(&mut self, playlist: Playlist) -> Result<(), PlayerError> {
+        self.playlist = playlist;
+        self.standby = None;
+        match self.playlist.current().map(Path::to_path_buf) {
+            Some(path) if path != self.path => self.replace_media(&path),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the playlist
+    #[must_use]
+    pub fn playlist(&self) -> &Playlist {
+        &self.playlist
+    }
+
+    /// Get the playlist for editing. The preloaded next item is discarded,
+    /// as the edit may change what plays next.
+    pub fn playlist_mut(&mut self) -> &mut Playlist {
+        self.standby = None;
+        &mut self.playlist
+    }
+
+    /// Jump to a playlist item
+    pub fn play_item(&mut self, index: usize) -> Result<(), PlayerError> {
+        let Some(path) = self.playlist.select(index).map(Path::to_path_buf) else {
+            return Err(PlayerError::Other(format!(
+                "Playlist has no item {}",
+                index
+            )));
+        };
+        self.replace_media(&path)?;
+        let _ = self
+            .event_sender
+            .try_send(PlayerEvent::PlaylistAdvanced { index });
+        Ok(())
+    }
+
+    /// Skip to the next playlist item
+    pub fn next_item(&mut self) -> Result<(), PlayerError> {
+        let index = self.playlist.current_index().map_or(0, |i| i + 1);
+        self.play_item(index)
+    }
+
+    /// Go back to the previous playlist item
+    pub fn previous_item(&mut self) -> Result<(), PlayerError> {
+        let index = self.playlist.current_index().unwrap_or(0);
+        self.play_item(index.saturating_sub(1))
+    }
+
+    /// Open the next video or image in the current file's folder, by name
+    pub fn next_file(&mut self) -> Result<(), PlayerError> {
+        self.open_sibling(1)
+    }
+
+    /// Open the previous video or image in the current file's folder, by name
+    pub fn previous_file(&mut self) -> Result<(), PlayerError> {
+        self.open_sibling(-1)
+    }
+
+    fn open_sibling(&mut self, step: isize) -> Result<(), PlayerError> {
+        let path = folder::sibling_file(&self.path, step).ok_or_else(|| {
+            PlayerError::Other(format!(
+                "No {} file in the folder",
+                if step > 0 { "next" } else { "previous" }
+            ))
+        })?;
+        self.replace_media(&path)
+    }
+
+    /// Start opening the next playlist item once the current one is about to end
+    fn preload_next_item(&mut self, position: f64) {
+        if let Some(ref mut standby) = self.standby {
+            if let Err(e) = standby.poll() {
+                self.warn(format!("Failed to preload next item: {:#}", e));
+            }
+            return;
+        }
+        // Live streams have no end to preload for
+        if self.duration <= 0.0 || self.range_end() - position > PRELOAD_AHEAD {
+            return;
+        }
+        if let Some(next) = self.playlist.peek_next() {
+            self.standby = Some(Standby::start(
+                next,
+                self.audio_output.format().sample_rate,
+                self.audio_tap.clone(),
+                self.video_effects.clone(),
+                self.event_sender.clone(),
+                self.recovery,
+                self.effective_decoder_options(),
+                self.frame_budget.clone(),
+            ));
+        }
+    }
+
+    /// Move on to the next playlist item at the end of the current one.
+    /// Returns false if there is nothing left to play.
+    fn advance_playlist(&mut self) -> bool {
+        let (Some(index), Some(next)) = (
+            self.playlist.next_index(),
+            self.playlist.peek_next().map(Path::to_path_buf),
+        ) else {
+            return false;
+        };
+
+        let standby = self.standby.take().filter(|standby| standby.path() == next);
+        match standby.and_then(Standby::into_pipeline) {
+            Some(pipeline) => self.hand_off(&next, pipeline),
+            // Not prebuffered (yet) - reopen in the running decoder instead
+            None => {
+                if let Err(e) = self.replace_media(&next) {
+                    self.warn(format!("Failed to open next item: {:#}", e));
+                    return false;
+                }
+            }
+        }
+
+        self.playlist.select(index);
+        let _ = self
+            .event_sender
+            .try_send(PlayerEvent::PlaylistAdvanced { index });
+        true
+    }
+
+    /// Swap the finished decoder for the prebuffered standby pipeline.
+    /// Its frames and audio are already queued, so playback continues immediately.
+    fn hand_off(&mut self, path: &Path, pipeline: Pipeline) {
+        // The old decoder is parked at EOF and exits on its own
+        retire_decoder(&self.command_sender, &self.stop_flag);
+        self.command_sender = pipeline.command_sender;
+        self.stop_flag = pipeline.stop_flag;
+        self.open_requests = pipeline.open_requests;
+        self.info_receiver = pipeline.info_receiver;
+        self.pending_open = None;
+        self.decoder_handle = Some(pipeline.decoder_handle);
+        self.restore_decoder_state();
+        self.frame_cache.clear();
+
+        self.audio_output.set_source(
+            AudioSource::new(
+                pipeline.audio_consumer,
+                pipeline.clock.clone(),
+                self.fade.clone(),
+            )
+            .with_balance(self.balance.clone())
+            .with_history(self.sample_history.clone())
+            .without_fade_in(),
+        );
+        self.clock = pipeline.clock;
+        self.clock.set_output_latency(self.output_latency_ms);
+        self.clock.set_rate(self.speed);
+        self.clock.set_video_master(!self.sync_policy.audio_master);
+        // Prebuffered with the clock stopped, so it starts from the top only now
+        self.clock.sync_position(0.0);
+        if self.state == PlayerState::Playing {
+            self.clock.resume();
+        } else {
+            let _ = self.command_sender.send(DecoderCommand::Pause);
+        }
+        self.frame_queue.replace_receiver(pipeline.video_receiver);
+        self.error_receiver = pipeline.error_receiver;
+        self.telemetry_receiver = pipeline.telemetry_receiver;
+        self.cache_counters = pipeline.cache_counters;
+
+        self.path = path.to_path_buf();
+        self.width = pipeline.info.width;
+        self.height = pipeline.info.height;
+        self.duration = pipeline.info.duration;
+        self.frame_rate = pipeline.info.frame_rate;
+        self.still_image = pipeline.info.still_image;
+        self.seekable = pipeline.info.seekable;
+        self.streams = pipeline.info.streams;
+        self.bit_rate = pipeline.info.bit_rate;
+        self.chapters = pipeline.info.chapters;
+        self.set_attachments(pipeline.info.attachments);
+        if self.still_image {
+            self.pause();
+        }
+        self.view = ViewTransform::default();
+        self.seek_history.clear();
+        self.reverse_shuttle = None;
+        self.range_start = 0.0;
+        self.range_end = None;
+        self.clear_subtitles();
+        self.secondary_subtitles.clear();
+        self.load_preferred_subtitles();
+        self.telemetry.clear();
+        self.refresh_waveform();
+        self.refresh_thumbnails();
+        self.refresh_scene_changes();
+        self.refresh_track_splits();
+        self.refresh_silent_ranges();
+        if self.file_watch.is_some() {
+            self.watch_file(true);
+        }
+    }
+
+    /// Apply the player's color and video settings to a freshly opened decoder
+    fn restore_decoder_state(&self) {
+        if self.color_management != ColorManagement::Auto {
+            let _ = self
+                .command_sender
+                .send(DecoderCommand::SetColorManagement(self.color_management));
+        }
+        if !self.video_enabled {
+            let _ = self
+                .command_sender
+                .send(DecoderCommand::SetVideoEnabled(false));
+        }
+        if self.pitch != 0.0 {
+            let _ = self
+                .command_sender
+                .send(DecoderCommand::SetPitch(self.pitch));
+        }
+    }
+
     /// Start or resume playback
     pub fn play(&mut self) {
-        if self.state != PlayerState::Playing {
+        // A still image has nothing to play past its one frame
+        if self.state != PlayerState::Playing && !self.still_image {
+            self.reverse_shuttle = None;
+            self.hold_frame = false;
             self.state = PlayerState::Playing;
-            self.sink.play();
+            self.fade.fade_in();
+            self.audio_output.play();
             let _ = self.command_sender.send(DecoderCommand::Resume);
+            if self.showing_cached {
+                // Bring the decoder to the cached frame shown
+                self.start_seek(self.position().as_secs_f64(), SeekMode::Exact);
+            }
         }
     }
 
-    /// Pause playback
+    /// Pause playback. Audio fades out rather than stopping mid-waveform.
     pub fn pause(&mut self) {
         if self.state == PlayerState::Playing {
             self.state = PlayerState::Paused;
-            self.sink.pause();
+            self.fade.fade_out();
             let _ = self.command_sender.send(DecoderCommand::Pause);
         }
     }
 
-    /// Stop playback and seek to beginning
+    /// Stop playback and seek to beginning. What stays on screen is set by
+    /// `set_stop_behavior()`.
     pub fn stop(&mut self) {
         self.state = PlayerState::Stopped;
-        self.sink.pause();
+        self.audio_output.pause();
         let _ = self.command_sender.send(DecoderCommand::Pause);
         self.seek(Duration::ZERO);
+        match self.stop_behavior {
+            StopBehavior::ShowFirstFrame => {}
+            StopBehavior::ShowLastFrame => self.hold_frame = true,
+            StopBehavior::Clear => {
+                self.hold_frame = true;
+                self.clear_frame();
+            }
+        }
     }
 
-    /// Seek to position
-    pub fn seek(&mut self, position: Duration) {
-        let position_secs = position.as_secs_f64().clamp(0.0, self.duration);
-        self.seeking = true;
-        self.seek_target = position_secs;
-        self.sink.pause(); // Pause audio during seek to stop clock advancement
-        self.frame_queue.clear();
-        self.clock.set_position(position_secs);
-        let _ = self.command_sender.send(DecoderCommand::Seek(position_secs));
+    /// Set what `stop()` leaves on screen
+    pub fn set_stop_behavior(&mut self, behavior: StopBehavior) {
+        self.stop_behavior = behavior;
     }
 
-    /// Check if currently seeking
+    /// Get what `stop()` leaves on screen
     #[must_use]
-    pub fn is_seeking(&self) -> bool {
-        self.seeking
+    pub fn stop_behavior(&self) -> StopBehavior {
+        self.stop_behavior
     }
 
-    /// Set volume
-    pub fn set_volume(&mut self, volume: Volume) {
-        self.sink.set_volume(volume.get());
+    /// Seek to position, landing on the exact frame
+    pub fn seek(&mut self, position: Duration) {
+        self.seek_with_mode(position, SeekMode::Exact);
     }
 
-    /// Get current volume
-    #[must_use]
-    pub fn volume(&self) -> Volume {
-        // Safe: rodio volume is always 0.0..=1.0
-        Volume(self.sink.volume())
+    /// Seek to position with the given precision.
+    /// Keyframe seeks suit scrubbing; follow them with an exact seek to settle.
+    /// When paused, the frame at the position is shown and audio stays paused;
+    /// once it is, `position()` is that frame's PTS, where audio resumes on `play()`.
+    /// Ignored for inputs that cannot seek. The position seeked from can be
+    /// returned to with `undo_seek()`.
+    pub fn seek_with_mode(&mut self, position: Duration, mode: SeekMode) {
+        if !self.seekable {
+            return;
+        }
+        self.seek_history.record(self.position().as_secs_f64());
+        if !self.show_cached_frame(position.as_secs_f64()) {
+            self.start_seek(position.as_secs_f64(), mode);
+        }
     }
 
-    /// Toggle display mode
-    pub fn toggle_display_mode(&mut self) {
-        self.display_mode = match self.display_mode {
-            DisplayMode::FitToWindow => DisplayMode::NativeSize,
-            DisplayMode::NativeSize => DisplayMode::FitToWindow,
+    /// Show the cached frame at `position` while paused, without decoding.
+    /// The decoder catches up on `play()`. Returns false if it is not cached.
+    fn show_cached_frame(&mut self, position: f64) -> bool {
+        if self.state == PlayerState::Playing || self.seeking || !self.video_enabled {
+            return false;
+        }
+        let target = (self.range_start + position).clamp(self.range_start, self.range_end());
+        let Some(frame) = self.frame_cache.frame_at(target, self.frame_interval()) else {
+            return false;
         };
+        let (pts, image, metadata) = (frame.pts, frame.image.clone(), frame.metadata.clone());
+        tracing::debug!(pts, target, "showing cached frame");
+        let shown = Duration::from_secs_f64(pts.max(0.0));
+        let frame_number = (pts.max(0.0) * self.frame_rate).round() as u64;
+        let _ = self.event_sender.try_send(PlayerEvent::FramePresented {
+            pts: shown,
+            frame_number,
+        });
+        self.presented = Some((shown, frame_number));
+        self.frame_metadata = Some(metadata);
+        self.width = image.width() as u32;
+        self.height = image.height() as u32;
+        self.upload(image, Some(shown));
+        self.queued_jump = None;
+        self.hold_frame = false;
+        self.clock.sync_position(pts);
+        self.showing_cached = true;
+        true
     }
 
-    /// Get current display mode
+    /// Keep up to `bytes` of recently shown frames, so stepping and seeking
+    /// back over them while paused is instant (0 disables the cache)
+    pub fn set_frame_cache_size(&mut self, bytes: usize) {
+        self.frame_cache.set_capacity(bytes);
+    }
+
+    /// Get the memory cap of the recently shown frames, in bytes
     #[must_use]
-    pub fn display_mode(&self) -> DisplayMode {
-        self.display_mode
+    pub fn frame_cache_size(&self) -> usize {
+        self.frame_cache.capacity()
     }
 
-    /// Update player state and texture (call each frame)
-    pub fn update(&mut self, ctx: &Context) {
-        // Handle seeking state - check for first frame after seek
+    /// Nominal time between frames
+    fn frame_interval(&self) -> f64 {
+        if self.frame_rate > 0.0 {
+            1.0 / self.frame_rate
+        } else {
+            DEFAULT_FRAME_INTERVAL
+        }
+    }
+
+    /// Jump forwards, or backwards for negative `seconds`, from the current
+    /// position. Jumps made before the previous one lands add up and are sent
+    /// to the decoder as a single seek, so holding a key stays responsive.
+    pub fn seek_relative(&mut self, seconds: f64) {
+        if !self.seekable || seconds == 0.0 {
+            return;
+        }
+        // position() is already the target of a seek in flight
+        let from = self
+            .queued_jump
+            .unwrap_or_else(|| self.position().as_secs_f64());
+        let target = (from + seconds).clamp(0.0, self.duration().as_secs_f64());
         if self.seeking {
-            if let Some(frame) = self.frame_queue.get_first_frame_after_seek(self.seek_target) {
-                // Frame arrived - seek complete
-                if let Some(ref mut texture) = self.texture {
-                    // Zero-copy: move pixels directly into ColorImage
-                    let image = ColorImage {
-                        size: [frame.width as usize, frame.height as usize],
-                        pixels: frame.pixels,
-                    };
-                    texture.set(image, TextureOptions::LINEAR);
-                }
-                // Update clock to match the actual frame we got
-                self.clock.set_position(frame.pts);
-                self.seeking = false;
-                // Resume audio if we were playing
-                if self.state == PlayerState::Playing {
-                    self.sink.play();
-                }
-            }
-            ctx.request_repaint();
+            self.queued_jump = Some(target);
             return;
         }
+        self.seek_history.record(self.position().as_secs_f64());
+        self.start_seek(target, SeekMode::Exact);
+    }
 
-        if self.state != PlayerState::Playing {
+    /// Set the small and large jumps of the rewind and fast-forward controls.
+    /// Defaults to 5 and 30 seconds.
+    pub fn set_seek_increments(&mut self, small: Duration, large: Duration) {
+        self.seek_increments = (small, large.max(small));
+    }
+
+    /// Get the small and large jumps of the rewind and fast-forward controls
+    #[must_use]
+    pub fn seek_increments(&self) -> (Duration, Duration) {
+        self.seek_increments
+    }
+
+    /// Return to the position before the last seek. Seeks in quick succession,
+    /// such as a timeline drag, are undone together. Returns false if there
+    /// is nothing to undo.
+    pub fn undo_seek(&mut self) -> bool {
+        if !self.seekable {
+            return false;
+        }
+        let Some(position) = self.seek_history.undo(self.position().as_secs_f64()) else {
+            return false;
+        };
+        self.start_seek(position, SeekMode::Exact);
+        true
+    }
+
+    /// Go back to the position left by the last `undo_seek()`. Returns false
+    /// if there is nothing to redo.
+    pub fn redo_seek(&mut self) -> bool {
+        if !self.seekable {
+            return false;
+        }
+        let Some(position) = self.seek_history.redo(self.position().as_secs_f64()) else {
+            return false;
+        };
+        self.start_seek(position, SeekMode::Exact);
+        true
+    }
+
+    /// Check if there is a seek to undo
+    #[must_use]
+    pub fn can_undo_seek(&self) -> bool {
+        self.seek_history.can_undo()
+    }
+
+    /// Check if there is an undone seek to redo
+    #[must_use]
+    pub fn can_redo_seek(&self) -> bool {
+        self.seek_history.can_redo()
+    }
+
+    /// Pause and move by whole frames, negative for backwards
+    pub fn step_frames(&mut self, frames: i32) {
+        if !self.seekable || frames == 0 {
             return;
         }
+        self.pause();
+        // Aim half a frame past the wanted one, so the current frame is never accepted
+        let target =
+            self.position().as_secs_f64() + (f64::from(frames) + 0.5) * self.frame_interval();
+        self.seek(Duration::from_secs_f64(target.max(0.0)));
+    }
 
-        let audio_time = self.clock.position();
+    /// Apply the actions of a gamepad, jog wheel or other control surface.
+    /// Call once per frame, before `update()`.
+    pub fn poll_control_surface(&mut self, surface: &mut dyn ControlSurface) {
+        for action in surface.poll() {
+            self.apply_surface_action(action);
+        }
+    }
 
-        if let Some(frame) = self.frame_queue.get_display_frame(audio_time) {
-            // Update texture with new frame (zero-copy)
-            if let Some(ref mut texture) = self.texture {
-                let image = ColorImage {
-                    size: [frame.width as usize, frame.height as usize],
-                    pixels: frame.pixels,
+    /// Apply one control surface action
+    pub fn apply_surface_action(&mut self, action: SurfaceAction) {
+        match action {
+            SurfaceAction::TogglePlayback => {
+                if self.is_playing() {
+                    self.pause();
+                } else {
+                    self.play();
+                }
+            }
+            SurfaceAction::Shuttle(value) => self.shuttle(value),
+            SurfaceAction::StepFrames(frames) => self.step_frames(frames),
+            SurfaceAction::Stop => self.stop(),
+            SurfaceAction::NextItem | SurfaceAction::PreviousItem => {
+                let next = action == SurfaceAction::NextItem;
+                let result = match (self.playlist.is_empty(), next) {
+                    (false, true) => self.next_item(),
+                    (false, false) => self.previous_item(),
+                    (true, true) => self.next_file(),
+                    (true, false) => self.previous_file(),
                 };
-                texture.set(image, TextureOptions::LINEAR);
+                if let Err(e) = result {
+                    self.warn(e.to_string());
+                }
             }
         }
+    }
 
-        // Check for end of stream
-        if self.frame_queue.is_empty() && audio_time >= self.duration - 0.1 {
-            self.state = PlayerState::Stopped;
-            self.sink.pause();
+    /// Play forwards faster or slower with the deflection, or step backwards
+    /// through keyframes while paused. Centering restores the previous speed.
+    fn shuttle(&mut self, value: f32) {
+        let deflection = shuttle_deflection(value);
+        if deflection < 0.0 {
+            if let Some(speed) = self.shuttle_base_speed.take() {
+                self.set_speed(speed);
+            }
+            self.pause();
+            match self.reverse_shuttle {
+                Some(ref mut shuttle) => shuttle.deflection = deflection,
+                None => {
+                    self.reverse_shuttle = Some(ReverseShuttle {
+                        deflection,
+                        position: self.position().as_secs_f64(),
+                        last_update: Instant::now(),
+                    });
+                }
+            }
+            return;
         }
 
-        ctx.request_repaint();
+        // Settle on the exact frame where backwards shuttling stopped
+        if self.reverse_shuttle.take().is_some() {
+            self.start_seek(self.position().as_secs_f64(), SeekMode::Exact);
+        }
+        if deflection > 0.0 {
+            self.shuttle_base_speed.get_or_insert(self.speed);
+            self.set_speed(shuttle_speed(deflection));
+            self.play();
+        } else if let Some(speed) = self.shuttle_base_speed.take() {
+            self.set_speed(speed);
+            self.pause();
+        }
     }
 
-    /// Get texture handle for rendering
-    #[must_use]
-    pub fn texture(&self) -> Option<&TextureHandle> {
-        self.texture.as_ref()
+    /// Move the backwards shuttle on by the time since the last update
+    fn update_reverse_shuttle(&mut self) {
+        let Some(ref mut shuttle) = self.reverse_shuttle else {
+            return;
+        };
+        let now = Instant::now();
+        let elapsed = now.duration_since(shuttle.last_update).as_secs_f64();
+        shuttle.position =
+            (shuttle.position - elapsed * f64::from(shuttle_speed(shuttle.deflection))).max(0.0);
+        shuttle.last_update = now;
+        let target = shuttle.position;
+        self.start_seek(target, SeekMode::Keyframe);
     }
 
-    /// Get video dimensions
-    #[must_use]
-    pub fn video_size(&self) -> (u32, u32) {
-        (self.width, self.height)
+    /// Seek without recording the position in the seek history
+    fn start_seek(&mut self, position_secs: f64, mode: SeekMode) {
+        if !self.seekable {
+            return;
+        }
+        self.queued_jump = None;
+        self.showing_cached = false;
+        let position_secs =
+            (self.range_start + position_secs).clamp(self.range_start, self.range_end());
+        if self.state == PlayerState::Playing {
+            // Undo a fade-out at the end of media
+            self.fade.fade_in();
+        }
+        // Without video there is no frame to wait for
+        self.seeking = self.video_enabled;
+        self.hold_frame = false;
+        self.seek_target = position_secs;
+        self.seek_mode = mode;
+        if self.seeking {
+            self.audio_output.pause(); // Pause audio during seek to stop clock advancement
+        }
+        let generation = self.frame_queue.begin_seek();
+        tracing::debug!(target = position_secs, ?mode, generation, "seek requested");
+        self.clock.set_position(position_secs);
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::Seek(position_secs, mode, generation));
     }
 
-    /// Get video duration
-    #[must_use]
-    pub fn duration(&self) -> Duration {
-        Duration::from_secs_f64(self.duration)
+    /// Decode video (true) or play audio only, discarding video packets in the
+    /// demuxer to save CPU, e.g. while the window is minimized.
+    /// The last frame, or the cover art of files that have one, stays in the texture while video is off.
+    pub fn set_video_enabled(&mut self, enabled: bool) {
+        if enabled == self.video_enabled {
+            return;
+        }
+        self.video_enabled = enabled;
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::SetVideoEnabled(enabled));
+        if enabled {
+            // Restart video from a keyframe at the current position
+            self.start_seek(self.position().as_secs_f64(), SeekMode::Exact);
+        } else {
+            self.frame_queue.clear();
+            self.show_cover_art();
+            if self.seeking {
+                self.seeking = false;
+                self.queued_jump = None;
+                if self.state == PlayerState::Playing {
+                    self.audio_output.play();
+                }
+            }
+        }
     }
 
-    /// Get current playback position
+    /// Check if video is decoded
     #[must_use]
-    pub fn position(&self) -> Duration {
-        let secs = if self.seeking {
-            self.seek_target // Show seek target while seeking
-        } else {
-            self.clock.position()
-        };
-        Duration::from_secs_f64(secs)
+    pub fn is_video_enabled(&self) -> bool {
+        self.video_enabled
     }
 
-    /// Check if currently playing
+    /// Check if currently seeking. The last frame stays in the texture until
+    /// the first frame at the new position arrives.
     #[must_use]
-    pub fn is_playing(&self) -> bool {
-        self.state == PlayerState::Playing
+    pub fn is_seeking(&self) -> bool {
+        self.seeking
     }
 
-    /// Get player state
+    /// Check if the input can seek. Pipes cannot; they also have no duration,
+    /// and features that reread the file (waveform, scenes, export) are unavailable.
     #[must_use]
-    pub fn state(&self) -> PlayerState {
-        self.state
+    pub fn is_seekable(&self) -> bool {
+        self.seekable
     }
 
-    /// Poll for decoder errors (non-blocking)
+    /// Set volume
+    pub fn set_volume(&mut self, volume: Volume) {
+        self.volume = volume;
+        if !self.muted {
+            self.audio_output.set_volume(volume.get());
+        }
+    }
+
+    /// Get current volume (unaffected by mute)
     #[must_use]
-    pub fn error(&self) -> Option<String> {
-        self.error_receiver.try_recv().ok()
+    pub fn volume(&self) -> Volume {
+        self.volume
+    }
+
+    /// Mute or unmute audio output, keeping the volume setting
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        let volume = if muted { 0.0 } else { self.volume.get() };
+        self.audio_output.set_volume(volume);
+    }
+
+    /// Check if audio output is muted
+    #[must_use]
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Pan the audio between the left (-1.0) and right (1.0) speaker; 0.0 is centered.
+    /// The opposite channel is attenuated, the nearer one stays at full volume.
+    pub fn set_balance(&mut self, balance: f32) {
+        self.balance.set(balance);
+    }
+
+    /// Get the left/right balance
+    #[must_use]
+    pub fn balance(&self) -> f32 {
+        self.balance.get()
+    }
+
+    /// Compensate for audio output latency, e.g. 150-250 ms on Bluetooth headphones.
+    /// Video is delayed by this amount so it stays in sync with what is heard.
+    pub fn set_output_latency(&mut self, ms: i64) {
+        self.output_latency_ms = ms;
+        self.clock.set_output_latency(ms);
+    }
+
+    /// Get the audio output latency compensation in milliseconds
+    #[must_use]
+    pub fn output_latency(&self) -> i64 {
+        self.output_latency_ms
+    }
+
+    /// Format the audio device was opened with; decoded audio is resampled to its rate
+    #[must_use]
+    pub fn audio_output_format(&self) -> AudioOutputFormat {
+        self.audio_output.format()
+    }
+
+    /// Change how video is kept in sync; see `SyncPolicy`
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+        self.frame_queue.set_pacing(policy.pacing);
+        self.clock.set_video_master(!policy.audio_master);
+    }
+
+    /// Get the sync policy
+    #[must_use]
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    /// Play audio through another backend, reopening the output and
+    /// resuming from the position shown. The current backend stays on failure.
+    pub fn set_audio_backend(&mut self, backend: AudioBackend) -> Result<(), PlayerError> {
+        let previous = std::mem::replace(&mut self.audio_backend, backend);
+        let result = self.reopen_audio_output();
+        if result.is_err() {
+            self.audio_backend = previous;
+        }
+        result
+    }
+
+    /// Get the audio backend in use
+    #[must_use]
+    pub fn audio_backend(&self) -> AudioBackend {
+        self.audio_backend
+    }
+
+    /// Set playback speed (0.25 to 4.0, 1.0 being normal). Audio is resampled, so pitch changes.
+    /// With interpolation on, a new slow-motion speed restarts decoding at the current position.
+    pub fn set_speed(&mut self, speed: f32) {
+        let previous = self.interpolation.filter(self.frame_rate, self.speed);
+        self.speed = speed.clamp(0.25, 4.0);
+        self.audio_output.set_speed(self.speed);
+        self.clock.set_rate(self.speed);
+        if self.interpolation.filter(self.frame_rate, self.speed) != previous {
+            if let Err(e) = self.reopen_decoder() {
+                self.warn(format!("Failed to apply interpolation: {}", e));
+            }
+        }
+    }
+
+    /// Get playback speed
+    #[must_use]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Shift the audio pitch by -12 to 12 semitones without changing the tempo, e.g.
+    /// to play along in another key. It adds to the pitch change of `set_speed`;
+    /// `set_pitch(-12.0 * speed.log2())` keeps the original pitch when slowed down.
+    pub fn set_pitch(&mut self, semitones: f32) {
+        self.pitch = semitones.clamp(-12.0, 12.0);
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::SetPitch(self.pitch));
+    }
+
+    /// Get the audio pitch shift in semitones
+    #[must_use]
+    pub fn pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    /// Set the length of the volume ramps on play, pause and after seeks,
+    /// which avoid clicks. Clamped to 5-50 ms; defaults to 10 ms.
+    pub fn set_audio_fade(&mut self, ms: u32) {
+        self.fade
+            .set_duration_ms(ms.clamp(*FADE_RANGE_MS.start(), *FADE_RANGE_MS.end()));
+    }
+
+    /// Get the volume ramp length in milliseconds
+    #[must_use]
+    pub fn audio_fade(&self) -> u32 {
+        self.fade.duration_ms()
+    }
+
+    /// Fade the audio out as the media ends, unless a playlist item follows
+    pub fn set_fade_out_at_end(&mut self, enabled: bool) {
+        self.fade_out_at_end = enabled;
+    }
+
+    /// Check if audio fades out at the end of media
+    #[must_use]
+    pub fn fade_out_at_end(&self) -> bool {
+        self.fade_out_at_end
+    }
+
+    /// Generate intermediate frames at speeds below 1x instead of holding each frame longer.
+    /// Restarts decoding at the current position if slowed down.
+    pub fn set_interpolation(&mut self, mode: InterpolationMode) -> Result<(), PlayerError> {
+        let previous = self.interpolation.filter(self.frame_rate, self.speed);
+        self.interpolation = mode;
+        if mode.filter(self.frame_rate, self.speed) != previous {
+            self.reopen_decoder()?;
+        }
+        Ok(())
+    }
+
+    /// Get the frame interpolation mode
+    #[must_use]
+    pub fn interpolation(&self) -> InterpolationMode {
+        self.interpolation
+    }
+
+    /// Play short snippets of audio while the seek bar is dragged, to find
+    /// a spot by ear. Snippets are decoded separately from playback.
+    pub fn set_scrub_audio(&mut self, mode: ScrubAudio) {
+        self.scrub_audio = mode;
+        self.scrub_player = match mode {
+            ScrubAudio::Off => None,
+            ScrubAudio::Snippets => Some(
+                self.scrub_player
+                    .take()
+                    .unwrap_or_else(|| self.new_scrub_player()),
+            ),
+        };
+    }
+
+    fn new_scrub_player(&self) -> ScrubPlayer {
+        ScrubPlayer::new(
+            self.audio_output.snippets(),
+            self.audio_output.format().sample_rate,
+        )
+    }
+
+    /// Get the scrub audio mode
+    #[must_use]
+    pub fn scrub_audio(&self) -> ScrubAudio {
+        self.scrub_audio
+    }
+
+    /// Play the scrub snippet at `position`, if scrub audio is on.
+    /// Called by `PlayerControls` while the seek bar is dragged.
+    pub fn scrub_audio_at(&self, position: Duration) {
+        if let Some(ref scrub_player) = self.scrub_player {
+            if !self.muted && self.seekable {
+                scrub_player.play(&self.path, position.as_secs_f64(), self.volume.get());
+            }
+        }
+    }
+
+    /// Snapshot of the current preferences, e.g. to save between sessions
+    #[must_use]
+    pub fn settings(&self) -> Settings {
+        Settings {
+            volume: self.volume.get(),
+            muted: self.muted,
+            balance: self.balance(),
+            output_latency_ms: self.output_latency_ms,
+            speed: self.speed,
+            pitch: self.pitch,
+            subtitle_delay_ms: self.subtitle_delay_ms,
+            subtitle_scale: self.subtitle_scale,
+            subtitle_languages: self.subtitle_languages.clone(),
+            prefer_forced_subtitles: self.prefer_forced_subtitles,
+            audio_languages: self.decoder_options.audio_languages.clone(),
+            display_mode: self.display_mode,
+            deinterlace: self.deinterlace,
+            scrub_audio: self.scrub_audio,
+        }
+    }
+
+    /// Apply a settings profile. It also applies to every file opened afterwards.
+    /// Changing the audio languages or deinterlacing reopens the current file
+    /// at the same position.
+    pub fn set_settings(&mut self, settings: Settings) -> Result<(), PlayerError> {
+        let reopen = settings.audio_languages != self.decoder_options.audio_languages
+            || settings.deinterlace != self.deinterlace;
+        let languages_changed = settings.subtitle_languages != self.subtitle_languages
+            || settings.prefer_forced_subtitles != self.prefer_forced_subtitles;
+        self.apply_live_settings(&settings);
+        self.decoder_options.audio_languages = settings.audio_languages;
+        self.deinterlace = settings.deinterlace;
+
+        if languages_changed {
+            self.clear_subtitles();
+            self.load_preferred_subtitles();
+        }
+        if reopen {
+            self.reopen_decoder()?;
+        }
+        Ok(())
+    }
+
+    /// Restart decoding of the current file with new decoder options, at the same position
+    fn reopen_decoder(&mut self) -> Result<(), PlayerError> {
+        if !self.seekable {
+            return Err(PlayerError::NotSeekable);
+        }
+        let position = self.position();
+        // A replacement still opening stays one, with these options
+        let kind = match self.pending_open {
+            Some((_, OpenKind::Replace)) => OpenKind::Replace,
+            _ => OpenKind::Reopen,
+        };
+        let request = self.send_open(&self.path, self.effective_decoder_options());
+        self.pending_open = Some((request, kind));
+        self.standby = None;
+        self.frame_cache.clear();
+        self.start_seek(position.as_secs_f64(), SeekMode::Exact);
+        self.restore_decoder_state();
+        if self.state == PlayerState::Playing {
+            let _ = self.command_sender.send(DecoderCommand::Resume);
+        }
+        Ok(())
+    }
+
+    /// Apply the settings that take effect without reopening the decoder
+    fn apply_live_settings(&mut self, settings: &Settings) {
+        self.set_volume(Volume::new(settings.volume.clamp(0.0, 1.0)).unwrap_or(self.volume));
+        self.set_muted(settings.muted);
+        self.set_balance(settings.balance);
+        self.set_output_latency(settings.output_latency_ms);
+        self.set_speed(settings.speed);
+        self.set_pitch(settings.pitch);
+        self.set_subtitle_delay(settings.subtitle_delay_ms);
+        self.set_subtitle_scale(settings.subtitle_scale);
+        self.subtitle_languages = settings.subtitle_languages.clone();
+        self.prefer_forced_subtitles = settings.prefer_forced_subtitles;
+        self.display_mode = settings.display_mode;
+        self.set_scrub_audio(settings.scrub_audio);
+    }
+
+    /// Decoder options with the settings profile's deinterlacing applied
+    fn effective_decoder_options(&self) -> DecoderOptions {
+        effective_decoder_options(
+            &self.decoder_options,
+            self.deinterlace,
+            self.interpolation.filter(self.frame_rate, self.speed),
+        )
+    }
+
+    /// Load the sidecar subtitle file best matching the preferred languages
+    fn load_preferred_subtitles(&mut self) {
+        if self.subtitle_languages.is_empty() {
+            return;
+        }
+        if let Some(path) = find_subtitle_file(
+            &self.path,
+            &self.subtitle_languages,
+            self.prefer_forced_subtitles,
+        ) {
+            if let Err(e) = self.load_subtitles(&path) {
+                self.warn(format!("Failed to load {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Install a hook that receives decoded audio before playback
+    pub fn set_audio_tap(&mut self, tap: Box<dyn AudioTap>) {
+        *self.audio_tap.lock() = Some(tap);
+    }
+
+    /// Remove the audio tap, returning it
+    pub fn clear_audio_tap(&mut self) -> Option<Box<dyn AudioTap>> {
+        self.audio_tap.lock().take()
+    }
+
+    /// Add an effect to the end of the video effect chain.
+    /// It applies to frames decoded from now on, including after seeks.
+    pub fn add_video_effect(&mut self, effect: Box<dyn VideoEffect>) {
+        self.video_effects.lock().push(effect);
+        self.frame_cache.clear();
+    }
+
+    /// Remove all video effects, returning them in chain order
+    pub fn clear_video_effects(&mut self) -> Vec<Box<dyn VideoEffect>> {
+        self.frame_cache.clear();
+        std::mem::take(&mut *self.video_effects.lock())
+    }
+
+    /// Toggle display mode
+    pub fn toggle_display_mode(&mut self) {
+        self.display_mode = match self.display_mode {
+            DisplayMode::FitToWindow => DisplayMode::NativeSize,
+            DisplayMode::NativeSize => DisplayMode::NativePhysical,
+            DisplayMode::NativePhysical => DisplayMode::IntegerScale,
+            DisplayMode::IntegerScale => DisplayMode::FitToWindow,
+        };
+    }
+
+    /// Set display mode
+    pub fn set_display_mode(&mut self, mode: DisplayMode) {
+        self.display_mode = mode;
+    }
+
+    /// Get current display mode
+    #[must_use]
+    pub fn display_mode(&self) -> DisplayMode {
+        self.display_mode
+    }
+
+    /// Set the zoom and pan, e.g. to restore a view saved for this file
+    pub fn set_view_transform(&mut self, view: ViewTransform) {
+        self.view = view.clamped();
+    }
+
+    /// Get the zoom and pan. Opening other media resets it.
+    #[must_use]
+    pub fn view_transform(&self) -> ViewTransform {
+        self.view
+    }
+
+    /// Show the whole frame again
+    pub fn reset_view(&mut self) {
+        self.view = ViewTransform::default();
+    }
+
+    /// Cycle through time display modes
+    pub fn toggle_time_display_mode(&mut self) {
+        self.time_display_mode = match self.time_display_mode {
+            TimeDisplayMode::Standard => TimeDisplayMode::WithMillis,
+            TimeDisplayMode::WithMillis => TimeDisplayMode::Smpte,
+            TimeDisplayMode::Smpte => TimeDisplayMode::FrameNumber,
+            TimeDisplayMode::FrameNumber => TimeDisplayMode::Standard,
+        };
+    }
+
+    /// Set how the time labels show positions
+    pub fn set_time_display_mode(&mut self, mode: TimeDisplayMode) {
+        self.time_display_mode = mode;
+    }
+
+    /// Get the time display mode
+    #[must_use]
+    pub fn time_display_mode(&self) -> TimeDisplayMode {
+        self.time_display_mode
+    }
+
+    /// Set the texture sampling used when the frame is scaled
+    pub fn set_texture_filter(&mut self, filter: TextureFilter) {
+        self.texture_filter = filter;
+        // Re-upload so the change is visible while paused
+        if let (Some(texture), Some(frame)) = (&mut self.texture, &self.current_frame) {
+            texture.set(frame.clone(), texture_options(filter));
+        }
+        if let Some((ref mut texture, ref frame, _)) = self.reference {
+            texture.set(frame.clone(), texture_options(filter));
+        }
+    }
+
+    /// Get current texture filter
+    #[must_use]
+    pub fn texture_filter(&self) -> TextureFilter {
+        self.texture_filter
+    }
+
+    /// Get the fonts, cover art and other files embedded in the media (e.g. MKV attachments)
+    #[must_use]
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    /// Get the codec details of every stream in the media
+    #[must_use]
+    pub fn streams(&self) -> &[StreamInfo] {
+        &self.streams
+    }
+
+    /// Get the overall bit rate of the media in bits per second, if known
+    #[must_use]
+    pub fn bit_rate(&self) -> Option<u64> {
+        self.bit_rate
+    }
+
+    /// Font family for subtitles: the fonts attached to the media if it has any,
+    /// otherwise egui's proportional font
+    #[must_use]
+    pub fn subtitle_font(&self) -> FontFamily {
+        self.subtitle_font
+            .clone()
+            .unwrap_or(FontFamily::Proportional)
+    }
+
+    /// Check if the open media is a single image, shown paused
+    #[must_use]
+    pub fn is_still_image(&self) -> bool {
+        self.still_image
+    }
+
+    /// Set the frame rate image sequences play at (0.0 for 25 fps).
+    /// An open sequence restarts at the same position with the new rate.
+    pub fn set_image_frame_rate(&mut self, fps: f64) -> Result<(), PlayerError> {
+        self.decoder_options.image_frame_rate = fps.max(0.0);
+        if sequence::is_image_sequence(&self.path) {
+            self.reopen_decoder()?;
+        }
+        Ok(())
+    }
+
+    /// Get the frame rate image sequences play at (0.0 for the default)
+    #[must_use]
+    pub fn image_frame_rate(&self) -> f64 {
+        self.decoder_options.image_frame_rate
+    }
+
+    /// Check if the current video has an alpha channel (e.g. ProRes 4444 or VP9 with alpha)
+    #[must_use]
+    pub fn has_alpha(&self) -> bool {
+        self.has_alpha
+    }
+
+    /// Show transparent video over a checkerboard instead of the widget background
+    pub fn set_checkerboard(&mut self, enabled: bool) {
+        self.checkerboard = enabled;
+    }
+
+    /// Check if transparent video is shown over a checkerboard
+    #[must_use]
+    pub fn checkerboard(&self) -> bool {
+        self.checkerboard
+    }
+
+    /// Draw the exact timestamp and number of the frame on screen over the video
+    pub fn set_timecode_overlay(&mut self, enabled: bool) {
+        self.timecode_overlay = enabled;
+    }
+
+    /// Check if the timecode overlay is shown
+    #[must_use]
+    pub fn timecode_overlay(&self) -> bool {
+        self.timecode_overlay
+    }
+
+    /// Show the coordinates and color of the pixel under the pointer in
+    /// `VideoView`; clicking copies the color
+    pub fn set_pixel_inspector(&mut self, enabled: bool) {
+        self.pixel_inspector = enabled;
+    }
+
+    /// Check if the pixel inspector is on
+    #[must_use]
+    pub fn pixel_inspector(&self) -> bool {
+        self.pixel_inspector
+    }
+
+    /// Media timestamp and frame number of the frame in the texture, the same
+    /// as in its `FramePresented` event. None before the first frame and while
+    /// cover art is shown.
+    #[must_use]
+    pub fn presented_frame(&self) -> Option<(Duration, u64)> {
+        self.presented
+    }
+
+    /// Set how YUV frames are converted to RGB
+    pub fn set_color_management(&mut self, mode: ColorManagement) {
+        self.color_management = mode;
+        self.frame_cache.clear();
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::SetColorManagement(mode));
+        // Re-decode the current frame so the change is visible while paused
+        if !self.is_playing() {
+            self.start_seek(self.position().as_secs_f64(), SeekMode::Exact);
+        }
+    }
+
+    /// Get current color management mode
+    #[must_use]
+    pub fn color_management(&self) -> ColorManagement {
+        self.color_management
+    }
+
+    /// Update player state and texture (call each frame)
+    pub fn update(&mut self, ctx: &Context) {
+        self.update_synced(ctx, None);
+    }
+
+    /// Update using an external master clock (in seconds) for frame selection.
+    /// Used by `PlayerGroup` to keep followers frame-locked to the master.
+    pub(crate) fn update_synced(&mut self, ctx: &Context, master_time: Option<f64>) {
+        self.receive_telemetry();
+        if self.attached_fonts_pending {
+            self.register_attached_fonts(ctx);
+        }
+        if let Some(waveform) = self
+            .waveform_receiver
+            .as_ref()
+            .and_then(|r| r.try_recv().ok())
+        {
+            self.waveform = Some(waveform);
+            self.waveform_receiver = None;
+        }
+        if let Some(track) = self
+            .thumbnail_receiver
+            .as_ref()
+            .and_then(|r| r.try_recv().ok())
+        {
+            self.receive_thumbnails(ctx, track);
+        }
+        self.receive_scene_changes();
+        self.receive_track_splits();
+        self.receive_silent_ranges();
+        self.prefetch_playlist_metadata();
+        self.receive_media_info();
+        self.check_file_changed(ctx);
+        self.check_audio_output();
+        self.measure_audio_levels();
+
+        // Handle seeking state - check for first frame after seek
+        if self.seeking {
+            let earliest = self
+                .seek_mode
+                .earliest_pts(self.seek_target, self.frame_interval());
+            if let Some(frame) = self.frame_queue.get_first_frame_after_seek(earliest) {
+                // Frame arrived - seek complete. The clock reports the frame shown,
+                // not the target, and the decoder starts the audio at it too.
+                tracing::debug!(pts = frame.pts, target = self.seek_target, "seek landed");
+                self.clock.sync_position(frame.pts);
+                if !self.hold_frame {
+                    self.present_frame(frame);
+                }
+                self.seeking = false;
+                if let Some(target) = self.queued_jump.take() {
+                    // Jumps made during the seek go out as one
+                    self.start_seek(target, SeekMode::Exact);
+                } else if self.state == PlayerState::Playing {
+                    // Resume audio if we were playing
+                    self.audio_output.play();
+                }
+            }
+            self.performance.reset();
+            ctx.request_repaint();
+            return;
+        }
+
+        if self.reverse_shuttle.is_some() {
+            self.update_reverse_shuttle();
+            ctx.request_repaint();
+            return;
+        }
+
+        if self.state != PlayerState::Playing {
+            self.performance.reset();
+            return;
+        }
+        if !self.video_enabled {
+            self.render_visualizer();
+        }
+
+        let audio_time = master_time.unwrap_or_else(|| self.clock.presentation_position());
+
+        if self.apply_silence_skip(audio_time) {
+            ctx.request_repaint();
+            return;
+        }
+
+        let dropped = self.frame_queue.dropped_frames();
+        let frame = self.frame_queue.get_display_frame(audio_time);
+        let drift = frame.as_ref().map(|frame| frame.pts - audio_time);
+        if let Some(frame) = frame {
+            self.present_frame(frame);
+        }
+        if let (Some(limit), Some(off)) = (
+            self.sync_policy.max_drift_before_resync,
+            self.frame_queue.drift(audio_time),
+        ) {
+            if off.abs() > limit {
+                tracing::warn!(drift = off, "video out of sync, resyncing");
+                self.start_seek(audio_time - self.range_start, SeekMode::Exact);
+                ctx.request_repaint();
+                return;
+            }
+        }
+        let dropped = (self.frame_queue.dropped_frames() - dropped) as u32;
+        if let Some((dropped_frames, drift_ms)) = self.performance.record(dropped, drift) {
+            tracing::warn!(dropped_frames, drift_ms, "playback is struggling");
+            let _ = self.event_sender.try_send(PlayerEvent::PerformanceWarning {
+                dropped_frames,
+                drift_ms,
+            });
+        }
+        self.preload_next_item(audio_time);
+
+        // Start the fade a frame early, as this only runs once per repaint
+        let end = self.range_end();
+        if self.fade_out_at_end
+            && end > 0.0
+            && end - audio_time <= f64::from(self.fade.duration_ms()) / 1000.0 + 0.02
+            && self.playlist.peek_next().is_none()
+        {
+            self.fade.fade_out();
+        }
+
+        // Check for end of stream, continuing with the next playlist item if any.
+        // Pipes have no known end, so they stay on their last frame. A virtual
+        // range ends at its end time, with frames past it still queued.
+        let at_end = match self.range_end {
+            Some(end) => audio_time >= end,
+            None => self.frame_queue.is_empty() && audio_time >= end - 0.1,
+        };
+        if self.seekable && at_end && !self.advance_playlist() {
+            self.state = PlayerState::Stopped;
+            self.audio_output.pause();
+            let _ = self.command_sender.send(DecoderCommand::Pause);
+        }
+
+        ctx.request_repaint();
+    }
+
+    /// Reopen the file whenever it changes on disk, e.g. when a render tool overwrites it.
+    /// Position and paused state are kept, and `PlayerEvent::MediaReloaded` is emitted.
+    pub fn watch_file(&mut self, enabled: bool) {
+        self.file_watch = (enabled && self.seekable).then(|| FileWatch::new(&self.path));
+    }
+
+    /// Check if the file is watched for changes
+    #[must_use]
+    pub fn is_watching_file(&self) -> bool {
+        self.file_watch.is_some()
+    }
+
+    fn check_file_changed(&mut self, ctx: &Context) {
+        let Some(ref mut watch) = self.file_watch else {
+            return;
+        };
+        // Keep polling while paused, when nothing else repaints
+        ctx.request_repaint_after(WATCH_INTERVAL);
+        if !watch.poll(&self.path) {
+            return;
+        }
+        match self.reopen_decoder() {
+            Ok(()) => {
+                self.refresh_waveform();
+                self.refresh_thumbnails();
+                self.refresh_scene_changes();
+                self.refresh_track_splits();
+                self.refresh_silent_ranges();
+                let _ = self
+                    .event_sender
+                    .try_send(PlayerEvent::MediaReloaded(self.path.clone()));
+            }
+            Err(e) => self.warn(format!("Failed to reload {}: {}", self.path.display(), e)),
+        }
+    }
+
+    /// Reopen the audio output once it has taken no samples for a while during
+    /// playback, which is how an unplugged device shows up. Retried until a
+    /// device opens.
+    fn check_audio_output(&mut self) {
+        let pulls = self.clock.pulls();
+        let now = Instant::now();
+        let playing =
+            self.state == PlayerState::Playing && !self.seeking && self.reverse_shuttle.is_none();
+        if !playing || pulls != self.output_watch.0 {
+            self.output_watch = (pulls, now);
+            return;
+        }
+        if now - self.output_watch.1 < OUTPUT_STALL_TIMEOUT {
+            return;
+        }
+
+        let first_attempt = !self.audio_device_lost;
+        if first_attempt {
+            self.audio_device_lost = true;
+            tracing::warn!("audio output stopped taking samples");
+            let _ = self.event_sender.try_send(PlayerEvent::AudioDeviceLost);
+        }
+        match self.reopen_audio_output() {
+            Ok(()) => self.audio_device_lost = false,
+            Err(e) if first_attempt => {
+                self.warn(format!("Failed to reopen the audio output: {}", e));
+            }
+            Err(_) => {}
+        }
+        self.output_watch = (self.clock.pulls(), Instant::now());
+    }
+
+    /// Open the current default audio device and restart decoding at its rate,
+    /// resuming from the position shown
+    fn reopen_audio_output(&mut self) -> Result<(), PlayerError> {
+        let position = self.position().as_secs_f64();
+        let (mut audio_output, fallback) = open_output(self.audio_backend)?;
+        if let Some(reason) = fallback {
+            let _ = self
+                .event_sender
+                .try_send(PlayerEvent::AudioOutputFallback {
+                    format: audio_output.format(),
+                    reason,
+                });
+        }
+        let pipeline = Pipeline::start(
+            &self.path,
+            audio_output.format().sample_rate,
+            self.audio_tap.clone(),
+            self.video_effects.clone(),
+            self.event_sender.clone(),
+            self.recovery,
+            self.effective_decoder_options(),
+            self.frame_budget.clone(),
+        )?;
+        audio_output.set_source(
+            AudioSource::new(
+                pipeline.audio_consumer,
+                pipeline.clock.clone(),
+                self.fade.clone(),
+            )
+            .with_balance(self.balance.clone())
+            .with_history(self.sample_history.clone()),
+        );
+        audio_output.set_volume(if self.muted { 0.0 } else { self.volume.get() });
+        audio_output.set_speed(self.speed);
+
+        retire_decoder(&self.command_sender, &self.stop_flag);
+        self.command_sender = pipeline.command_sender;
+        self.stop_flag = pipeline.stop_flag;
+        self.open_requests = pipeline.open_requests;
+        self.info_receiver = pipeline.info_receiver;
+        self.decoder_handle = Some(pipeline.decoder_handle);
+        self.audio_output = audio_output;
+        self.frame_cache.clear();
+        self.clock = pipeline.clock;
+        self.clock.set_output_latency(self.output_latency_ms);
+        self.clock.set_rate(self.speed);
+        self.clock.set_video_master(!self.sync_policy.audio_master);
+        self.frame_queue.replace_receiver(pipeline.video_receiver);
+        self.error_receiver = pipeline.error_receiver;
+        self.telemetry_receiver = pipeline.telemetry_receiver;
+        self.cache_counters = pipeline.cache_counters;
+        if self.scrub_player.is_some() {
+            self.scrub_player = Some(self.new_scrub_player());
+        }
+        self.standby = None;
+        // The new decoder opened the file afresh, with the info still awaited
+        if let Some((_, kind)) = self.pending_open.take() {
+            self.apply_media_info(pipeline.info, kind);
+        }
+
+        self.restore_decoder_state();
+        self.start_seek(position, SeekMode::Exact);
+        if !self.seeking {
+            // Audio-only playback waits for no frame
+            self.audio_output.play();
+        }
+        let _ = self.command_sender.send(DecoderCommand::Resume);
+        Ok(())
+    }
+
+    /// Take the attachments of newly opened media. Fonts are registered on the next
+    /// update; cover art stands in for the video while it is off.
+    fn set_attachments(&mut self, attachments: Vec<Attachment>) {
+        self.attachments = attachments;
+        self.attached_fonts_pending = true;
+        if !self.video_enabled {
+            self.show_cover_art();
+        }
+    }
+
+    /// Add the attached fonts to egui as one family for the subtitle overlay
+    fn register_attached_fonts(&mut self, ctx: &Context) {
+        self.attached_fonts_pending = false;
+        // egui panics on fonts it cannot parse, so check them first
+        let fonts: Vec<&Attachment> = self
+            .attachments
+            .iter()
+            .filter(|a| a.is_font() && ab_glyph::FontRef::try_from_slice(&a.data).is_ok())
+            .collect();
+        if fonts.is_empty() {
+            self.subtitle_font = None;
+            return;
+        }
+
+        let mut definitions = ctx.fonts(|f| f.lock().fonts.definitions().clone());
+        let mut names = Vec::new();
+        for font in fonts {
+            let name = format!("{}/{}", ATTACHED_FONT_FAMILY, font.filename);
+            definitions
+                .font_data
+                .insert(name.clone(), FontData::from_owned(font.data.to_vec()));
+            names.push(name);
+        }
+        // Fall back to the default fonts for glyphs the attached ones lack
+        names.extend(
+            definitions
+                .families
+                .get(&FontFamily::Proportional)
+                .cloned()
+                .unwrap_or_default(),
+        );
+        let family = FontFamily::Name(ATTACHED_FONT_FAMILY.into());
+        definitions.families.insert(family.clone(), names);
+        ctx.set_fonts(definitions);
+        self.subtitle_font = Some(family);
+    }
+
+    /// Show the media's cover art in place of the video, returning whether it has any
+    fn show_cover_art(&mut self) -> bool {
+        let Some(result) = attachments::cover_art(&self.attachments).map(attachments::decode_image)
+        else {
+            return false;
+        };
+        match result {
+            Ok(image) => {
+                self.show_still(Arc::new(image));
+                true
+            }
+            Err(e) => {
+                self.warn(format!("{:#}", e));
+                false
+            }
+        }
+    }
+
+    /// Show the cover art, or else the placeholder image, before the first frame
+    fn show_poster(&mut self) {
+        if !self.show_cover_art() {
+            if let Some(placeholder) = self.placeholder.clone() {
+                self.show_still(placeholder);
+            }
+        }
+    }
+
+    /// Upload an image that is not a decoded frame, sizing the player to it
+    fn show_still(&mut self, image: Arc<ColorImage>) {
+        self.width = image.width() as u32;
+        self.height = image.height() as u32;
+        self.has_alpha = false;
+        self.presented = None;
+        self.upload(image, None);
+    }
+
+    /// Set the image shown before the first frame of media without cover art,
+    /// e.g. a logo (black if None). Applies from the next `replace_media()`.
+    pub fn set_placeholder_image(&mut self, image: Option<ColorImage>) {
+        self.placeholder = image.map(Arc::new);
+    }
+
+    /// Draw `visualizer` in place of the video while it is off
+    /// (`set_video_enabled(false)`), at `size` pixels and `fps` frames per
+    /// second. It sees the samples as the output plays them.
+    pub fn set_visualizer(&mut self, visualizer: Box<dyn Visualizer>, size: [usize; 2], fps: f32) {
+        self.visualizer = Some(visualizer);
+        self.visualizer_image =
+            ColorImage::new([size[0].max(1), size[1].max(1)], egui::Color32::BLACK);
+        self.visualizer_interval = Duration::from_secs_f32(1.0 / fps.clamp(1.0, 120.0));
+        self.visualizer_rendered = None;
+        self.sample_history.set_active(true);
+    }
+
+    /// Remove the visualizer, returning it. Cover art, if any, shows again
+    /// while video is off.
+    pub fn clear_visualizer(&mut self) -> Option<Box<dyn Visualizer>> {
+        let visualizer = self.visualizer.take();
+        self.sample_history.set_active(self.level_meter.is_some());
+        if visualizer.is_some() && !self.video_enabled {
+            self.show_cover_art();
+        }
+        visualizer
+    }
+
+    /// Measure the levels of the audio being played, for `audio_levels`.
+    /// Metering records the played samples like a visualizer does.
+    pub fn set_level_meter(&mut self, enabled: bool) {
+        if enabled == self.level_meter.is_some() {
+            return;
+        }
+        self.level_meter = enabled.then(LevelMeter::new);
+        self.sample_history
+            .set_active(enabled || self.visualizer.is_some());
+    }
+
+    /// Levels, held peaks and clipping of the audio as heard, after volume.
+    /// None unless `set_level_meter(true)` was called.
+    #[must_use]
+    pub fn audio_levels(&self) -> Option<AudioLevels> {
+        self.level_meter.as_ref().map(LevelMeter::levels)
+    }
+
+    fn measure_audio_levels(&mut self) {
+        let Some(ref mut meter) = self.level_meter else {
+            return;
+        };
+        let samples = if self.state == PlayerState::Playing {
+            self.sample_history.snapshot()
+        } else {
+            Vec::new()
+        };
+        let gain = if self.muted { 0.0 } else { self.volume.get() };
+        meter.measure(&samples, self.clock.channels(), gain);
+    }
+
+    /// Render the next visualizer frame once its interval has passed
+    fn render_visualizer(&mut self) {
+        let Some(ref mut visualizer) = self.visualizer else {
+            return;
+        };
+        if self
+            .visualizer_rendered
+            .is_some_and(|time| time.elapsed() < self.visualizer_interval)
+        {
+            return;
+        }
+        self.visualizer_rendered = Some(Instant::now());
+        visualizer.render(
+            &self.sample_history.snapshot(),
+            self.clock.channels(),
+            self.clock.sample_rate(),
+            &mut self.visualizer_image,
+        );
+        self.show_still(Arc::new(self.visualizer_image.clone()));
+    }
+
+    /// Drop the current frame and shrink the texture to one black pixel
+    fn clear_frame(&mut self) {
+        self.current_frame = None;
+        self.presented = None;
+        self.frame_metadata = None;
+        if let Some(ref mut texture) = self.texture {
+            texture.set(
+                ColorImage::new([1, 1], egui::Color32::BLACK),
+                texture_options(self.texture_filter),
+            );
+        }
+    }
+
+    /// Upload a decoded frame to the texture and keep it as the current frame
+    fn present_frame(&mut self, frame: DecodedVideoFrame) {
+        // Filters may change the frame size
+        self.width = frame.width;
+        self.height = frame.height;
+        self.has_alpha = frame.has_alpha;
+        self.decoder_threads = frame.decoder_threads;
+
+        let pts = Duration::from_secs_f64(frame.pts.max(0.0));
+        let frame_number = (frame.pts.max(0.0) * self.frame_rate).round() as u64;
+        let _ = self
+            .event_sender
+            .try_send(PlayerEvent::FramePresented { pts, frame_number });
+        self.presented = Some((pts, frame_number));
+        self.frame_metadata = Some(frame.side_data.clone());
+
+        // Zero-copy: move pixels directly into ColorImage, shared with the texture
+        let image = Arc::new(ColorImage {
+            size: [frame.width as usize, frame.height as usize],
+            pixels: frame.pixels,
+        });
+        if self.frame_cache.capacity() > 0 {
+            let cached = CachedFrame {
+                pts: frame.pts,
+                image: image.clone(),
+                metadata: frame.side_data,
+            };
+            self.frame_cache.push(cached, self.frame_interval() * 1.5);
+        }
+        self.upload(image, Some(pts));
+    }
+
+    /// Hand an image to the frame sink, or else the texture, and keep it as the current frame
+    fn upload(&mut self, image: Arc<ColorImage>, pts: Option<Duration>) {
+        if let Some(ref mut sink) = self.frame_sink {
+            sink.present(image.clone(), pts);
+        } else if let Some(ref mut texture) = self.texture {
+            texture.set(image.clone(), texture_options(self.texture_filter));
+        }
+        self.current_frame = Some(image);
+    }
+
+    /// Send frames to a custom compositor instead of the texture, which keeps
+    /// the last frame uploaded before. Decoding, A/V sync and the other
+    /// features work as before. The current frame is presented right away.
+    pub fn set_frame_sink(&mut self, mut sink: Box<dyn FrameSink>) {
+        if let Some(ref frame) = self.current_frame {
+            sink.present(frame.clone(), None);
+        }
+        self.frame_sink = Some(sink);
+    }
+
+    /// Remove the frame sink, returning it. Frames go to the texture again from
+    /// the next one presented.
+    pub fn clear_frame_sink(&mut self) -> Option<Box<dyn FrameSink>> {
+        self.frame_sink.take()
+    }
+
+    /// Get the frame currently shown in the texture
+    #[must_use]
+    pub fn current_frame(&self) -> Option<Arc<ColorImage>> {
+        self.current_frame.clone()
+    }
+
+    /// Freeze the current frame as the reference for `CompareView`. It is kept
+    /// across seeks and media changes, so two encodes can be compared.
+    pub fn freeze_reference(&mut self, ctx: &Context) -> Result<(), PlayerError> {
+        let frame = self
+            .current_frame
+            .clone()
+            .ok_or_else(|| PlayerError::Other("No frame decoded yet".to_owned()))?;
+        let options = texture_options(self.texture_filter);
+        let at = self.position();
+        match self.reference {
+            Some((ref mut texture, ref mut image, ref mut position)) => {
+                texture.set(frame.clone(), options);
+                *image = frame;
+                *position = at;
+            }
+            None => {
+                let texture = TrackedTexture::new(ctx.load_texture(
+                    "video_reference",
+                    frame.clone(),
+                    options,
+                ));
+                self.reference = Some((texture, frame, at));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the reference frame
+    pub fn clear_reference(&mut self) {
+        self.reference = None;
+    }
+
+    /// Texture of the reference frame, if one is frozen
+    #[must_use]
+    pub fn reference_texture(&self) -> Option<&TextureHandle> {
+        self.reference.as_ref().map(|(texture, _, _)| &**texture)
+    }
+
+    /// Playback position the reference frame was frozen at
+    #[must_use]
+    pub fn reference_position(&self) -> Option<Duration> {
+        self.reference.as_ref().map(|&(_, _, position)| position)
+    }
+
+    /// Timecode, HDR metadata and GoPro telemetry carried by the frame on
+    /// screen, e.g. for dashcam overlays. None before the first frame.
+    #[must_use]
+    pub fn frame_metadata(&self) -> Option<&FrameMetadata> {
+        self.frame_metadata.as_ref()
+    }
+
+    /// Color of a pixel of the current frame at native resolution, premultiplied
+    /// if the video has alpha. None outside the frame or before the first one.
+    #[must_use]
+    pub fn pixel_at(&self, x: usize, y: usize) -> Option<Color32> {
+        let frame = self.current_frame.as_ref()?;
+        let [width, height] = frame.size;
+        (x < width && y < height).then(|| frame.pixels[y * width + x])
+    }
+
+    /// Place the current frame on the system clipboard as an image
+    pub fn copy_frame_to_clipboard(&self) -> Result<(), PlayerError> {
+        let frame = self
+            .current_frame
+            .as_ref()
+            .ok_or_else(|| PlayerError::Other("No frame decoded yet".to_owned()))?;
+        Ok(snapshot::copy_to_clipboard(frame)?)
+    }
+
+    /// Save the current frame as a PNG file
+    pub fn save_frame(&self, path: &Path) -> Result<(), PlayerError> {
+        let frame = self
+            .current_frame
+            .as_ref()
+            .ok_or_else(|| PlayerError::Other("No frame decoded yet".to_owned()))?;
+        Ok(snapshot::save_png(frame, path)?)
+    }
+
+    /// Get a copy of the current frame with the selected overlays burned in,
+    /// for QC screenshots. Overlays are sized relative to the frame, not the window.
+    pub fn capture_frame_with_overlay(
+        &self,
+        flags: OverlayFlags,
+    ) -> Result<ColorImage, PlayerError> {
+        let mut image = self
+            .current_frame
+            .as_deref()
+            .cloned()
+            .ok_or_else(|| PlayerError::Other("No frame decoded yet".to_owned()))?;
+        if flags == OverlayFlags::NONE {
+            return Ok(image);
+        }
+
+        let burn_in = BurnIn::new()?;
+        if flags.contains(OverlayFlags::TELEMETRY) {
+            if let Some(sample) = self.telemetry() {
+                burn_in.draw(&mut image, &format_sample(sample), Corner::TopLeft, true);
+            }
+        }
+        if flags.contains(OverlayFlags::TIMECODE) {
+            let timecode = format_time(self.position(), TimeDisplayMode::Smpte, self.frame_rate());
+            burn_in.draw(&mut image, &timecode, Corner::TopRight, true);
+        }
+        if flags.contains(OverlayFlags::SUBTITLES) {
+            let primary = self.subtitle();
+            #[cfg(feature = "libass")]
+            let primary = match self.ass_subtitle_image(image.size) {
+                Some(overlay) => {
+                    ass::draw_over(&mut image, &overlay);
+                    None
+                }
+                None => primary.filter(|_| self.ass_subtitles.is_none()),
+            };
+            if let Some(text) = primary {
+                burn_in.draw(&mut image, &text, Corner::BottomCenter, false);
+            }
+            if let Some(text) = self.secondary_subtitle() {
+                burn_in.draw(&mut image, &text, Corner::TopCenter, false);
+            }
+        }
+        Ok(image)
+    }
+
+    /// Save the current frame with the selected overlays burned in as a PNG file
+    pub fn save_frame_with_overlay(
+        &self,
+        path: &Path,
+        flags: OverlayFlags,
+    ) -> Result<(), PlayerError> {
+        let image = self.capture_frame_with_overlay(flags)?;
+        Ok(snapshot::save_png(&image, path)?)
+    }
+
+    /// Extract the audio track within `range` to a file on a background thread.
+    /// The range is relative to the virtual range, if one is set.
+    /// Progress and completion are reported through `poll_event()`.
+    pub fn export_audio(&self, path: &Path, format: AudioFormat, range: Range<Duration>) {
+        if !self.seekable {
+            let _ = self.event_sender.try_send(PlayerEvent::ExportFailed(
+                PlayerError::NotSeekable.to_string(),
+            ));
+            return;
+        }
+        export::start_export_thread(
+            self.path.clone(),
+            path.to_path_buf(),
+            format,
+            range.start + self.range_offset()..range.end + self.range_offset(),
+            self.event_sender.clone(),
+        );
+    }
+
+    /// Save `count` frames around the current position at native resolution,
+    /// `step` frames apart, as PNG files in `directory`, e.g. to pick the
+    /// sharpest frame of a moment. Runs on a background thread, decoding
+    /// forward from the preceding keyframe; `PlayerEvent::BurstFinished` lists
+    /// the saved files.
+    pub fn capture_burst(&self, directory: &Path, count: usize, step: usize) {
+        burst::start_burst_thread(
+            self.path.clone(),
+            directory.to_path_buf(),
+            Duration::from_secs_f64(self.media_position()),
+            count,
+            step,
+            self.event_sender.clone(),
+        );
+    }
+
+    /// Copy the incoming stream to a file without re-encoding, e.g. to capture a
+    /// live RTSP or HLS feed. Packets are saved as they are read for playback, so
+    /// pausing also pauses the recording. The container follows the file extension.
+    pub fn start_recording(&self, path: &Path) {
+        let _ = self
+            .command_sender
+            .send(DecoderCommand::StartRecording(path.to_path_buf()));
+    }
+
+    /// Finish the recording. `PlayerEvent::RecordingFinished` reports the saved file.
+    /// Opening another file also ends the recording.
+    pub fn stop_recording(&self) {
+        let _ = self.command_sender.send(DecoderCommand::StopRecording);
+    }
+
+    /// Report a non-fatal problem through the event channel
+    pub(crate) fn warn(&self, message: String) {
+        tracing::warn!("{}", message);
+        let _ = self.event_sender.try_send(PlayerEvent::Warning(message));
+    }
+
+    /// Store newly decoded telemetry samples, keeping them ordered by timestamp
+    fn receive_telemetry(&mut self) {
+        while let Ok(sample) = self.telemetry_receiver.try_recv() {
+            match self
+                .telemetry
+                .binary_search_by(|s| s.timestamp.cmp(&sample.timestamp))
+            {
+                Ok(i) => self.telemetry[i] = sample, // Re-decoded after a seek
+                Err(i) => self.telemetry.insert(i, sample),
+            }
+        }
+    }
+
+    /// Analyze the audio track in the background so `waveform()` can be drawn
+    /// along the timeline. Regenerated automatically when the media changes.
+    pub fn generate_waveform(&mut self) {
+        if !self.seekable {
+            self.waveform = None;
+            self.waveform_receiver = None;
+            self.warn("No waveform for piped input".to_owned());
+            return;
+        }
+        let (sender, receiver) = bounded(1);
+        waveform::start_waveform_thread(self.path.clone(), sender, self.event_sender.clone());
+        self.waveform = None;
+        self.waveform_receiver = Some(receiver);
+    }
+
+    /// Get the audio waveform once generated
+    #[must_use]
+    pub fn waveform(&self) -> Option<&Waveform> {
+        self.waveform.as_ref()
+    }
+
+    /// Regenerate the waveform for new media if one was requested
+    fn refresh_waveform(&mut self) {
+        if self.waveform.is_some() || self.waveform_receiver.is_some() {
+            self.generate_waveform();
+        }
+    }
+
+    /// Build low-resolution thumbnails every `interval` in the background, for
+    /// hover previews on the timeline and filmstrips. Long media gets wider
+    /// spacing. With a `cache_dir`, the sheet is stored there keyed by the
+    /// file's contents and reused across sessions. Regenerated automatically
+    /// when the media changes.
+    pub fn generate_thumbnails(&mut self, interval: Duration, cache_dir: Option<PathBuf>) {
+        self.thumbnails = None;
+        self.thumbnail_receiver = None;
+        self.thumbnail_request = Some((interval, cache_dir.clone()));
+        if !self.seekable {
+            self.warn("No thumbnails for piped input".to_owned());
+            return;
+        }
+        let (sender, receiver) = bounded(1);
+        thumbnail_track::start_thumbnail_thread(
+            self.path.clone(),
+            interval,
+            cache_dir,
+            sender,
+            self.event_sender.clone(),
+        );
+        self.thumbnail_receiver = Some(receiver);
+    }
+
+    /// Get the thumbnail track once generated
+    #[must_use]
+    pub fn thumbnails(&self) -> Option<&ThumbnailTrack> {
+        self.thumbnails.as_ref()
+    }
+
+    /// Texture and texture coordinates of the thumbnail for a playback
+    /// position, e.g. for `egui::Image::new(...).uv(rect)`
+    #[must_use]
+    pub fn thumbnail_preview(&self, position: Duration) -> Option<(TextureId, Rect)> {
+        let track = self.thumbnails.as_ref()?;
+        let texture = self.thumbnail_texture.as_ref()?;
+        let at = position + Duration::from_secs_f64(self.range_start);
+        Some((texture.id(), track.uv(track.index_at(at))))
+    }
+
+    /// Upload a finished thumbnail sheet
+    fn receive_thumbnails(&mut self, ctx: &Context, track: ThumbnailTrack) {
+        let options = texture_options(TextureFilter::Linear);
+        match self.thumbnail_texture {
+            Some(ref mut texture) => texture.set(track.sheet().clone(), options),
+            None => {
+                self.thumbnail_texture = Some(TrackedTexture::new(ctx.load_texture(
+                    "video_thumbnails",
+                    track.sheet().clone(),
+                    options,
+                )));
+            }
+        }
+        self.thumbnails = Some(track);
+        self.thumbnail_receiver = None;
+    }
+
+    /// Regenerate the thumbnails for new media if they were requested
+    fn refresh_thumbnails(&mut self) {
+        if let Some((interval, cache_dir)) = self.thumbnail_request.clone() {
+            self.generate_thumbnails(interval, cache_dir);
+        }
+    }
+
+    /// Start a background pass that finds scene cuts. Results appear in
+    /// `scene_changes()` as they are found; rerun automatically when the media changes.
+    pub fn detect_scenes(&mut self) {
+        self.scene_cancel.store(true, Ordering::Relaxed);
+        if !self.seekable {
+            self.scene_changes.clear();
+            self.scene_receiver = None;
+            self.warn("No scene detection for piped input".to_owned());
+            return;
+        }
+        self.scene_cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = bounded(64);
+        analysis::start_scene_detection_thread(
+            self.path.clone(),
+            sender,
+            self.scene_cancel.clone(),
+            self.event_sender.clone(),
+        );
+        self.scene_changes.clear();
+        self.scene_receiver = Some(receiver);
+    }
+
+    /// Get the scene cuts detected so far, within the virtual range if one is set
+    #[must_use]
+    pub fn scene_changes(&self) -> Vec<Duration> {
+        let start = self.range_offset();
+        let end = Duration::from_secs_f64(self.range_end().max(0.0));
+        self.scene_changes
+            .iter()
+            .filter(|&&scene| scene >= start && (self.range_end.is_none() || scene <= end))
+            .map(|&scene| scene - start)
+            .collect()
+    }
+
+    /// Seek to the next scene cut after the current position
+    pub fn seek_to_next_scene(&mut self) {
+        let position = self.position() + Duration::from_millis(100);
+        if let Some(scene) = self.scene_changes().into_iter().find(|&s| s > position) {
+            self.seek(scene);
+        }
+    }
+
+    /// Seek to the start of the current scene, or the previous one if just past a cut
+    pub fn seek_to_previous_scene(&mut self) {
+        let position = self.position().saturating_sub(Duration::from_secs(1));
+        let scene = self
+            .scene_changes()
+            .iter()
+            .rev()
+            .find(|&&s| s < position)
+            .copied()
+            .unwrap_or(Duration::ZERO);
+        self.seek(scene);
+    }
+
+    /// Probe playlist items added since the last call, and attach finished results
+    fn prefetch_playlist_metadata(&mut self) {
+        while let Ok((path, metadata)) = self.metadata_receiver.try_recv() {
+            self.playlist.set_metadata(&path, &metadata);
+        }
+        let pending: Vec<PathBuf> = self
+            .playlist
+            .items()
+            .iter()
+            .filter(|item| item.metadata.is_none())
+            .filter(|item| self.metadata_requested.insert(item.path.clone()))
+            .map(|item| item.path.clone())
+            .collect();
+        if !pending.is_empty() {
+            playlist::prefetch_metadata(pending, self.metadata_sender.clone());
+        }
+    }
+
+    fn receive_scene_changes(&mut self) {
+        let Some(ref receiver) = self.scene_receiver else {
+            return;
+        };
+        loop {
+            match receiver.try_recv() {
+                Ok(scene) => self.scene_changes.push(scene),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.scene_receiver = None; // Pass finished
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Rerun scene detection for new media if it was requested
+    fn refresh_scene_changes(&mut self) {
+        if !self.scene_changes.is_empty() || self.scene_receiver.is_some() {
+            self.detect_scenes();
+        }
+    }
+
+    /// Start a background pass that splits long recordings without chapters,
+    /// such as concerts or audiobooks, into tracks at long quiet gaps. The
+    /// tracks appear in `chapters()` as they are found when the media has no
+    /// chapters of its own; rerun automatically when the media changes.
+    pub fn split_tracks(&mut self) {
+        self.track_cancel.store(true, Ordering::Relaxed);
+        self.track_splits.clear();
+        self.track_receiver = None;
+        if !self.seekable {
+            self.warn("No track splitting for piped input".to_owned());
+            return;
+        }
+        self.track_cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = bounded(64);
+        analysis::start_track_split_thread(
+            self.path.clone(),
+            sender,
+            self.track_cancel.clone(),
+            self.event_sender.clone(),
+        );
+        self.track_receiver = Some(receiver);
+    }
+
+    /// Get the chapters of the media, or the tracks found by `split_tracks()`
+    /// if it has none, within the virtual range if one is set
+    #[must_use]
+    pub fn chapters(&self) -> Vec<Chapter> {
+        let duration = Duration::from_secs_f64(self.duration.max(0.0));
+        let tracks;
+        let chapters = if self.chapters.is_empty() {
+            tracks = chapters::tracks(&self.track_splits, duration, analysis::MIN_TRACK_LENGTH);
+            &tracks
+        } else {
+            &self.chapters
+        };
+        let start = self.range_offset();
+        let end = match self.range_end {
+            Some(_) => Duration::from_secs_f64(self.range_end().max(0.0)),
+            None => Duration::MAX,
+        };
+        chapters
+            .iter()
+            .filter(|chapter| chapter.end > start && chapter.start < end)
+            .map(|chapter| Chapter {
+                title: chapter.title.clone(),
+                start: chapter.start.max(start) - start,
+                end: chapter.end.min(end) - start,
+            })
+            .collect()
+    }
+
+    /// Get the chapter playing at the current position
+    #[must_use]
+    pub fn current_chapter(&self) -> Option<Chapter> {
+        let position = self.position();
+        self.chapters()
+            .into_iter()
+            .rev()
+            .find(|chapter| chapter.start <= position)
+    }
+
+    /// Seek to the start of the next chapter
+    pub fn seek_to_next_chapter(&mut self) {
+        let position = self.position() + Duration::from_millis(100);
+        if let Some(chapter) = self.chapters().into_iter().find(|c| c.start > position) {
+            self.seek(chapter.start);
+        }
+    }
+
+    /// Seek to the start of the current chapter, or the previous one if just past its start
+    pub fn seek_to_previous_chapter(&mut self) {
+        let position = self.position().saturating_sub(Duration::from_secs(1));
+        let start = self
+            .chapters()
+            .iter()
+            .rev()
+            .find(|c| c.start < position)
+            .map_or(Duration::ZERO, |c| c.start);
+        self.seek(start);
+    }
+
+    fn receive_track_splits(&mut self) {
+        let Some(ref receiver) = self.track_receiver else {
+            return;
+        };
+        loop {
+            match receiver.try_recv() {
+                Ok(split) => self.track_splits.push(split),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.track_receiver = None; // Pass finished
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Rerun track splitting for new media if it was requested
+    fn refresh_track_splits(&mut self) {
+        if !self.track_splits.is_empty() || self.track_receiver.is_some() {
+            self.split_tracks();
+        }
+    }
+
+    /// Skip or speed through quiet parts of the audio, found by a background
+    /// pass over the file; `silent_ranges()` fills in as it runs. None turns
+    /// it off. The pass reruns when the media or the detection settings change.
+    pub fn set_silence_skip(&mut self, skip: Option<SilenceSkip>) {
+        let rerun = match (&self.silence_skip, &skip) {
+            (Some(old), Some(new)) => !old.same_detection(new),
+            (_, new) => new.is_some(),
+        };
+        self.silence_skip = skip;
+        if let Some(speed) = self.silence_base_speed.take() {
+            self.set_speed(speed);
+        }
+        if skip.is_none() {
+            self.silence_cancel.store(true, Ordering::Relaxed);
+            self.silent_ranges.clear();
+            self.silence_receiver = None;
+        } else if rerun {
+            self.detect_silence();
+        }
+    }
+
+    /// Get the silence skip settings
+    #[must_use]
+    pub fn silence_skip(&self) -> Option<SilenceSkip> {
+        self.silence_skip
+    }
+
+    /// Get the quiet parts found so far, within the virtual range if one is set
+    #[must_use]
+    pub fn silent_ranges(&self) -> Vec<Range<Duration>> {
+        let start = self.range_offset();
+        let end = match self.range_end {
+            Some(_) => Duration::from_secs_f64(self.range_end().max(0.0)),
+            None => Duration::MAX,
+        };
+        self.silent_ranges
+            .iter()
+            .filter(|range| range.end > start && range.start < end)
+            .map(|range| range.start.max(start) - start..range.end.min(end) - start)
+            .collect()
+    }
+
+    fn detect_silence(&mut self) {
+        self.silence_cancel.store(true, Ordering::Relaxed);
+        self.silent_ranges.clear();
+        self.silence_receiver = None;
+        let Some(settings) = self.silence_skip else {
+            return;
+        };
+        if !self.seekable {
+            self.warn("No silence detection for piped input".to_owned());
+            return;
+        }
+        self.silence_cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = bounded(64);
+        silence::start_silence_detection_thread(
+            self.path.clone(),
+            settings,
+            sender,
+            self.silence_cancel.clone(),
+            self.event_sender.clone(),
+        );
+        self.silence_receiver = Some(receiver);
+    }
+
+    /// Find the quiet parts of new media if silence skip is on
+    fn refresh_silent_ranges(&mut self) {
+        if let Some(speed) = self.silence_base_speed.take() {
+            self.set_speed(speed);
+        }
+        if self.silence_skip.is_some() {
+            self.detect_silence();
+        }
+    }
+
+    fn receive_silent_ranges(&mut self) {
+        let Some(ref receiver) = self.silence_receiver else {
+            return;
+        };
+        loop {
+            match receiver.try_recv() {
+                Ok(range) => self.silent_ranges.push(range),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.silence_receiver = None; // Pass finished
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Skip the quiet part playing at `audio_time`, or play it faster, up to
+    /// `SILENCE_LEAD` before its end so speech does not start cut off.
+    /// Returns true if a seek was started.
+    fn apply_silence_skip(&mut self, audio_time: f64) -> bool {
+        let Some(settings) = self.silence_skip else {
+            return false;
+        };
+        let quiet_end = self
+            .silent_ranges
+            .iter()
+            .map(|range| {
+                (
+                    range.start.as_secs_f64(),
+                    range.end.as_secs_f64() - SILENCE_LEAD,
+                )
+            })
+            .find(|&(start, end)| (start..end).contains(&audio_time))
+            .map(|(_, end)| end);
+        match (settings.action, quiet_end) {
+            (SilenceAction::Skip, Some(end)) => {
+                tracing::debug!(from = audio_time, to = end, "skipping silence");
+                self.start_seek(end - self.range_start, SeekMode::Exact);
+                return true;
+            }
+            (SilenceAction::SpeedUp(speed), Some(_)) => {
+                if self.silence_base_speed.is_none() {
+                    self.silence_base_speed = Some(self.speed);
+                    self.set_speed(speed);
+                }
+            }
+            (_, None) => {
+                if let Some(speed) = self.silence_base_speed.take() {
+                    self.set_speed(speed);
+                }
+            }
+        }
+        false
+    }
+
+    /// Load an external SRT, WebVTT or ASS/SSA subtitle file, replacing any loaded
+    /// subtitles. ASS/SSA files show as plain text unless the `libass` feature
+    /// renders them with their styling (`ass_subtitle_image`).
+    pub fn load_subtitles(&mut self, path: &Path) -> Result<(), PlayerError> {
+        self.subtitles = subtitles::load_subtitles(path)?;
+        #[cfg(feature = "libass")]
+        {
+            self.ass_subtitles = None;
+            if ass::is_ass_file(path) {
+                let size = [self.width, self.height];
+                match ass::AssSubtitles::load(path, &self.attachments, size) {
+                    Ok(script) => self.ass_subtitles = Some(script),
+                    Err(e) => self.warn(format!("Showing ASS subtitles as text: {:#}", e)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove loaded subtitles
+    pub fn clear_subtitles(&mut self) {
+        self.subtitles.clear();
+        #[cfg(feature = "libass")]
+        {
+            self.ass_subtitles = None;
+        }
+    }
+
+    /// Check if ASS/SSA subtitles are loaded for rendering with their styling
+    #[cfg(feature = "libass")]
+    #[must_use]
+    pub fn has_ass_subtitles(&self) -> bool {
+        self.ass_subtitles.is_some()
+    }
+
+    /// Render the ASS/SSA subtitles at the current position, with delay and
+    /// scale applied, onto a transparent image of `size`, e.g. the on-screen
+    /// size of the video in physical pixels. The same image is returned while
+    /// nothing changes. None if nothing is shown.
+    #[cfg(feature = "libass")]
+    #[must_use]
+    pub fn ass_subtitle_image(&self, size: [usize; 2]) -> Option<Arc<ColorImage>> {
+        let script = self.ass_subtitles.as_ref()?;
+        let time = (self.media_position() - self.subtitle_delay_ms as f64 / 1000.0)
+            / f64::from(self.subtitle_scale);
+        script.render((time * 1000.0).round() as i64, size)
+    }
+
+    /// Load a second subtitle file to show at the same time as the
+    /// primary subtitles, e.g. a translation. Delay and scale apply to both.
+    pub fn load_secondary_subtitles(&mut self, path: &Path) -> Result<(), PlayerError> {
+        self.secondary_subtitles = subtitles::load_subtitles(path)?;
+        Ok(())
+    }
+
+    /// Remove the secondary subtitles
+    pub fn clear_secondary_subtitles(&mut self) {
+        self.secondary_subtitles.clear();
+    }
+
+    /// Get all secondary subtitle cues, ordered by start time
+    #[must_use]
+    pub fn secondary_subtitle_cues(&self) -> &[SubtitleCue] {
+        &self.secondary_subtitles
+    }
+
+    /// Get all loaded subtitle cues, ordered by start time
+    #[must_use]
+    pub fn subtitle_cues(&self) -> &[SubtitleCue] {
+        &self.subtitles
+    }
+
+    /// Shift subtitles later (positive) or earlier (negative) by the given milliseconds
+    pub fn set_subtitle_delay(&mut self, ms: i64) {
+        self.subtitle_delay_ms = ms;
+    }
+
+    /// Get subtitle delay in milliseconds
+    #[must_use]
+    pub fn subtitle_delay(&self) -> i64 {
+        self.subtitle_delay_ms
+    }
+
+    /// Stretch subtitle timing by a factor, e.g. 25.0 / 23.976 for a frame rate mismatch
+    pub fn set_subtitle_scale(&mut self, scale: f32) {
+        self.subtitle_scale = scale.max(0.1);
+    }
+
+    /// Get subtitle timing scale
+    #[must_use]
+    pub fn subtitle_scale(&self) -> f32 {
+        self.subtitle_scale
+    }
+
+    /// Get the subtitle text for the current playback position, with delay and scale applied
+    #[must_use]
+    pub fn subtitle(&self) -> Option<String> {
+        self.active_subtitle(&self.subtitles)
+    }
+
+    /// Get the secondary subtitle text for the current playback position
+    #[must_use]
+    pub fn secondary_subtitle(&self) -> Option<String> {
+        self.active_subtitle(&self.secondary_subtitles)
+    }
+
+    /// Playback position at which a cue appears, after the subtitle delay and scale
+    #[must_use]
+    pub fn subtitle_cue_position(&self, cue: &SubtitleCue) -> Duration {
+        Duration::from_secs_f64((self.cue_start(cue) - self.range_start).max(0.0))
+    }
+
+    /// Seek to the start of the next subtitle cue. Returns false if there is none.
+    pub fn seek_to_next_subtitle(&mut self) -> bool {
+        let position = self.media_position();
+        let next = self
+            .subtitles
+            .iter()
+            .find(|cue| self.cue_start(cue) > position + SUBTITLE_SEEK_MARGIN);
+        let Some(target) = next.map(|cue| self.subtitle_cue_position(cue)) else {
+            return false;
+        };
+        self.seek(target);
+        true
+    }
+
+    /// Seek to the start of the previous subtitle cue. Pressed just after a cue
+    /// starts, it goes to the one before. Returns false if there is none.
+    pub fn seek_to_previous_subtitle(&mut self) -> bool {
+        let position = self.media_position();
+        let previous = self
+            .subtitles
+            .iter()
+            .rev()
+            .find(|cue| self.cue_start(cue) < position - PREVIOUS_SUBTITLE_GRACE);
+        let Some(target) = previous.map(|cue| self.subtitle_cue_position(cue)) else {
+            return false;
+        };
+        self.seek(target);
+        true
+    }
+
+    /// Media time a cue appears at, in seconds
+    fn cue_start(&self, cue: &SubtitleCue) -> f64 {
+        cue.start.as_secs_f64() * f64::from(self.subtitle_scale)
+            + self.subtitle_delay_ms as f64 / 1000.0
+    }
+
+    fn active_subtitle(&self, cues: &[SubtitleCue]) -> Option<String> {
+        // Map the playback position onto the subtitle file's timeline
+        let position = self.media_position() - self.subtitle_delay_ms as f64 / 1000.0;
+        if position < 0.0 {
+            return None;
+        }
+        let cue_time = Duration::from_secs_f64(position / f64::from(self.subtitle_scale));
+
+        let started = cues.partition_point(|cue| cue.start <= cue_time);
+        let lines: Vec<&str> = cues[..started]
+            .iter()
+            .filter(|cue| cue.end > cue_time)
+            .map(|cue| cue.text.as_str())
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Get the telemetry sample for the current playback position
+    #[must_use]
+    pub fn telemetry(&self) -> Option<&TelemetrySample> {
+        let position = Duration::from_secs_f64(self.media_position());
+        let index = self.telemetry.partition_point(|s| s.timestamp <= position);
+        index.checked_sub(1).map(|i| &self.telemetry[i])
+    }
+
+    /// Get all telemetry samples decoded so far, ordered by timestamp
+    #[must_use]
+    pub fn telemetry_samples(&self) -> &[TelemetrySample] {
+        &self.telemetry
+    }
+
+    /// Get current playback statistics
+    #[must_use]
+    pub fn stats(&self) -> PlaybackStats {
+        PlaybackStats {
+            frame_intervals: self.frame_queue.frame_intervals(),
+            decoder_threads: self.decoder_threads,
+            cache: self.cache_counters.snapshot(),
+            dropped_frames: self.frame_queue.dropped_frames(),
+            frame_memory: self.frame_budget.used(),
+            frame_memory_limit: self.frame_budget.limit(),
+        }
+    }
+
+    /// Get texture handle for rendering
+    #[must_use]
+    pub fn texture(&self) -> Option<&TextureHandle> {
+        self.texture.as_deref()
+    }
+
+    /// Get the texture id, which stays the same across frames and media changes
+    /// (for use in custom meshes and paint callbacks)
+    #[must_use]
+    pub fn texture_id(&self) -> Option<TextureId> {
+        self.texture.as_deref().map(TextureHandle::id)
+    }
+
+    /// Get the path of the opened media
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get video dimensions
+    #[must_use]
+    pub fn video_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Get the average video frame rate (0.0 if unknown)
+    #[must_use]
+    pub fn frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    /// Get video duration
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64((self.range_end() - self.range_start).max(0.0))
+    }
+
+    /// Get current playback position: the seek target while a seek is in
+    /// progress, then the PTS of the frame it landed on
+    #[must_use]
+    pub fn position(&self) -> Duration {
+        Duration::from_secs_f64((self.media_position() - self.range_start).max(0.0))
+    }
+
+    /// Position in the file, ignoring the virtual range (seconds)
+    fn media_position(&self) -> f64 {
+        if self.seeking {
+            self.seek_target // Show seek target while seeking
+        } else {
+            self.clock.position()
+        }
+    }
+
+    /// Play only the part of the file between `start` and `end`, as if it were
+    /// the whole media: `duration()`, `position()` and seeks are relative to
+    /// `start`, and playback stops at `end`. Useful for clips referenced
+    /// inside long recordings. Moves to `start` if the position is outside.
+    pub fn set_virtual_range(&mut self, start: Duration, end: Duration) -> Result<(), PlayerError> {
+        if !self.seekable {
+            return Err(PlayerError::NotSeekable);
+        }
+        let mut end = end.as_secs_f64();
+        if self.duration > 0.0 {
+            end = end.min(self.duration);
+        }
+        let start = start.as_secs_f64();
+        if start >= end {
+            return Err(PlayerError::Other(format!(
+                "Virtual range start {:.3}s is not before its end {:.3}s",
+                start, end
+            )));
+        }
+        let position = self.media_position();
+        self.range_start = start;
+        self.range_end = Some(end);
+        self.seek_history.clear();
+        if !(start..end).contains(&position) {
+            self.start_seek(0.0, SeekMode::Exact);
+        }
+        Ok(())
+    }
+
+    /// Treat the whole file as the media again
+    pub fn clear_virtual_range(&mut self) {
+        self.range_start = 0.0;
+        self.range_end = None;
+        self.seek_history.clear();
+    }
+
+    /// Get the part of the file played as the whole media, if limited
+    #[must_use]
+    pub fn virtual_range(&self) -> Option<Range<Duration>> {
+        self.range_end
+            .map(|end| Duration::from_secs_f64(self.range_start)..Duration::from_secs_f64(end))
+    }
+
+    /// End of the media or of the virtual range (seconds)
+    fn range_end(&self) -> f64 {
+        self.range_end.unwrap_or(self.duration)
+    }
+
+    fn range_offset(&self) -> Duration {
+        Duration::from_secs_f64(self.range_start)
+    }
+
+    /// Check if currently playing
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.state == PlayerState::Playing
+    }
+
+    /// Get player state
+    #[must_use]
+    pub fn state(&self) -> PlayerState {
+        self.state
+    }
+
+    /// Poll for decoder errors (non-blocking)
+    #[must_use]
+    pub fn error(&self) -> Option<PlayerError> {
+        self.error_receiver.try_recv().ok()
+    }
+
+    /// Poll for player events such as recovered decoder errors (non-blocking)
+    #[must_use]
+    pub fn poll_event(&self) -> Option<PlayerEvent> {
+        self.event_receiver.try_recv().ok()
+    }
+}
+
+/// Prepend the deinterlacer and frame interpolation to the custom video filter
+fn effective_decoder_options(
+    options: &DecoderOptions,
+    deinterlace: bool,
+    interpolation: Option<String>,
+) -> DecoderOptions {
+    let mut options = options.clone();
+    let filters: Vec<String> = deinterlace
+        .then(|| "yadif".to_owned())
+        .into_iter()
+        .chain(interpolation)
+        .chain(options.video_filter.take())
+        .collect();
+    if !filters.is_empty() {
+        options.video_filter = Some(filters.join(","));
+    }
+    options
+}
+
+/// Texture options using the same filter for magnification and minification
+fn texture_options(filter: TextureFilter) -> TextureOptions {
+    TextureOptions {
+        magnification: filter,
+        minification: filter,
+        ..TextureOptions::LINEAR
     }
 }
 
 impl Drop for VideoPlayer {
     fn drop(&mut self) {
-        // Signal decoder to stop
+        // Signal decoder and analysis threads to stop
         self.stop_flag.store(true, Ordering::Relaxed);
+        self.scene_cancel.store(true, Ordering::Relaxed);
+        self.track_cancel.store(true, Ordering::Relaxed);
+        self.silence_cancel.store(true, Ordering::Relaxed);
         let _ = self.command_sender.send(DecoderCommand::Stop);
 
         // Wait for decoder thread
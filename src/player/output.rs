@@ -0,0 +1,358 @@
+use crate::diagnostics::{Resource, ResourceGuard};
+use rodio::buffer::SamplesBuffer;
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::cpal::{Device, SampleFormat, SampleRate, SupportedStreamConfig};
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::cmp::Reverse;
+
+use super::audio::AudioSource;
+use super::error::PlayerError;
+#[cfg(any(feature = "audio-cpal", feature = "audio-sdl"))]
+use super::mixer::{Mixer, MixerOutput};
+
+/// Channels the decoder mixes to; the output maps them onto the device's layout
+pub const OUTPUT_CHANNELS: u16 = 2;
+
+/// Rate used when the device does not report its default
+const FALLBACK_RATE: u32 = 48000;
+
+/// Library the audio is played through. Switch away from rodio when its
+/// buffering adds latency or it mishandles a device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AudioBackend {
+    /// rodio's mixer on the default cpal device
+    #[default]
+    Rodio,
+    /// A cpal stream filled directly, without rodio's mixer and its queue
+    #[cfg(feature = "audio-cpal")]
+    Cpal,
+    /// SDL2's audio subsystem. On macOS, open the player from the main thread.
+    #[cfg(feature = "audio-sdl")]
+    Sdl,
+}
+
+/// Format the audio device was opened with. Decoded audio is resampled to
+/// this rate, so the output does not resample a second time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioOutputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl AudioOutputFormat {
+    fn of(config: &SupportedStreamConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+        }
+    }
+}
+
+/// An open audio device playing the decoder's audio. Outputs open paused.
+pub(crate) trait AudioOutput {
+    /// Format the device was opened with
+    fn format(&self) -> AudioOutputFormat;
+
+    /// Play `source` in place of the current one, without a gap
+    fn set_source(&mut self, source: AudioSource);
+
+    fn play(&self);
+
+    fn pause(&self);
+
+    fn set_volume(&self, volume: f32);
+
+    /// Set the playback rate. Audio is resampled, so pitch changes.
+    fn set_speed(&self, speed: f32);
+
+    /// Output for scrub snippets, mixed over the source
+    fn snippets(&self) -> Box<dyn SnippetOutput>;
+}
+
+/// Plays short clips of interleaved stereo at the output rate
+pub(crate) trait SnippetOutput: Send {
+    /// Play `samples`, cutting off the previous clip
+    fn play(&mut self, samples: Vec<f32>, volume: f32);
+}
+
+/// Open the default device through `backend`. Also returns why the preferred
+/// f32 stereo format at the device rate was not used.
+pub(crate) fn open_output(
+    backend: AudioBackend,
+) -> Result<(Box<dyn AudioOutput>, Option<String>), PlayerError> {
+    match backend {
+        AudioBackend::Rodio => {
+            let (output, fallback) = RodioOutput::open()?;
+            Ok((Box::new(output), fallback))
+        }
+        #[cfg(feature = "audio-cpal")]
+        AudioBackend::Cpal => open_cpal(),
+        #[cfg(feature = "audio-sdl")]
+        AudioBackend::Sdl => open_sdl(),
+    }
+}
+
+fn default_device() -> Result<Device, PlayerError> {
+    rodio::cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| PlayerError::AudioDevice("No audio output device".to_owned()))
+}
+
+/// Output through a rodio sink
+struct RodioOutput {
+    _stream: OutputStream, // Keep alive
+    _guard: ResourceGuard,
+    handle: OutputStreamHandle,
+    sink: Sink,
+    format: AudioOutputFormat,
+}
+
+impl RodioOutput {
+    /// Open the default device in the format closest to f32 stereo at the
+    /// device's own rate, falling back to rodio's default configuration
+    fn open() -> Result<(Self, Option<String>), PlayerError> {
+        let device = default_device()?;
+
+        let fallback = match negotiate(&device) {
+            Some((config, fallback)) => {
+                let format = AudioOutputFormat::of(&config);
+                let sample_format = config.sample_format();
+                match OutputStream::try_from_device_config(&device, config) {
+                    Ok((stream, handle)) => {
+                        return Ok((Self::new(stream, handle, format)?, fallback));
+                    }
+                    Err(e) => format!(
+                        "{} Hz {}-channel {} output failed ({}), using the default configuration",
+                        format.sample_rate, format.channels, sample_format, e
+                    ),
+                }
+            }
+            None => {
+                "Device formats could not be queried, using the default configuration".to_owned()
+            }
+        };
+
+        let (stream, handle) =
+            OutputStream::try_default().map_err(|e| PlayerError::AudioDevice(e.to_string()))?;
+        let format = device.default_output_config().map_or(
+            AudioOutputFormat {
+                sample_rate: FALLBACK_RATE,
+                channels: OUTPUT_CHANNELS,
+            },
+            |config| AudioOutputFormat::of(&config),
+        );
+        Ok((Self::new(stream, handle, format)?, Some(fallback)))
+    }
+
+    fn new(
+        stream: OutputStream,
+        handle: OutputStreamHandle,
+        format: AudioOutputFormat,
+    ) -> Result<Self, PlayerError> {
+        let sink = Sink::try_new(&handle).map_err(|e| PlayerError::AudioDevice(e.to_string()))?;
+        sink.pause();
+        Ok(Self {
+            _stream: stream,
+            _guard: ResourceGuard::new(Resource::AudioOutput),
+            handle,
+            sink,
+            format,
+        })
+    }
+}
+
+impl AudioOutput for RodioOutput {
+    fn format(&self) -> AudioOutputFormat {
+        self.format
+    }
+
+    fn set_source(&mut self, source: AudioSource) {
+        // Queue the new source behind the old one, then drop the old one
+        self.sink.append(source);
+        if self.sink.len() > 1 {
+            self.sink.skip_one();
+        }
+    }
+
+    fn play(&self) {
+        self.sink.play();
+    }
+
+    fn pause(&self) {
+        self.sink.pause();
+    }
+
+    fn set_volume(&self, volume: f32) {
+        self.sink.set_volume(volume);
+    }
+
+    fn set_speed(&self, speed: f32) {
+        self.sink.set_speed(speed);
+    }
+
+    fn snippets(&self) -> Box<dyn SnippetOutput> {
+        Box::new(RodioSnippets {
+            handle: self.handle.clone(),
+            sample_rate: self.format.sample_rate,
+            current: None,
+        })
+    }
+}
+
+/// Plays each snippet on its own sink, stopping the previous one
+struct RodioSnippets {
+    handle: OutputStreamHandle,
+    sample_rate: u32,
+    current: Option<Sink>,
+}
+
+impl SnippetOutput for RodioSnippets {
+    fn play(&mut self, samples: Vec<f32>, volume: f32) {
+        let Ok(sink) = Sink::try_new(&self.handle) else {
+            return;
+        };
+        sink.set_volume(volume);
+        sink.append(SamplesBuffer::new(
+            OUTPUT_CHANNELS,
+            self.sample_rate,
+            samples,
+        ));
+        if let Some(previous) = self.current.replace(sink) {
+            previous.stop();
+        }
+    }
+}
+
+/// Open the default device as a cpal stream filled by a `Mixer`
+#[cfg(feature = "audio-cpal")]
+fn open_cpal() -> Result<(Box<dyn AudioOutput>, Option<String>), PlayerError> {
+    use rodio::cpal::traits::StreamTrait;
+
+    let device = default_device()?;
+    let (config, fallback) = negotiate(&device).ok_or_else(|| {
+        PlayerError::AudioDevice("Device formats could not be queried".to_owned())
+    })?;
+    let format = AudioOutputFormat::of(&config);
+    let mixer = Mixer::default();
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build_cpal_stream::<f32>(&device, &config, mixer.clone()),
+        SampleFormat::I16 => build_cpal_stream::<i16>(&device, &config, mixer.clone()),
+        SampleFormat::U16 => build_cpal_stream::<u16>(&device, &config, mixer.clone()),
+        other => {
+            return Err(PlayerError::AudioDevice(format!(
+                "Unsupported sample format {}",
+                other
+            )))
+        }
+    }
+    .map_err(|e| PlayerError::AudioDevice(e.to_string()))?;
+    stream
+        .play()
+        .map_err(|e| PlayerError::AudioDevice(e.to_string()))?;
+    Ok((Box::new(MixerOutput::new(stream, mixer, format)), fallback))
+}
+
+#[cfg(feature = "audio-cpal")]
+fn build_cpal_stream<T>(
+    device: &Device,
+    config: &SupportedStreamConfig,
+    mixer: Mixer,
+) -> Result<rodio::cpal::Stream, rodio::cpal::BuildStreamError>
+where
+    T: rodio::cpal::SizedSample + rodio::cpal::FromSample<f32>,
+{
+    let channels = config.channels();
+    let mut buffer = Vec::new();
+    device.build_output_stream(
+        &config.config(),
+        move |data: &mut [T], _| {
+            buffer.resize(data.len(), 0.0);
+            mixer.fill(&mut buffer, channels);
+            for (out, &sample) in data.iter_mut().zip(&buffer) {
+                *out = T::from_sample(sample);
+            }
+        },
+        |e| tracing::warn!(error = %e, "audio stream error"),
+        None,
+    )
+}
+
+/// Open the default device through SDL2, which converts from f32 stereo
+/// to the device's format itself
+#[cfg(feature = "audio-sdl")]
+fn open_sdl() -> Result<(Box<dyn AudioOutput>, Option<String>), PlayerError> {
+    use sdl2::audio::AudioSpecDesired;
+
+    let audio = sdl2::init()
+        .and_then(|sdl| sdl.audio())
+        .map_err(PlayerError::AudioDevice)?;
+    let desired = AudioSpecDesired {
+        freq: None,
+        channels: Some(OUTPUT_CHANNELS as u8),
+        samples: None,
+    };
+    let mixer = Mixer::default();
+    let device = audio
+        .open_playback(None, &desired, |spec| SdlCallback {
+            mixer: mixer.clone(),
+            channels: u16::from(spec.channels),
+        })
+        .map_err(PlayerError::AudioDevice)?;
+    let format = AudioOutputFormat {
+        sample_rate: device.spec().freq.max(1) as u32,
+        channels: u16::from(device.spec().channels),
+    };
+    device.resume();
+    Ok((Box::new(MixerOutput::new(device, mixer, format)), None))
+}
+
+#[cfg(feature = "audio-sdl")]
+struct SdlCallback {
+    mixer: Mixer,
+    channels: u16,
+}
+
+#[cfg(feature = "audio-sdl")]
+impl sdl2::audio::AudioCallback for SdlCallback {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.mixer.fill(out, self.channels);
+    }
+}
+
+/// Pick the supported configuration closest to f32 stereo at the device's
+/// default rate. Returns it with a description of any compromise made.
+fn negotiate(device: &Device) -> Option<(SupportedStreamConfig, Option<String>)> {
+    let rate = device
+        .default_output_config()
+        .map_or(FALLBACK_RATE, |config| config.sample_rate().0);
+    let best = device
+        .supported_output_configs()
+        .ok()?
+        .max_by_key(|range| {
+            (
+                range.channels() == OUTPUT_CHANNELS,
+                range.sample_format() == SampleFormat::F32,
+                (range.min_sample_rate().0..=range.max_sample_rate().0).contains(&rate),
+                Reverse(range.channels().abs_diff(OUTPUT_CHANNELS)),
+                range.sample_format().sample_size(),
+            )
+        })?;
+
+    let config_rate = rate.clamp(best.min_sample_rate().0, best.max_sample_rate().0);
+    let config = best.with_sample_rate(SampleRate(config_rate));
+    let exact = config.channels() == OUTPUT_CHANNELS
+        && config.sample_format() == SampleFormat::F32
+        && config_rate == rate;
+    let fallback = (!exact).then(|| {
+        format!(
+            "No f32 stereo output at {} Hz, using {} Hz {}-channel {}",
+            rate,
+            config_rate,
+            config.channels(),
+            config.sample_format()
+        )
+    });
+    Some((config, fallback))
+}
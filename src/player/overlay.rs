@@ -0,0 +1,155 @@
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use anyhow::{anyhow, Result};
+use egui::{Color32, ColorImage, FontDefinitions};
+use std::ops::{BitOr, BitOrAssign};
+
+/// Overlays burned into a captured frame by `VideoPlayer::capture_frame_with_overlay`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverlayFlags(u8);
+
+impl OverlayFlags {
+    pub const NONE: Self = Self(0);
+    /// The subtitle cue on screen, centered near the bottom
+    pub const SUBTITLES: Self = Self(1);
+    /// The telemetry OSD, in the top-left corner
+    pub const TELEMETRY: Self = Self(1 << 1);
+    /// SMPTE timecode of the frame, in the top-right corner
+    pub const TIMECODE: Self = Self(1 << 2);
+    pub const ALL: Self = Self(0b111);
+
+    /// Check if all overlays in `other` are set
+    #[must_use]
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for OverlayFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for OverlayFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// Where a text box is placed on the frame
+#[derive(Clone, Copy)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    TopCenter,
+    BottomCenter,
+}
+
+/// Draws text boxes into frames using egui's built-in fonts
+pub struct BurnIn {
+    proportional: FontVec,
+    monospace: FontVec,
+}
+
+impl BurnIn {
+    pub fn new() -> Result<Self> {
+        let mut fonts = FontDefinitions::default();
+        let mut load = |name: &str| {
+            let data = fonts
+                .font_data
+                .remove(name)
+                .ok_or_else(|| anyhow!("Font {} is not available", name))?;
+            FontVec::try_from_vec_and_index(data.font.into_owned(), data.index)
+                .map_err(|e| anyhow!("Failed to load font {}: {}", name, e))
+        };
+        Ok(Self {
+            proportional: load("Ubuntu-Light")?,
+            monospace: load("Hack")?,
+        })
+    }
+
+    /// Draw `text` on a translucent box, like the on-screen overlays.
+    /// Text size follows the frame height so captures of any resolution look alike.
+    pub fn draw(&self, image: &mut ColorImage, text: &str, corner: Corner, monospace: bool) {
+        let height = image.height() as f32;
+        let (font, px) = if monospace {
+            (&self.monospace, (height / 45.0).max(12.0))
+        } else {
+            (&self.proportional, (height * 0.05).max(14.0))
+        };
+        let font = font.as_scaled(PxScale::from(px));
+
+        let lines: Vec<&str> = text.lines().collect();
+        let widths: Vec<f32> = lines
+            .iter()
+            .map(|line| line.chars().map(|c| font.h_advance(font.glyph_id(c))).sum())
+            .collect();
+        let line_height = font.height() + font.line_gap();
+        let padding = (px / 3.0).round();
+        let margin = (height * 0.02).round();
+        let box_width = widths.iter().copied().fold(0.0, f32::max) + padding * 2.0;
+        let box_height = line_height * lines.len() as f32 + padding * 2.0;
+
+        let width = image.width() as f32;
+        let (left, top) = match corner {
+            Corner::TopLeft => (margin, margin),
+            Corner::TopRight => (width - margin - box_width, margin),
+            Corner::TopCenter => ((width - box_width) / 2.0, height * 0.06),
+            Corner::BottomCenter => ((width - box_width) / 2.0, height * 0.94 - box_height),
+        };
+        fill_rect(
+            image,
+            [left, top, left + box_width, top + box_height],
+            Color32::from_black_alpha(160),
+        );
+
+        for (i, (line, line_width)) in lines.iter().zip(&widths).enumerate() {
+            let mut x = left + padding;
+            if let Corner::TopCenter | Corner::BottomCenter = corner {
+                x += (box_width - padding * 2.0 - line_width) / 2.0;
+            }
+            let baseline = top + padding + font.ascent() + line_height * i as f32;
+            for c in line.chars() {
+                let mut glyph = font.scaled_glyph(c);
+                glyph.position = point(x, baseline);
+                x += font.h_advance(glyph.id);
+                let Some(outlined) = font.outline_glyph(glyph) else {
+                    continue; // Whitespace
+                };
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    let px = bounds.min.x as i64 + i64::from(gx);
+                    let py = bounds.min.y as i64 + i64::from(gy);
+                    blend(image, px, py, Color32::WHITE.gamma_multiply(coverage));
+                });
+            }
+        }
+    }
+}
+
+fn fill_rect(image: &mut ColorImage, [x0, y0, x1, y1]: [f32; 4], color: Color32) {
+    for y in y0.max(0.0) as i64..y1.max(0.0) as i64 {
+        for x in x0.max(0.0) as i64..x1.max(0.0) as i64 {
+            blend(image, x, y, color);
+        }
+    }
+}
+
+/// Draw a premultiplied color over the pixel at (x, y), ignoring points outside the image
+fn blend(image: &mut ColorImage, x: i64, y: i64, color: Color32) {
+    let [width, height] = image.size;
+    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+        return;
+    }
+    let pixel = &mut image.pixels[y as usize * width + x as usize];
+    let keep = 255 - u16::from(color.a());
+    let mix = |src: u8, dst: u8| (u16::from(src) + u16::from(dst) * keep / 255) as u8;
+    *pixel = Color32::from_rgba_premultiplied(
+        mix(color.r(), pixel.r()),
+        mix(color.g(), pixel.g()),
+        mix(color.b(), pixel.b()),
+        mix(color.a(), pixel.a()),
+    );
+}
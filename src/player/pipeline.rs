@@ -0,0 +1,212 @@
+use anyhow::Result;
+use crossbeam_channel::{bounded, unbounded, Receiver, SendError, Sender};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use super::budget::FrameBudget;
+use super::cache::SharedCacheCounters;
+use super::clock::AudioClock;
+use super::decoder::{
+    probe_media, start_decoder_thread, DecodedVideoFrame, DecoderCommand, DecoderOptions,
+    MediaInfo, OpenRequests, RecoveryPolicy,
+};
+use super::effect::SharedVideoEffects;
+use super::error::PlayerError;
+use super::events::PlayerEvent;
+use super::output::OUTPUT_CHANNELS;
+use super::ring_buffer::{audio_ring, AudioConsumer};
+use super::tap::SharedAudioTap;
+use super::telemetry::TelemetrySample;
+
+/// Decoder thread and the channels carrying one media item's frames, audio and errors
+pub struct Pipeline {
+    pub info: MediaInfo,
+    pub decoder_handle: JoinHandle<()>,
+    pub command_sender: Sender<DecoderCommand>,
+    pub stop_flag: Arc<AtomicBool>,
+    /// Raised before each `Open` command, so the decoder drops superseded opens
+    pub open_requests: OpenRequests,
+    pub video_receiver: Receiver<DecodedVideoFrame>,
+    pub audio_consumer: AudioConsumer,
+    pub clock: AudioClock,
+    pub error_receiver: Receiver<PlayerError>,
+    /// Info of each file opened by an `Open` command, tagged with its number
+    pub info_receiver: Receiver<(u64, MediaInfo)>,
+    pub telemetry_receiver: Receiver<TelemetrySample>,
+    pub cache_counters: SharedCacheCounters,
+}
+
+impl Pipeline {
+    /// Probe the file and start a paused decoder thread for it.
+    /// Audio is resampled to `output_rate`, the rate of the audio device.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        path: &Path,
+        output_rate: u32,
+        audio_tap: SharedAudioTap,
+        video_effects: SharedVideoEffects,
+        event_sender: Sender<PlayerEvent>,
+        recovery: RecoveryPolicy,
+        options: DecoderOptions,
+        frame_budget: FrameBudget,
+    ) -> Result<Self> {
+        // Probe media file
+        let info = probe_media(path, &options)?;
+
+        // Create audio clock
+        let clock = AudioClock::new(output_rate, OUTPUT_CHANNELS);
+
+        // Create the audio queue (about 1 second of buffer)
+        let buffer_size = output_rate as usize * OUTPUT_CHANNELS as usize * 2;
+        let (audio_producer, audio_consumer) = audio_ring(buffer_size);
+
+        // Create video frame channel
+        let (video_sender, video_receiver) = bounded(30);
+
+        // Create command channel
+        let (command_sender, command_receiver) = bounded(16);
+
+        // Create error channel
+        let (error_sender, error_receiver) = bounded(4);
+
+        // Media info channel, for files opened after the first
+        let (info_sender, info_receiver) = unbounded();
+
+        // Create telemetry channel
+        let (telemetry_sender, telemetry_receiver) = bounded(256);
+
+        // Network cache counters, read for the playback stats
+        let cache_counters = SharedCacheCounters::default();
+
+        // Start decoder thread
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let open_requests = OpenRequests::default();
+        let decoder_handle = start_decoder_thread(
+            path,
+            video_sender,
+            audio_producer,
+            command_receiver,
+            clock.clone(),
+            stop_flag.clone(),
+            error_sender,
+            info_sender,
+            event_sender,
+            telemetry_sender,
+            audio_tap,
+            video_effects,
+            cache_counters.clone(),
+            frame_budget,
+            recovery,
+            options,
+            open_requests.clone(),
+        )?;
+
+        Ok(Self {
+            info,
+            decoder_handle,
+            command_sender,
+            stop_flag,
+            open_requests,
+            video_receiver,
+            audio_consumer,
+            clock,
+            error_receiver,
+            info_receiver,
+            telemetry_receiver,
+            cache_counters,
+        })
+    }
+}
+
+/// Signal a decoder thread to exit without waiting for it
+pub fn retire_decoder(command_sender: &Sender<DecoderCommand>, stop_flag: &AtomicBool) {
+    stop_flag.store(true, Ordering::Relaxed);
+    let _ = command_sender.try_send(DecoderCommand::Stop);
+}
+
+/// The next playlist item, opened and prebuffered in the background so
+/// auto-advance can hand it over without waiting for the decoder
+pub struct Standby {
+    path: PathBuf,
+    receiver: Receiver<Result<Pipeline>>,
+    pipeline: Option<Pipeline>,
+}
+
+impl Standby {
+    /// Start probing `path` on a helper thread. Once open, the decoder runs
+    /// until its frame channel is full and then waits for the handoff. Its
+    /// clock stays stopped until then, as nothing of the item plays yet.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        path: &Path,
+        output_rate: u32,
+        audio_tap: SharedAudioTap,
+        video_effects: SharedVideoEffects,
+        event_sender: Sender<PlayerEvent>,
+        recovery: RecoveryPolicy,
+        options: DecoderOptions,
+        frame_budget: FrameBudget,
+    ) -> Self {
+        let (sender, receiver) = bounded(1);
+        let standby_path = path.to_path_buf();
+        thread::spawn(move || {
+            let result = Pipeline::start(
+                &standby_path,
+                output_rate,
+                audio_tap,
+                video_effects,
+                event_sender,
+                recovery,
+                options,
+                frame_budget,
+            );
+            if let Ok(ref pipeline) = result {
+                let _ = pipeline.command_sender.send(DecoderCommand::Prebuffer);
+            }
+            // A dropped receiver means the standby was cancelled. Stop the
+            // decoder right away, even if it is still opening the file.
+            if let Err(SendError(Ok(pipeline))) = sender.send(result) {
+                retire_decoder(&pipeline.command_sender, &pipeline.stop_flag);
+            }
+        });
+
+        Self {
+            path: path.to_path_buf(),
+            receiver,
+            pipeline: None,
+        }
+    }
+
+    /// File this standby is preparing
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Collect the pipeline if the helper thread finished.
+    /// Returns the open error once if the item could not be opened.
+    pub fn poll(&mut self) -> Result<()> {
+        if self.pipeline.is_none() {
+            if let Ok(result) = self.receiver.try_recv() {
+                self.pipeline = Some(result?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the prebuffered pipeline, if it is ready
+    pub fn into_pipeline(mut self) -> Option<Pipeline> {
+        let _ = self.poll();
+        self.pipeline.take()
+    }
+}
+
+impl Drop for Standby {
+    fn drop(&mut self) {
+        // Cancelled before the handoff, so only the player's decoder reaches the output
+        if let Some(ref pipeline) = self.pipeline {
+            retire_decoder(&pipeline.command_sender, &pipeline.stop_flag);
+        }
+    }
+}
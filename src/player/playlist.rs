@@ -0,0 +1,483 @@
+use crossbeam_channel::Sender;
+use egui::ColorImage;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use super::decoder::{is_network_source, is_pipe, probe_media, DecoderOptions};
+use super::error::PlayerError;
+use super::thumbnail;
+
+/// Size limit of the preview frame in `ItemMetadata`
+const THUMBNAIL_SIZE: [u32; 2] = [160, 90];
+/// Lines checked for HLS tags, which follow the `#EXTM3U` header
+const HLS_SNIFF_LINES: usize = 16;
+/// Levels of playlists listed in playlists that are expanded
+const MAX_NESTING: usize = 8;
+
+/// Details of a playlist item, probed in the background once it is queued
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ItemMetadata {
+    /// Zero for live streams
+    pub duration: Duration,
+    pub width: u32,
+    pub height: u32,
+    /// Title tag of the container
+    pub title: Option<String>,
+    /// Preview frame for queue lists (not saved with the playlist)
+    #[serde(skip)]
+    pub thumbnail: Option<ColorImage>,
+}
+
+impl ItemMetadata {
+    /// Probe a file's length, size and title and grab a thumbnail from a tenth of the way in.
+    /// Blocks; network streams get no thumbnail.
+    pub(crate) fn probe(path: &Path) -> Option<Self> {
+        let info = probe_media(path, &DecoderOptions::default()).ok()?;
+        let thumbnail = (!is_network_source(path))
+            .then(|| {
+                thumbnail::generate(
+                    path,
+                    Duration::from_secs_f64(info.duration / 10.0),
+                    THUMBNAIL_SIZE,
+                )
+                .ok()
+            })
+            .flatten();
+        Some(Self {
+            duration: Duration::from_secs_f64(info.duration),
+            width: info.width,
+            height: info.height,
+            title: info.title,
+            thumbnail,
+        })
+    }
+}
+
+/// An entry in a `Playlist`: a local path or URL, with an optional display title
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistItem {
+    pub path: PathBuf,
+    /// Title from the playlist file (EXTINF or PLS TitleN)
+    pub title: Option<String>,
+    /// Position in the unshuffled order
+    #[serde(default)]
+    original_index: usize,
+    /// Length, size and thumbnail, filled in by the player in the background
+    #[serde(default)]
+    pub metadata: Option<ItemMetadata>,
+}
+
+impl PlaylistItem {
+    fn new(path: PathBuf, title: Option<String>, original_index: usize) -> Self {
+        Self {
+            path,
+            title,
+            original_index,
+            metadata: None,
+        }
+    }
+
+    /// Title from the playlist or the file's tags if known, otherwise the file name
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        let tagged = self.metadata.as_ref().and_then(|m| m.title.as_ref());
+        match self.title.as_ref().or(tagged) {
+            Some(title) => title.clone(),
+            None => self
+                .path
+                .file_name()
+                .unwrap_or(self.path.as_os_str())
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+}
+
+/// What happens when a playlist item ends
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepeatMode {
+    /// Stop after the last item
+    #[default]
+    Off,
+    /// Play the current item again
+    One,
+    /// Start over after the last item
+    All,
+}
+
+/// Ordered list of media files played back to back.
+/// The player advances to the next item automatically when one ends.
+/// Serialize it with any serde format to save and restore a session.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Playlist {
+    items: Vec<PlaylistItem>,
+    current: usize,
+    repeat: RepeatMode,
+    shuffled: bool,
+}
+
+impl Playlist {
+    /// Create a playlist positioned at the first item
+    #[must_use]
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let items = paths
+            .into_iter()
+            .enumerate()
+            .map(|(i, path)| PlaylistItem::new(path, None, i))
+            .collect();
+        Self {
+            items,
+            ..Default::default()
+        }
+    }
+
+    /// Read an M3U, M3U8 or PLS playlist file.
+    /// Relative entries are resolved against the playlist's folder, and listed
+    /// playlists are expanded in place, up to 8 levels deep and each once.
+    pub fn load(path: &Path) -> Result<Self, PlayerError> {
+        let items = load_items(path, 0, &mut HashSet::new())?;
+        if items.is_empty() {
+            return Err(PlayerError::Other(format!(
+                "Playlist {} has no entries",
+                path.display()
+            )));
+        }
+        let mut playlist = Self {
+            items,
+            ..Default::default()
+        };
+        playlist.renumber();
+        Ok(playlist)
+    }
+
+    /// Append an item to the end of the queue
+    pub fn push(&mut self, path: PathBuf) {
+        self.items
+            .push(PlaylistItem::new(path, None, self.items.len()));
+    }
+
+    /// Queue an item to play after the current one
+    pub fn insert_next(&mut self, path: PathBuf) {
+        let index = (self.current + 1).min(self.items.len());
+        // Also follows the current item once the shuffle is undone
+        let original_index = self
+            .items
+            .get(self.current)
+            .map_or(0, |item| item.original_index + 1);
+        for item in &mut self.items {
+            if item.original_index >= original_index {
+                item.original_index += 1;
+            }
+        }
+        self.items
+            .insert(index, PlaylistItem::new(path, None, original_index));
+    }
+
+    /// Move the item at `from` to `to`, shifting the items between.
+    /// The current item stays current.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from >= self.items.len() || to >= self.items.len() {
+            return;
+        }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        if self.current == from {
+            self.current = to;
+        } else if from < self.current && self.current <= to {
+            self.current -= 1;
+        } else if to <= self.current && self.current < from {
+            self.current += 1;
+        }
+        if !self.shuffled {
+            self.renumber();
+        }
+    }
+
+    /// Remove the item at `index`. Removing the current item makes the one after it current.
+    pub fn remove(&mut self, index: usize) -> Option<PlaylistItem> {
+        if index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(index);
+        if index < self.current {
+            self.current -= 1;
+        }
+        for other in &mut self.items {
+            if other.original_index > item.original_index {
+                other.original_index -= 1;
+            }
+        }
+        Some(item)
+    }
+
+    /// Shuffle the items after moving the current one to the front.
+    /// `unshuffle` restores the original order, including later edits.
+    pub fn shuffle(&mut self) {
+        if self.current < self.items.len() {
+            let current = self.items.remove(self.current);
+            self.items.insert(0, current);
+            self.current = 0;
+        }
+        // Fisher-Yates with a randomly seeded xorshift generator
+        let mut state = RandomState::new().build_hasher().finish() | 1;
+        for i in (2..self.items.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let j = 1 + (state % i as u64) as usize;
+            self.items.swap(i, j);
+        }
+        self.shuffled = true;
+    }
+
+    /// Restore the order from before `shuffle`, keeping the current item current
+    pub fn unshuffle(&mut self) {
+        let current = self.items.get(self.current).map(|item| item.original_index);
+        self.items.sort_by_key(|item| item.original_index);
+        if let Some(current) = current {
+            self.current = self
+                .items
+                .iter()
+                .position(|item| item.original_index == current)
+                .unwrap_or(0);
+        }
+        self.shuffled = false;
+    }
+
+    /// Check if the items are in shuffled order
+    #[must_use]
+    pub fn is_shuffled(&self) -> bool {
+        self.shuffled
+    }
+
+    /// Set what happens when an item ends
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    /// Get the repeat mode
+    #[must_use]
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    /// Make the current order the original one
+    fn renumber(&mut self) {
+        for (i, item) in self.items.iter_mut().enumerate() {
+            item.original_index = i;
+        }
+    }
+
+    /// All items in play order
+    #[must_use]
+    pub fn items(&self) -> &[PlaylistItem] {
+        &self.items
+    }
+
+    /// Number of items
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the playlist has no items
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Index of the current item (None if empty)
+    #[must_use]
+    pub fn current_index(&self) -> Option<usize> {
+        (self.current < self.items.len()).then_some(self.current)
+    }
+
+    /// The current item
+    #[must_use]
+    pub fn current(&self) -> Option<&Path> {
+        self.items.get(self.current).map(|item| item.path.as_path())
+    }
+
+    /// Index of the item to play when the current one ends, following the repeat mode
+    #[must_use]
+    pub fn next_index(&self) -> Option<usize> {
+        match self.repeat {
+            RepeatMode::One => self.current_index(),
+            RepeatMode::All if self.current + 1 >= self.items.len() => {
+                (!self.items.is_empty()).then_some(0)
+            }
+            _ => (self.current + 1 < self.items.len()).then_some(self.current + 1),
+        }
+    }
+
+    /// The item to play when the current one ends, without moving
+    #[must_use]
+    pub fn peek_next(&self) -> Option<&Path> {
+        self.next_index()
+            .map(|index| self.items[index].path.as_path())
+    }
+
+    /// Attach probed metadata to every item playing `path`
+    pub(crate) fn set_metadata(&mut self, path: &Path, metadata: &ItemMetadata) {
+        for item in self.items.iter_mut().filter(|item| item.path == path) {
+            item.metadata = Some(metadata.clone());
+        }
+    }
+
+    /// Make `index` the current item, returning it
+    pub fn select(&mut self, index: usize) -> Option<&Path> {
+        let item = self.items.get(index)?;
+        self.current = index;
+        Some(&item.path)
+    }
+}
+
+/// Probe `paths` one after another on a background thread, sending each result.
+/// Items that fail to open are skipped. Pipes are never probed, as that would consume them.
+pub(crate) fn prefetch_metadata(paths: Vec<PathBuf>, sender: Sender<(PathBuf, ItemMetadata)>) {
+    thread::spawn(move || {
+        for path in paths.into_iter().filter(|path| !is_pipe(path)) {
+            if let Some(metadata) = ItemMetadata::probe(&path) {
+                if sender.send((path, metadata)).is_err() {
+                    return; // Player dropped
+                }
+            }
+        }
+    });
+}
+
+/// Whether `path` is a local playlist file rather than media.
+/// HLS .m3u8 files are media streams and are left to FFmpeg.
+pub fn is_playlist_file(path: &Path) -> bool {
+    if is_network_source(path) || !has_extension(path, &["m3u", "m3u8", "pls"]) {
+        return false;
+    }
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    !BufReader::new(file)
+        .split(b'\n')
+        .take(HLS_SNIFF_LINES)
+        .map_while(Result::ok)
+        .any(|line| line.starts_with(b"#EXT-X-"))
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Read the entries of a playlist file. Playlists it lists are expanded in
+/// place, except beyond `MAX_NESTING` levels and those expanded before, which
+/// includes every playlist on the way here, so a playlist listing itself ends.
+fn load_items(
+    path: &Path,
+    depth: usize,
+    expanded: &mut HashSet<PathBuf>,
+) -> Result<Vec<PlaylistItem>, PlayerError> {
+    expanded.insert(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+    let bytes = std::fs::read(path).map_err(|e| {
+        PlayerError::Io(format!("Failed to read playlist {}: {}", path.display(), e))
+    })?;
+    // Older .m3u files are often Latin-1; keep what decodes
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    let entries = if has_extension(path, &["pls"]) {
+        parse_pls(text, base)
+    } else {
+        parse_m3u(text, base)
+    };
+    let mut items = Vec::with_capacity(entries.len());
+    for item in entries {
+        // HLS manifests and URLs are media, not lists
+        if !is_playlist_file(&item.path) {
+            items.push(item);
+            continue;
+        }
+        let nested = item
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| item.path.clone());
+        if depth + 1 >= MAX_NESTING || expanded.contains(&nested) {
+            tracing::warn!(path = %item.path.display(), "skipped nested playlist");
+            continue;
+        }
+        match load_items(&item.path, depth + 1, expanded) {
+            Ok(nested_items) => items.extend(nested_items),
+            Err(e) => tracing::warn!(error = %e, "skipped nested playlist"),
+        }
+    }
+    Ok(items)
+}
+
+/// Resolve a playlist entry to a URL or path
+fn resolve_entry(entry: &str, base: &Path) -> PathBuf {
+    let entry = PathBuf::from(entry);
+    if is_network_source(&entry) || entry.is_absolute() {
+        entry
+    } else {
+        base.join(entry)
+    }
+}
+
+fn parse_m3u(text: &str, base: &Path) -> Vec<PlaylistItem> {
+    let mut items = Vec::new();
+    let mut title = None;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:<seconds>[ attributes],<title>
+            title = info
+                .split_once(',')
+                .map(|(_, t)| t.trim().to_owned())
+                .filter(|t| !t.is_empty());
+        } else if !line.starts_with('#') {
+            items.push(PlaylistItem::new(
+                resolve_entry(line, base),
+                title.take(),
+                0,
+            ));
+        }
+    }
+    items
+}
+
+fn parse_pls(text: &str, base: &Path) -> Vec<PlaylistItem> {
+    // Entries are numbered FileN / TitleN and may appear in any order
+    let mut files = BTreeMap::new();
+    let mut titles = BTreeMap::new();
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        if let Some(n) = key.strip_prefix("file").and_then(|n| n.parse::<u32>().ok()) {
+            files.insert(n, value.trim().to_owned());
+        } else if let Some(n) = key
+            .strip_prefix("title")
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            titles.insert(n, value.trim().to_owned());
+        }
+    }
+    files
+        .into_iter()
+        .map(|(n, file)| {
+            PlaylistItem::new(
+                resolve_entry(&file, base),
+                titles.remove(&n).filter(|t| !t.is_empty()),
+                0,
+            )
+        })
+        .collect()
+}
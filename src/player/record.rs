@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use ffmpeg_next::format::context::{Input, Output};
+use ffmpeg_next::media::Type;
+use ffmpeg_next::{codec, encoder, Packet, Rational};
+use std::path::{Path, PathBuf};
+
+/// Copies the packets read for playback into a file without re-encoding,
+/// so a live stream can be captured while it is watched
+pub struct Recorder {
+    path: PathBuf,
+    output: Output,
+    /// Output stream index and input time base for each input stream (None if not recorded)
+    streams: Vec<Option<(usize, Rational)>>,
+    video_index: Option<usize>,
+    /// Input position of the first written packet in seconds; the file starts at zero
+    start: Option<f64>,
+    finished: bool,
+}
+
+impl Recorder {
+    /// Create the output file with a copy of every audio, video and subtitle stream
+    pub fn new(path: &Path, input: &Input) -> Result<Self> {
+        let mut output = ffmpeg_next::format::output(path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+
+        let mut streams = Vec::new();
+        let mut next_index = 0;
+        for stream in input.streams() {
+            let medium = stream.parameters().medium();
+            if !matches!(medium, Type::Video | Type::Audio | Type::Subtitle) {
+                streams.push(None);
+                continue;
+            }
+            let mut out_stream = output.add_stream(encoder::find(codec::Id::None))?;
+            out_stream.set_parameters(stream.parameters());
+            // The source container's codec tag may not be valid in the output format
+            unsafe {
+                (*out_stream.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+            streams.push(Some((next_index, stream.time_base())));
+            next_index += 1;
+        }
+
+        output
+            .write_header()
+            .context("Failed to write recording header")?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            output,
+            streams,
+            video_index: input.streams().best(Type::Video).map(|s| s.index()),
+            start: None,
+            finished: false,
+        })
+    }
+
+    /// Write a copy of a packet read from the input.
+    /// Nothing is written until the first video keyframe, so the file starts decodable.
+    pub fn write(&mut self, packet: &Packet) -> Result<()> {
+        let Some(&Some((index, in_time_base))) = self.streams.get(packet.stream()) else {
+            return Ok(());
+        };
+        // The muxer may have picked its own time base while writing the header
+        let out_time_base = self
+            .output
+            .stream(index)
+            .map_or(in_time_base, |stream| stream.time_base());
+
+        let position = packet.dts().or(packet.pts()).unwrap_or(0) as f64 * f64::from(in_time_base);
+        let start = match self.start {
+            Some(start) => start,
+            None if self.video_index.is_none()
+                || (Some(packet.stream()) == self.video_index && packet.is_key()) =>
+            {
+                *self.start.insert(position)
+            }
+            None => return Ok(()),
+        };
+        if position < start {
+            return Ok(()); // Reordered packet from before the first keyframe
+        }
+
+        // Shift timestamps so the recording starts at zero
+        let offset = (start / f64::from(in_time_base)).round() as i64;
+        let mut copy = packet.clone();
+        copy.set_pts(packet.pts().map(|pts| pts - offset));
+        copy.set_dts(packet.dts().map(|dts| dts - offset));
+        copy.rescale_ts(in_time_base, out_time_base);
+        copy.set_position(-1);
+        copy.set_stream(index);
+        copy.write_interleaved(&mut self.output)
+            .context("Failed to write recording")
+    }
+
+    /// Finalize the file, returning its path
+    pub fn finish(mut self) -> Result<PathBuf> {
+        self.finished = true;
+        self.output
+            .write_trailer()
+            .context("Failed to finalize recording")?;
+        Ok(std::mem::take(&mut self.path))
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // Keep the file playable if the decoder exits mid-recording
+        if !self.finished {
+            let _ = self.output.write_trailer();
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError};
+use egui::Context;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{Error as WsError, Message};
+
+use super::error::PlayerError;
+use super::{PlayerState, VideoPlayer, Volume};
+
+/// How often client threads check for responses and notifications to send
+const CLIENT_POLL: Duration = Duration::from_millis(50);
+/// Time between position notifications while playing
+const POSITION_INTERVAL: Duration = Duration::from_millis(500);
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The player refused the command, e.g. a file that would not open
+const PLAYER_ERROR: i64 = -32000;
+
+type Clients = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// A call received from a client, with the queue its response goes to
+struct Request {
+    /// None for JSON-RPC notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    params: Value,
+    reply: Sender<String>,
+}
+
+/// JSON-RPC 2.0 over WebSocket, for controlling a player from phones or test
+/// harnesses.
+///
+/// Methods: `play`, `pause`, `toggle`, `stop`, `seek` (`{"position": seconds}`),
+/// `set_volume` (`{"volume": 0.0..1.0}`), `set_muted` (`{"muted": bool}`),
+/// `load` (`{"path": "..."}`) and `get_state`. Each returns the player state
+/// as `{"state", "position", "duration", "volume", "muted", "path"}`, which is
+/// also pushed as a `state` notification when playback state or file change,
+/// and as `position` every half second while playing.
+///
+/// Anyone who can connect can open files on this machine, so bind to
+/// localhost unless the network is trusted.
+pub struct RemoteServer {
+    address: SocketAddr,
+    requests: Receiver<Request>,
+    clients: Clients,
+    stop_flag: Arc<AtomicBool>,
+    /// State and file last notified
+    notified: Option<(PlayerState, PathBuf)>,
+    last_position: Instant,
+}
+
+impl RemoteServer {
+    /// Listen on `address`, e.g. "127.0.0.1:9090", serving each client on a
+    /// thread of its own. Requests wake `ctx` so they are handled while paused.
+    pub fn start(address: impl ToSocketAddrs, ctx: Context) -> Result<Self, PlayerError> {
+        let listener = TcpListener::bind(address)
+            .and_then(|listener| Ok((listener.local_addr()?, listener)))
+            .map_err(|e| PlayerError::Network {
+                message: format!("Failed to start remote server: {}", e),
+                retryable: false,
+            });
+        let (address, listener) = listener?;
+
+        let (request_sender, requests) = unbounded();
+        let clients = Clients::default();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let accepted = clients.clone();
+        let stopped = stop_flag.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let (reply, outgoing) = unbounded();
+                accepted.lock().push(reply.clone());
+                let requests = request_sender.clone();
+                let ctx = ctx.clone();
+                thread::spawn(move || serve_client(stream, &requests, &reply, &outgoing, &ctx));
+            }
+        });
+
+        Ok(Self {
+            address,
+            requests,
+            clients,
+            stop_flag,
+            notified: None,
+            last_position: Instant::now(),
+        })
+    }
+
+    /// Address the server listens on
+    #[must_use]
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Number of connected clients
+    #[must_use]
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().len()
+    }
+
+    /// Run the calls received since the last update on `player` and notify
+    /// clients of changes. Call once per frame.
+    pub fn update(&mut self, player: &mut VideoPlayer) {
+        while let Ok(request) = self.requests.try_recv() {
+            let result = execute(player, &request.method, &request.params);
+            let Some(id) = request.id else {
+                continue;
+            };
+            let response = match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => error_response(id, code, &message),
+            };
+            let _ = request.reply.send(response.to_string());
+        }
+
+        let changed = match self.notified {
+            Some((state, ref path)) => state != player.state() || path != player.path(),
+            None => true,
+        };
+        let position_due = player.is_playing() && self.last_position.elapsed() >= POSITION_INTERVAL;
+        if !changed && !position_due {
+            return;
+        }
+        self.notified = Some((player.state(), player.path().to_path_buf()));
+        self.last_position = Instant::now();
+        let method = if changed { "state" } else { "position" };
+        let notification = json!({ "jsonrpc": "2.0", "method": method, "params": status(player) });
+        let text = notification.to_string();
+        // Clients that disconnected have dropped their queue
+        self.clients
+            .lock()
+            .retain(|client| client.send(text.clone()).is_ok());
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        // Wake the listener so it sees the flag; client threads end once their
+        // queue disconnects
+        let _ = TcpStream::connect(self.address);
+        self.clients.lock().clear();
+    }
+}
+
+/// Forward one client's calls to the player and send back what is queued for it
+fn serve_client(
+    stream: TcpStream,
+    requests: &Sender<Request>,
+    reply: &Sender<String>,
+    outgoing: &Receiver<String>,
+    ctx: &Context,
+) {
+    let Ok(mut socket) = tungstenite::accept(stream) else {
+        return;
+    };
+    // Reads time out so queued messages go out between them
+    if socket
+        .get_ref()
+        .set_read_timeout(Some(CLIENT_POLL))
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match parse_request(&text) {
+                Ok((id, method, params)) => {
+                    let request = Request {
+                        id,
+                        method,
+                        params,
+                        reply: reply.clone(),
+                    };
+                    if requests.send(request).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                }
+                Err(response) => {
+                    if socket.send(Message::Text(response)).is_err() {
+                        return;
+                    }
+                }
+            },
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(WsError::Io(ref e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+
+        loop {
+            match outgoing.try_recv() {
+                Ok(text) => {
+                    if socket.send(Message::Text(text)).is_err() {
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Split a JSON-RPC request into its id, method and params, or make the error response
+fn parse_request(text: &str) -> Result<(Option<Value>, String, Value), String> {
+    let request: Value = serde_json::from_str(text)
+        .map_err(|e| error_response(Value::Null, PARSE_ERROR, &e.to_string()).to_string())?;
+    let id = request.get("id").cloned();
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            error_response(
+                id.clone().unwrap_or_default(),
+                INVALID_REQUEST,
+                "Missing method",
+            )
+            .to_string()
+        })?;
+    let params = request.get("params").cloned().unwrap_or_default();
+    Ok((id, method.to_owned(), params))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Run one method on the player, returning its state or an error code and message
+fn execute(player: &mut VideoPlayer, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    match method {
+        "play" => player.play(),
+        "pause" => player.pause(),
+        "toggle" => {
+            if player.is_playing() {
+                player.pause();
+            } else {
+                player.play();
+            }
+        }
+        "stop" => player.stop(),
+        "seek" => {
+            let position = param(params, "position", Value::as_f64)?;
+            player.seek(Duration::from_secs_f64(position.max(0.0)));
+        }
+        "set_volume" => {
+            let volume = param(params, "volume", Value::as_f64)?;
+            let volume = Volume::new(volume as f32).ok_or_else(|| {
+                (
+                    INVALID_PARAMS,
+                    "Volume must be between 0.0 and 1.0".to_owned(),
+                )
+            })?;
+            player.set_volume(volume);
+        }
+        "set_muted" => player.set_muted(param(params, "muted", Value::as_bool)?),
+        "load" => {
+            let path = param(params, "path", Value::as_str)?;
+            player
+                .replace_media(Path::new(path))
+                .map_err(|e| (PLAYER_ERROR, e.to_string()))?;
+        }
+        "get_state" => {}
+        _ => return Err((METHOD_NOT_FOUND, format!("Unknown method {}", method))),
+    }
+    Ok(status(player))
+}
+
+/// Named parameter of the expected JSON type
+fn param<'a, T>(
+    params: &'a Value,
+    name: &str,
+    get: impl Fn(&'a Value) -> Option<T>,
+) -> Result<T, (i64, String)> {
+    params
+        .get(name)
+        .and_then(get)
+        .ok_or_else(|| (INVALID_PARAMS, format!("Missing or invalid \"{}\"", name)))
+}
+
+fn status(player: &VideoPlayer) -> Value {
+    let state = match player.state() {
+        PlayerState::Stopped => "stopped",
+        PlayerState::Playing => "playing",
+        PlayerState::Paused => "paused",
+    };
+    json!({
+        "state": state,
+        "position": player.position().as_secs_f64(),
+        "duration": player.duration().as_secs_f64(),
+        "volume": player.volume().get(),
+        "muted": player.is_muted(),
+        "path": player.path().display().to_string(),
+    })
+}
@@ -0,0 +1,115 @@
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often a producer above the high watermark checks the queue again
+const DRAIN_POLL: Duration = Duration::from_millis(2);
+
+/// Create a lock-free single-producer single-consumer sample queue between
+/// the decoder thread and the audio output
+pub fn audio_ring(capacity: usize) -> (AudioProducer, AudioConsumer) {
+    let (producer, consumer) = RingBuffer::new(capacity);
+    let discard_until = Arc::new(AtomicU64::new(0));
+    (
+        AudioProducer {
+            producer,
+            // Keep some room free, and refill in batches once half has played
+            high_watermark: capacity - capacity / 8,
+            low_watermark: capacity / 2,
+            written: 0,
+            discard_until: discard_until.clone(),
+        },
+        AudioConsumer {
+            consumer,
+            read: 0,
+            discard_until,
+        },
+    )
+}
+
+/// Decoder side of the audio queue
+pub struct AudioProducer {
+    producer: Producer<f32>,
+    /// Queued samples at which pushing waits for the output
+    high_watermark: usize,
+    /// Queued samples the output plays down to before pushing resumes
+    low_watermark: usize,
+    /// Samples queued since the queue was created
+    written: u64,
+    /// Sample count up to which the output discards instead of playing
+    discard_until: Arc<AtomicU64>,
+}
+
+impl AudioProducer {
+    /// Queue samples. Once the queue reaches its high watermark this waits
+    /// until the output has played it down to the low watermark, so decoding
+    /// is paced by playback and nothing queued is lost while paused. Waiting
+    /// gives up, dropping the rest, as soon as `wanted` returns false, e.g.
+    /// after a seek.
+    pub fn push(&mut self, mut samples: &[f32], wanted: impl Fn() -> bool) {
+        while !samples.is_empty() {
+            let room = self.high_watermark.saturating_sub(self.queued());
+            if room == 0 {
+                while self.queued() > self.low_watermark {
+                    if !wanted() {
+                        return;
+                    }
+                    thread::sleep(DRAIN_POLL);
+                }
+                continue;
+            }
+            let count = room.min(samples.len());
+            if let Ok(chunk) = self.producer.write_chunk_uninit(count) {
+                chunk.fill_from_iter(samples[..count].iter().copied());
+                self.written += count as u64;
+            }
+            samples = &samples[count..];
+        }
+    }
+
+    /// Samples in the queue, including ones the output is yet to discard
+    pub fn queued(&self) -> usize {
+        self.producer.buffer().capacity() - self.producer.slots()
+    }
+
+    /// Drop everything queued so far, e.g. after a seek. The output skips it on its
+    /// next read, while samples pushed afterwards are kept.
+    pub fn discard_queued(&mut self) {
+        self.discard_until.store(self.written, Ordering::Relaxed);
+    }
+}
+
+/// Audio output side of the queue
+pub struct AudioConsumer {
+    consumer: Consumer<f32>,
+    /// Samples taken from the queue, played or discarded
+    read: u64,
+    discard_until: Arc<AtomicU64>,
+}
+
+impl AudioConsumer {
+    /// Move up to `out.len()` queued samples into `out`, returning how many were read
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        let discard = self
+            .discard_until
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.read)
+            .min(self.consumer.slots() as u64) as usize;
+        if let Ok(chunk) = self.consumer.read_chunk(discard) {
+            chunk.commit_all();
+            self.read += discard as u64;
+        }
+        let count = self.consumer.slots().min(out.len());
+        let Ok(chunk) = self.consumer.read_chunk(count) else {
+            return 0;
+        };
+        let (first, second) = chunk.as_slices();
+        out[..first.len()].copy_from_slice(first);
+        out[first.len()..count].copy_from_slice(second);
+        chunk.commit_all();
+        self.read += count as u64;
+        count
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::frame::Audio as AudioFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::software::resampling::Context as ResamplerContext;
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::util::format::sample::{Sample, Type as SampleType};
+use ffmpeg_next::{codec, decoder, Packet};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use super::filter::channel_layout;
+use super::output::SnippetOutput;
+
+/// Length of audio played per scrub position
+const SNIPPET_SECS: f64 = 0.08;
+
+/// Audio feedback while dragging the seek bar
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScrubAudio {
+    /// Silent scrubbing
+    #[default]
+    Off,
+    /// Play a short snippet at each position the seek bar is dragged to
+    Snippets,
+}
+
+/// A snippet to play: media, position in seconds and volume
+struct SnippetRequest {
+    path: PathBuf,
+    position: f64,
+    volume: f32,
+}
+
+/// Decodes and plays scrub snippets on its own thread, separate from the
+/// playback decoder so scrubbing never disturbs its queues.
+/// The thread exits when this is dropped.
+pub struct ScrubPlayer {
+    sender: Sender<SnippetRequest>,
+}
+
+impl ScrubPlayer {
+    /// Play snippets through `output`, which takes stereo at `output_rate`
+    pub fn new(output: Box<dyn SnippetOutput>, output_rate: u32) -> Self {
+        // Only the latest position matters; requests arriving while busy are dropped
+        let (sender, receiver) = bounded(1);
+        thread::spawn(move || snippet_loop(receiver, output, output_rate));
+        Self { sender }
+    }
+
+    /// Play the audio at `position` seconds, cutting off the previous snippet
+    pub fn play(&self, path: &Path, position: f64, volume: f32) {
+        let _ = self.sender.try_send(SnippetRequest {
+            path: path.to_path_buf(),
+            position,
+            volume,
+        });
+    }
+}
+
+/// The open audio track of the media being scrubbed
+struct SnippetDecoder {
+    input: Input,
+    stream_index: usize,
+    time_base: f64,
+    decoder: decoder::Audio,
+    resampler: ResamplerContext,
+    output_rate: u32,
+}
+
+impl SnippetDecoder {
+    fn open(path: &Path, output_rate: u32) -> Result<Self> {
+        let input = ffmpeg_next::format::input(path).context("Failed to open input file")?;
+        let stream = input
+            .streams()
+            .best(Type::Audio)
+            .ok_or_else(|| anyhow!("No audio stream found"))?;
+        let stream_index = stream.index();
+        let time_base = f64::from(stream.time_base());
+        let decoder = codec::Context::from_parameters(stream.parameters())?
+            .decoder()
+            .audio()?;
+        // Interleaved stereo f32 at the output rate
+        let resampler = ResamplerContext::get(
+            decoder.format(),
+            channel_layout(&decoder),
+            decoder.rate(),
+            Sample::F32(SampleType::Packed),
+            ChannelLayout::STEREO,
+            output_rate,
+        )?;
+        Ok(Self {
+            input,
+            stream_index,
+            time_base,
+            decoder,
+            resampler,
+            output_rate,
+        })
+    }
+
+    /// Decode `SNIPPET_SECS` of interleaved stereo samples starting at `position`
+    fn snippet(&mut self, position: f64) -> Result<Vec<f32>> {
+        let target_ts = (position * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+        self.input.seek(target_ts, ..target_ts)?;
+        self.decoder.flush();
+
+        let rate = f64::from(self.output_rate);
+        let wanted = (SNIPPET_SECS * rate) as usize * 2;
+        let mut samples = Vec::with_capacity(wanted);
+        let mut packet = Packet::empty();
+        let mut decoded = AudioFrame::empty();
+        let mut stereo = AudioFrame::empty();
+        while samples.len() < wanted {
+            match packet.read(&mut self.input) {
+                Ok(()) => {}
+                Err(ffmpeg_next::Error::Eof) => break,
+                Err(e) => return Err(e).context("Failed to read input"),
+            }
+            if packet.stream() != self.stream_index || self.decoder.send_packet(&packet).is_err() {
+                continue;
+            }
+            while self.decoder.receive_frame(&mut decoded).is_ok() {
+                if self.resampler.run(&decoded, &mut stereo).is_err() {
+                    continue;
+                }
+                // The seek lands on the packet before the target; skip up to it
+                let start = decoded
+                    .timestamp()
+                    .map_or(position, |ts| ts as f64 * self.time_base);
+                let skip =
+                    (((position - start) * rate).max(0.0) as usize * 2).min(stereo.samples() * 2);
+                samples.extend_from_slice(&stereo.plane::<f32>(0)[skip..stereo.samples() * 2]);
+            }
+        }
+        samples.truncate(wanted);
+        Ok(samples)
+    }
+}
+
+fn snippet_loop(
+    receiver: Receiver<SnippetRequest>,
+    mut output: Box<dyn SnippetOutput>,
+    output_rate: u32,
+) {
+    let mut open_path: Option<PathBuf> = None;
+    let mut snippet_decoder: Option<SnippetDecoder> = None;
+
+    while let Ok(request) = receiver.recv() {
+        if open_path.as_ref() != Some(&request.path) {
+            // Media without audio simply scrubs silently
+            snippet_decoder = SnippetDecoder::open(&request.path, output_rate).ok();
+            open_path = Some(request.path);
+        }
+        let Some(ref mut snippet_decoder) = snippet_decoder else {
+            continue;
+        };
+        let Ok(samples) = snippet_decoder.snippet(request.position) else {
+            continue;
+        };
+        if !samples.is_empty() {
+            output.play(samples, request.volume);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Still images and numbered image sequences, read through FFmpeg's image2 demuxer.
+//!
+//! A sequence is given either as a printf-style pattern (`frame_%04d.png`) or
+//! as a folder holding numbered images.
+
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::Dictionary;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Extensions treated as still images
+const IMAGE_EXTENSIONS: [&str; 10] = [
+    "png", "jpg", "jpeg", "bmp", "tif", "tiff", "tga", "webp", "exr", "dpx",
+];
+
+/// FFmpeg URL of standard input
+pub const STDIN_URL: &str = "pipe:0";
+
+/// Frame rate image sequences play at unless one is set
+pub const DEFAULT_FRAME_RATE: f64 = 25.0;
+
+/// Whether the path is a single image file
+pub fn is_image_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Whether the path is a folder or a `%d` pattern rather than a single file
+pub fn is_image_sequence(path: &Path) -> bool {
+    path.is_dir() || (!path.exists() && is_pattern(path))
+}
+
+/// Whether a file name holds a printf-style frame number, e.g. `%d` or `%04d`
+fn is_pattern(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.match_indices('%').any(|(i, _)| {
+        let rest = name[i + 1..].trim_start_matches(|c: char| c.is_ascii_digit());
+        rest.starts_with('d')
+    })
+}
+
+/// Open media for decoding. Image sequences play at `frame_rate` (0.0 for the default);
+/// `-` reads standard input and everything else is opened as usual.
+pub fn open_input(path: &Path, frame_rate: f64) -> Result<Input, ffmpeg_next::Error> {
+    if path.as_os_str() == "-" {
+        return ffmpeg_next::format::input(STDIN_URL);
+    }
+    if !is_image_sequence(path) {
+        return ffmpeg_next::format::input(path);
+    }
+
+    let frame_rate = if frame_rate > 0.0 {
+        frame_rate
+    } else {
+        DEFAULT_FRAME_RATE
+    };
+    let mut options = Dictionary::new();
+    options.set("framerate", &frame_rate.to_string());
+    let pattern = if path.is_dir() {
+        let (pattern, start) = folder_pattern(path).ok_or(ffmpeg_next::Error::InvalidData)?;
+        options.set("start_number", &start.to_string());
+        pattern
+    } else {
+        path.to_path_buf()
+    };
+    ffmpeg_next::format::input_with_dictionary(&pattern, options)
+}
+
+/// Like `open_input`, but `interrupt` can abandon opening a file or stream
+/// midway, and its later reads, by returning true. Standard input and image
+/// sequences open without it.
+pub fn open_input_interruptible(
+    path: &Path,
+    frame_rate: f64,
+    interrupt: impl FnMut() -> bool + Send + 'static,
+) -> Result<Input, ffmpeg_next::Error> {
+    if path.as_os_str() == "-" || is_image_sequence(path) {
+        return open_input(path, frame_rate);
+    }
+    ffmpeg_next::format::input_with_interrupt(path, interrupt)
+}
+
+/// Find the largest run of numbered images in a folder, as an image2 pattern
+/// and the first frame number
+fn folder_pattern(dir: &Path) -> Option<(PathBuf, u64)> {
+    // Files sharing a prefix and extension form one sequence
+    let mut sequences: HashMap<(String, String), Vec<&str>> = HashMap::new();
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    for name in &names {
+        let path = Path::new(name);
+        let (Some(stem), Some(ext)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|e| e.to_str()),
+        ) else {
+            continue;
+        };
+        if !IMAGE_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)) {
+            continue;
+        }
+        let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+        let digits = &stem[prefix.len()..];
+        if !digits.is_empty() {
+            sequences
+                .entry((prefix.to_owned(), ext.to_owned()))
+                .or_default()
+                .push(digits);
+        }
+    }
+
+    let ((prefix, ext), numbers) = sequences
+        .into_iter()
+        .max_by_key(|(_, numbers)| numbers.len())?;
+    let start = numbers.iter().filter_map(|n| n.parse().ok()).min()?;
+    // Zero-padded names need the exact width; unpadded ones use plain %d
+    let width = numbers[0].len();
+    let padded =
+        numbers.iter().all(|n| n.len() == width) && numbers.iter().any(|n| n.starts_with('0'));
+    let number = if padded {
+        format!("%0{}d", width)
+    } else {
+        "%d".to_owned()
+    };
+    Some((dir.join(format!("{}{}.{}", prefix, number, ext)), start))
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::scrub::ScrubAudio;
+use super::DisplayMode;
+
+/// Subtitle file extensions looked for next to the media
+const SUBTITLE_EXTENSIONS: [&str; 4] = ["srt", "vtt", "ass", "ssa"];
+
+/// User preferences carried from file to file. Serialize it with any serde
+/// format to persist it, and apply it with `VideoPlayer::set_settings` or
+/// `PlayerConfig::settings`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Volume level (0.0 to 1.0)
+    pub volume: f32,
+    pub muted: bool,
+    /// Left/right balance, -1.0 to 1.0
+    pub balance: f32,
+    /// Audio output latency compensation in milliseconds
+    pub output_latency_ms: i64,
+    /// Playback speed, 1.0 being normal
+    pub speed: f32,
+    /// Audio pitch shift in semitones
+    pub pitch: f32,
+    /// Subtitle delay in milliseconds
+    pub subtitle_delay_ms: i64,
+    /// Subtitle timing scale
+    pub subtitle_scale: f32,
+    /// Preferred subtitle languages in order, e.g. ["en", "de"].
+    /// A matching sidecar file (movie.en.srt) is loaded when a file opens;
+    /// without a match, the untagged or else the first subtitle file is used.
+    pub subtitle_languages: Vec<String>,
+    /// Pick forced subtitles (movie.en.forced.srt), which only translate foreign
+    /// dialogue, over full ones in the same language
+    pub prefer_forced_subtitles: bool,
+    /// Preferred audio languages in order; picks the audio track on open.
+    /// The container's default track is used when none match.
+    pub audio_languages: Vec<String>,
+    pub display_mode: DisplayMode,
+    /// Deinterlace video with yadif before any custom video filter
+    pub deinterlace: bool,
+    /// Audio feedback while dragging the seek bar
+    pub scrub_audio: ScrubAudio,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            balance: 0.0,
+            output_latency_ms: 0,
+            speed: 1.0,
+            pitch: 0.0,
+            subtitle_delay_ms: 0,
+            subtitle_scale: 1.0,
+            subtitle_languages: Vec::new(),
+            prefer_forced_subtitles: false,
+            audio_languages: Vec::new(),
+            display_mode: DisplayMode::FitToWindow,
+            deinterlace: false,
+            scrub_audio: ScrubAudio::Off,
+        }
+    }
+}
+
+/// Whether a track's language tag satisfies a preference.
+/// Case-insensitive; "en" matches "en-US" and "eng".
+pub fn language_matches(tag: &str, preference: &str) -> bool {
+    let tag = tag.trim().to_ascii_lowercase();
+    let preference = preference.trim().to_ascii_lowercase();
+    !preference.is_empty() && tag.starts_with(&preference)
+}
+
+/// Find a subtitle file next to `media`, trying `movie.<lang>.srt` for each
+/// preferred language in order, then an untagged `movie.srt`, then the first
+/// subtitle file for the media. Forced tracks (`movie.<lang>.forced.srt`) come
+/// before full ones in the same language if `prefer_forced` is set, and after otherwise.
+pub fn find_subtitle_file(
+    media: &Path,
+    languages: &[String],
+    prefer_forced: bool,
+) -> Option<PathBuf> {
+    let stem = media.file_stem()?.to_str()?;
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(media.parent()?)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    SUBTITLE_EXTENSIONS
+                        .iter()
+                        .any(|e| ext.eq_ignore_ascii_case(e))
+                })
+        })
+        .collect();
+    let tag_of = |path: &Path| {
+        let name = path.file_stem()?.to_str()?;
+        name.strip_prefix(stem)
+            .map(|rest| rest.strip_prefix('.').unwrap_or(rest).to_owned())
+    };
+    let is_forced = |path: &PathBuf| {
+        tag_of(path).is_some_and(|tag| {
+            tag.split('.')
+                .any(|part| part.eq_ignore_ascii_case("forced"))
+        })
+    };
+    // Sort by name, then by forcedness, so finds below honor the preference
+    candidates.retain(|path| tag_of(path).is_some());
+    candidates.sort();
+    candidates.sort_by_key(|path| is_forced(path) != prefer_forced);
+
+    for language in languages {
+        let found = candidates.iter().find(|path| {
+            tag_of(path).is_some_and(|tag| !tag.is_empty() && language_matches(&tag, language))
+        });
+        if let Some(path) = found {
+            return Some(path.clone());
+        }
+    }
+    let untagged = candidates
+        .iter()
+        .position(|path| tag_of(path).is_some_and(|tag| tag.is_empty()));
+    candidates.into_iter().nth(untagged.unwrap_or(0))
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::Sender;
+use ffmpeg_next::frame::Audio as AudioFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::software::resampling::Context as ResamplerContext;
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::util::format::sample::{Sample, Type as SampleType};
+use ffmpeg_next::{codec, Packet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::events::PlayerEvent;
+use super::filter::channel_layout;
+
+/// Length of audio each level is measured over (seconds)
+const WINDOW_SECS: f64 = 0.05;
+
+/// What playback does in a quiet part
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SilenceAction {
+    /// Jump to the end of the quiet part
+    Skip,
+    /// Play the quiet part at this speed (up to 4.0)
+    SpeedUp(f32),
+}
+
+/// Skipping or speeding through quiet parts, as lecture players do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SilenceSkip {
+    pub action: SilenceAction,
+    /// Level below which audio counts as silent, in dBFS. Higher values
+    /// catch more, e.g. -30 for a noisy room.
+    pub threshold_db: f32,
+    /// Shortest quiet part acted on
+    pub min_duration: Duration,
+}
+
+impl Default for SilenceSkip {
+    fn default() -> Self {
+        Self {
+            action: SilenceAction::Skip,
+            threshold_db: -40.0,
+            min_duration: Duration::from_millis(750),
+        }
+    }
+}
+
+impl SilenceSkip {
+    /// Check if `other` finds the same quiet parts
+    pub(crate) fn same_detection(&self, other: &Self) -> bool {
+        self.threshold_db == other.threshold_db && self.min_duration == other.min_duration
+    }
+}
+
+/// Decode the audio of `path` on a background thread and send every quiet
+/// part as it is found. The channel closes when the pass ends.
+pub fn start_silence_detection_thread(
+    path: PathBuf,
+    settings: SilenceSkip,
+    sender: Sender<Range<Duration>>,
+    cancel: Arc<AtomicBool>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || {
+        let found = |range| {
+            let _ = sender.send(range);
+        };
+        if let Err(e) = detect_silence(&path, &settings, found, &cancel) {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Silence detection failed: {:#}",
+                e
+            )));
+        }
+    });
+}
+
+/// Decode the audio of `path` and call `found` with every quiet part in order
+pub(crate) fn detect_silence(
+    path: &Path,
+    settings: &SilenceSkip,
+    mut found: impl FnMut(Range<Duration>),
+    cancel: &AtomicBool,
+) -> Result<()> {
+    let mut input = ffmpeg_next::format::input(path).context("Failed to open input file")?;
+
+    let stream = input
+        .streams()
+        .best(Type::Audio)
+        .ok_or_else(|| anyhow!("No audio stream found"))?;
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+    let mut decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .audio()?;
+
+    // Downmix to mono f32 at the source rate
+    let rate = decoder.rate();
+    let mut resampler = ResamplerContext::get(
+        decoder.format(),
+        channel_layout(&decoder),
+        rate,
+        Sample::F32(SampleType::Packed),
+        ChannelLayout::MONO,
+        rate,
+    )?;
+
+    let window_len = ((WINDOW_SECS * f64::from(rate)) as usize).max(1);
+    // Mean square of the samples at the threshold level
+    let threshold = 10f64.powf(f64::from(settings.threshold_db) / 10.0);
+    let min_secs = settings.min_duration.as_secs_f64();
+    let mut quiet_since: Option<f64> = None;
+    let mut sum = 0.0f64;
+    let mut count = 0;
+    let mut window_start = 0.0;
+    let mut position = 0.0;
+
+    let mut report = |start: f64, end: f64| {
+        if end - start >= min_secs {
+            found(Duration::from_secs_f64(start)..Duration::from_secs_f64(end));
+        }
+    };
+
+    let mut packet = Packet::empty();
+    let mut decoded = AudioFrame::empty();
+    let mut mono = AudioFrame::empty();
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match packet.read(&mut input) {
+            Ok(()) => {}
+            Err(ffmpeg_next::Error::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        }
+        // Skip other streams and corrupt packets
+        if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+            continue;
+        }
+
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            if let Some(ts) = decoded.timestamp() {
+                position = (ts as f64 * time_base).max(0.0);
+            }
+            if resampler.run(&decoded, &mut mono).is_err() {
+                continue;
+            }
+            for (i, &sample) in mono.plane::<f32>(0).iter().enumerate() {
+                if count == 0 {
+                    window_start = position + i as f64 / f64::from(rate);
+                }
+                sum += f64::from(sample) * f64::from(sample);
+                count += 1;
+                if count < window_len {
+                    continue;
+                }
+                let quiet = sum / (count as f64) < threshold;
+                match (quiet, quiet_since) {
+                    (true, None) => quiet_since = Some(window_start),
+                    (false, Some(start)) => {
+                        report(start, window_start);
+                        quiet_since = None;
+                    }
+                    _ => {}
+                }
+                sum = 0.0;
+                count = 0;
+            }
+            position += decoded.samples() as f64 / f64::from(rate);
+        }
+    }
+
+    if let Some(start) = quiet_since {
+        report(start, position);
+    }
+    Ok(())
+}
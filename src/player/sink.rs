@@ -0,0 +1,16 @@
+use egui::ColorImage;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Destination for presented frames, for drawing video with a custom
+/// compositor (e.g. an own wgpu pipeline) instead of the player's texture.
+///
+/// Frames arrive on the UI thread from `VideoPlayer::update()`, at the time
+/// they are due after A/V sync, as premultiplied RGBA. The image is shared
+/// with `current_frame()`, so keeping it is free.
+pub trait FrameSink {
+    /// `pts` is the frame's media timestamp, matching `PlayerEvent::FramePresented`,
+    /// or None for cover art, placeholder images and the frame already shown
+    /// when the sink is set
+    fn present(&mut self, frame: Arc<ColorImage>, pts: Option<Duration>);
+}
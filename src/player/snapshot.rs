@@ -0,0 +1,44 @@
+use anyhow::{Context, Result};
+use egui::ColorImage;
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Straight (unpremultiplied) RGBA bytes, as image formats expect
+fn rgba_bytes(image: &ColorImage) -> Cow<'_, [u8]> {
+    if image.pixels.iter().all(|pixel| pixel.a() == 255) {
+        Cow::Borrowed(image.as_raw())
+    } else {
+        Cow::Owned(
+            image
+                .pixels
+                .iter()
+                .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+                .collect(),
+        )
+    }
+}
+
+/// Copy an image to the system clipboard
+pub fn copy_to_clipboard(image: &ColorImage) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new().context("Failed to access clipboard")?;
+    clipboard
+        .set_image(arboard::ImageData {
+            width: image.width(),
+            height: image.height(),
+            bytes: rgba_bytes(image),
+        })
+        .context("Failed to copy image to clipboard")
+}
+
+/// Encode an image as PNG and write it to disk
+pub fn save_png(image: &ColorImage, path: &Path) -> Result<()> {
+    image::save_buffer_with_format(
+        path,
+        &rgba_bytes(image),
+        image.width() as u32,
+        image.height() as u32,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .with_context(|| format!("Failed to save frame to {}", path.display()))
+}
@@ -0,0 +1,106 @@
+use std::time::{Duration, Instant};
+
+use super::cache::CacheStats;
+
+/// Playback is judged over windows of this length
+const PERFORMANCE_WINDOW: Duration = Duration::from_secs(1);
+/// Minimum time between two performance warnings
+const WARNING_INTERVAL: Duration = Duration::from_secs(5);
+/// Share of frames dropped in a window that counts as struggling
+const MAX_DROP_RATE: f64 = 0.1;
+/// Average offset of shown frames from the audio clock that counts as out of sync
+const MAX_DRIFT_MS: f64 = 80.0;
+
+/// Observed spacing between consecutive video frames
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameIntervals {
+    pub average: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl FrameIntervals {
+    /// Whether the spacing varies enough to indicate variable frame rate content
+    #[must_use]
+    pub fn is_variable(&self) -> bool {
+        self.max.as_secs_f64() > self.min.as_secs_f64() * 1.5
+    }
+}
+
+/// Snapshot of playback statistics. Get with `VideoPlayer::stats()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlaybackStats {
+    /// Spacing of recently decoded frames (None until two frames arrived)
+    pub frame_intervals: Option<FrameIntervals>,
+    /// Threads the video codec decodes with (0 until the first frame)
+    pub decoder_threads: usize,
+    /// Network cache reads and downloads so far
+    pub cache: CacheStats,
+    /// Frames dropped for arriving too late so far
+    pub dropped_frames: u64,
+    /// Bytes of decoded frames queued for display
+    pub frame_memory: u64,
+    /// This player's share of its `MemoryBudget` in bytes
+    pub frame_memory_limit: u64,
+}
+
+/// Watches frame drops and A/V drift during playback, reporting a window of
+/// playback that drops too many frames or shows them too far off the audio
+#[derive(Default)]
+pub(crate) struct PerformanceMonitor {
+    window_start: Option<Instant>,
+    dropped: u32,
+    presented: u32,
+    /// Sum of the shown frames' offsets from the clock (seconds)
+    drift: f64,
+    last_warning: Option<Instant>,
+}
+
+impl PerformanceMonitor {
+    /// Count the frames dropped since the last call and the offset of the
+    /// frame shown (frame PTS minus clock, seconds), if any. Returns the
+    /// dropped frames and average drift in milliseconds of a window that
+    /// crossed a threshold.
+    pub fn record(&mut self, dropped: u32, drift: Option<f64>) -> Option<(u32, f64)> {
+        let now = Instant::now();
+        let start = *self.window_start.get_or_insert(now);
+        self.dropped += dropped;
+        if let Some(drift) = drift {
+            self.presented += 1;
+            self.drift += drift;
+        }
+        if now - start < PERFORMANCE_WINDOW {
+            return None;
+        }
+
+        let (dropped, presented, drift) = (self.dropped, self.presented, self.drift);
+        self.reset();
+        let total = dropped + presented;
+        let drop_rate = if total > 0 {
+            f64::from(dropped) / f64::from(total)
+        } else {
+            0.0
+        };
+        let drift_ms = if presented > 0 {
+            drift / f64::from(presented) * 1000.0
+        } else {
+            0.0
+        };
+        let struggling = drop_rate > MAX_DROP_RATE || drift_ms.abs() > MAX_DRIFT_MS;
+        let quiet = self
+            .last_warning
+            .is_none_or(|time| now - time >= WARNING_INTERVAL);
+        (struggling && quiet).then(|| {
+            self.last_warning = Some(now);
+            (dropped, drift_ms)
+        })
+    }
+
+    /// Start a new window, e.g. after a seek or pause
+    pub fn reset(&mut self) {
+        self.window_start = None;
+        self.dropped = 0;
+        self.presented = 0;
+        self.drift = 0.0;
+    }
+}
@@ -0,0 +1,113 @@
+use ffmpeg_next::format::context::Input;
+use ffmpeg_next::media::Type;
+use std::ffi::CStr;
+
+/// Kind of an elementary stream in the container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamKind {
+    Video,
+    Audio,
+    Subtitle,
+    /// Attachments, data and timed metadata streams
+    Other,
+}
+
+/// Codec details of one stream, as stated by the container and codec headers
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamInfo {
+    pub index: usize,
+    pub kind: StreamKind,
+    /// FFmpeg codec name, e.g. "h264" or "aac"
+    pub codec: String,
+    /// Codec profile, e.g. "High" or "LC"
+    pub profile: Option<String>,
+    /// Bits per second, if known without decoding
+    pub bit_rate: Option<u64>,
+    /// Language tag, e.g. "eng"
+    pub language: Option<String>,
+    /// Coded size of video streams (before rotation); zero otherwise
+    pub width: u32,
+    pub height: u32,
+    /// Sample rate and channel count of audio streams; zero otherwise
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl StreamInfo {
+    /// Short display name of the codec, e.g. "H.264" for "h264"
+    #[must_use]
+    pub fn codec_label(&self) -> String {
+        let label = match self.codec.as_str() {
+            "h264" => "H.264",
+            "hevc" => "H.265",
+            "mpeg2video" => "MPEG-2",
+            "mpeg4" => "MPEG-4",
+            "prores" => "ProRes",
+            "dnxhd" => "DNxHD",
+            "ac3" => "AC-3",
+            "eac3" => "E-AC-3",
+            "truehd" => "TrueHD",
+            "opus" => "Opus",
+            "vorbis" => "Vorbis",
+            codec if codec.starts_with("pcm_") => "PCM",
+            codec => return codec.to_uppercase(),
+        };
+        label.to_owned()
+    }
+
+    /// Speaker layout name for common channel counts, e.g. "5.1" for six channels
+    #[must_use]
+    pub fn channel_label(&self) -> String {
+        match self.channels {
+            1 => "mono".to_owned(),
+            2 => "stereo".to_owned(),
+            6 => "5.1".to_owned(),
+            8 => "7.1".to_owned(),
+            channels => format!("{} ch", channels),
+        }
+    }
+}
+
+/// Read the codec parameters of every stream in an input
+pub fn read_streams(input: &Input) -> Vec<StreamInfo> {
+    input
+        .streams()
+        .map(|stream| {
+            let parameters = stream.parameters();
+            let kind = match parameters.medium() {
+                Type::Video => StreamKind::Video,
+                Type::Audio => StreamKind::Audio,
+                Type::Subtitle => StreamKind::Subtitle,
+                _ => StreamKind::Other,
+            };
+            let (profile, bit_rate, width, height, sample_rate, channels) = unsafe {
+                let params = &*parameters.as_ptr();
+                let name = ffmpeg_next::ffi::avcodec_profile_name(params.codec_id, params.profile);
+                (
+                    (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned()),
+                    params.bit_rate,
+                    params.width,
+                    params.height,
+                    params.sample_rate,
+                    params.ch_layout.nb_channels,
+                )
+            };
+            StreamInfo {
+                index: stream.index(),
+                kind,
+                codec: parameters.id().name().to_owned(),
+                profile,
+                bit_rate: u64::try_from(bit_rate).ok().filter(|&rate| rate > 0),
+                language: stream
+                    .metadata()
+                    .get("language")
+                    .filter(|language| !language.is_empty() && *language != "und")
+                    .map(str::to_owned),
+                width: width.max(0) as u32,
+                height: height.max(0) as u32,
+                sample_rate: sample_rate.max(0) as u32,
+                channels: channels.clamp(0, i32::from(u16::MAX)) as u16,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// Fields of an ASS Dialogue line when the script has no Format line
+const ASS_EVENT_FIELDS: [&str; 10] = [
+    "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+];
+
+/// A timed subtitle from an external SRT, WebVTT or ASS/SSA file
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubtitleCue {
+    /// Time the cue appears (on the subtitle file's own timeline)
+    pub start: Duration,
+    /// Time the cue disappears
+    pub end: Duration,
+    /// Cue text with markup tags removed; lines separated by '\n'
+    pub text: String,
+}
+
+/// Load an SRT, WebVTT or ASS/SSA file, returning cues sorted by start time
+pub fn load_subtitles(path: &Path) -> Result<Vec<SubtitleCue>> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read subtitles from {}", path.display()))?;
+    Ok(parse_subtitles(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Parse SRT, WebVTT or ASS/SSA text. Blocks without a timing line (headers,
+/// NOTE, STYLE) are skipped.
+pub fn parse_subtitles(text: &str) -> Vec<SubtitleCue> {
+    let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    if text.contains("[Events]") {
+        return parse_ass(&text);
+    }
+    let mut cues = Vec::new();
+
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, end)) = parse_timing(timing) else {
+            continue;
+        };
+
+        let text = lines
+            .map(strip_tags)
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            cues.push(SubtitleCue { start, end, text });
+        }
+    }
+
+    cues.sort_by_key(|cue| cue.start);
+    cues
+}
+
+/// Parse "00:00:01,000 --> 00:00:02,500" (WebVTT cue settings after the end time are ignored)
+fn parse_timing(line: &str) -> Option<(Duration, Duration)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+/// Parse "HH:MM:SS,mmm", "HH:MM:SS.mmm" or the WebVTT short form "MM:SS.mmm"
+fn parse_timestamp(s: &str) -> Option<Duration> {
+    let (clock, millis) = s.split_once([',', '.'])?;
+    let millis: u64 = millis.parse().ok()?;
+
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+
+    Some(Duration::from_millis(seconds * 1000 + millis))
+}
+
+/// Parse the Dialogue lines of an ASS/SSA script as plain text, in the field
+/// order of its Format line. Styles, positions and effects are dropped, as are
+/// vector drawings.
+fn parse_ass(text: &str) -> Vec<SubtitleCue> {
+    let mut fields: Vec<String> = ASS_EVENT_FIELDS.map(str::to_owned).to_vec();
+    let mut cues = Vec::new();
+
+    for line in text.lines() {
+        if let Some(format) = line.strip_prefix("Format:") {
+            fields = format
+                .split(',')
+                .map(|field| field.trim().to_ascii_lowercase())
+                .collect();
+            continue;
+        }
+        let Some(dialogue) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+        // The text is the last field and may contain commas
+        let values: Vec<&str> = dialogue.splitn(fields.len(), ',').collect();
+        let field = |name: &str| {
+            let index = fields.iter().position(|field| field == name)?;
+            values.get(index).map(|value| value.trim())
+        };
+        let (Some(start), Some(end), Some(raw)) = (
+            field("start").and_then(parse_ass_time),
+            field("end").and_then(parse_ass_time),
+            field("text"),
+        ) else {
+            continue;
+        };
+        if raw.contains("\\p1") {
+            continue; // Vector drawing
+        }
+        let text = strip_tags(raw)
+            .replace("\\N", "\n")
+            .replace("\\n", "\n")
+            .replace("\\h", " ");
+        let text = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            cues.push(SubtitleCue { start, end, text });
+        }
+    }
+
+    cues.sort_by_key(|cue| cue.start);
+    cues
+}
+
+/// Parse the ASS "H:MM:SS.cc" form, in hundredths of a second
+fn parse_ass_time(s: &str) -> Option<Duration> {
+    let (clock, hundredths) = s.split_once('.')?;
+    let hundredths: u64 = hundredths.parse().ok()?;
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(Duration::from_millis(seconds * 1000 + hundredths * 10))
+}
+
+/// Remove <i>, <b>, <c.class>, <v Speaker> and similar markup, and {\an8}-style SRT overrides
+fn strip_tags(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut closing = None;
+    for c in line.chars() {
+        match closing {
+            Some(end) if c == end => closing = None,
+            Some(_) => {}
+            None if c == '<' => closing = Some('>'),
+            None if c == '{' => closing = Some('}'),
+            None => out.push(c),
+        }
+    }
+    out.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+}
@@ -0,0 +1,160 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::time::Instant;
+
+/// Deflection below this counts as centered, so a worn stick does not creep
+const SHUTTLE_DEAD_ZONE: f32 = 0.1;
+
+/// What a control surface asks the player to do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SurfaceAction {
+    /// Play if paused, pause if playing
+    TogglePlayback,
+    /// Variable speed from -1.0 (fastest backwards) through 0.0 (stopped) to
+    /// 1.0 (fastest forwards), as set by a shuttle ring or an analog stick
+    Shuttle(f32),
+    /// Pause and move by whole frames, negative for backwards
+    StepFrames(i32),
+    Stop,
+    /// Next playlist item, or the next file in the folder without a playlist
+    NextItem,
+    /// Previous playlist item, or the previous file in the folder
+    PreviousItem,
+}
+
+/// Hardware that drives a player, such as a gamepad, MIDI controller or jog
+/// wheel. Implement it for a device and pass it to
+/// `VideoPlayer::poll_control_surface()` once per frame.
+pub trait ControlSurface {
+    /// Actions triggered since the last call, in order
+    fn poll(&mut self) -> Vec<SurfaceAction>;
+}
+
+/// Keyboard media keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaKey {
+    PlayPause,
+    Stop,
+    NextTrack,
+    PreviousTrack,
+}
+
+/// Media keys as a control surface. egui delivers no events for them, so the
+/// app forwards them from where they arrive, e.g. winit's
+/// `NamedKey::MediaPlayPause` in its own event loop or the window messages on
+/// Windows, through a `MediaKeySender`.
+pub struct MediaKeys {
+    sender: Sender<MediaKey>,
+    receiver: Receiver<MediaKey>,
+}
+
+impl Default for MediaKeys {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+        Self { sender, receiver }
+    }
+}
+
+impl MediaKeys {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle for forwarding key presses, from any thread
+    #[must_use]
+    pub fn sender(&self) -> MediaKeySender {
+        MediaKeySender(self.sender.clone())
+    }
+}
+
+impl ControlSurface for MediaKeys {
+    fn poll(&mut self) -> Vec<SurfaceAction> {
+        self.receiver
+            .try_iter()
+            .map(|key| match key {
+                MediaKey::PlayPause => SurfaceAction::TogglePlayback,
+                MediaKey::Stop => SurfaceAction::Stop,
+                MediaKey::NextTrack => SurfaceAction::NextItem,
+                MediaKey::PreviousTrack => SurfaceAction::PreviousItem,
+            })
+            .collect()
+    }
+}
+
+/// Forwards media key presses to a `MediaKeys` surface
+#[derive(Clone)]
+pub struct MediaKeySender(Sender<MediaKey>);
+
+impl MediaKeySender {
+    /// Pass on a press; it takes effect on the next `poll_control_surface()`
+    pub fn press(&self, key: MediaKey) {
+        let _ = self.0.send(key);
+    }
+}
+
+/// Playback speed for a shuttle deflection: 0.25x just off center up to 4x
+pub(crate) fn shuttle_speed(deflection: f32) -> f32 {
+    2f32.powf(deflection.abs() * 4.0 - 2.0)
+}
+
+/// Deflection with the dead zone removed, so zero means centered
+pub(crate) fn shuttle_deflection(value: f32) -> f32 {
+    let value = value.clamp(-1.0, 1.0);
+    if value.abs() < SHUTTLE_DEAD_ZONE {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Backwards shuttling, which steps through keyframes while paused
+pub(crate) struct ReverseShuttle {
+    pub deflection: f32,
+    /// Where the shuttle has moved to, ahead of the seeks catching up (seconds)
+    pub position: f64,
+    pub last_update: Instant,
+}
+
+#[cfg(feature = "gamepad")]
+pub use gamepad::GamepadSurface;
+
+#[cfg(feature = "gamepad")]
+mod gamepad {
+    use gilrs::{Axis, Button, EventType, Gilrs};
+
+    use super::{ControlSurface, SurfaceAction};
+    use crate::player::PlayerError;
+
+    /// Gamepads as a control surface: South (A/Cross) toggles playback, the
+    /// D-pad steps frames and the left stick shuttles
+    pub struct GamepadSurface {
+        gilrs: Gilrs,
+    }
+
+    impl GamepadSurface {
+        /// Start listening to all connected gamepads, including ones plugged in later
+        pub fn new() -> Result<Self, PlayerError> {
+            let gilrs = Gilrs::new().map_err(|e| PlayerError::Other(e.to_string()))?;
+            Ok(Self { gilrs })
+        }
+    }
+
+    impl ControlSurface for GamepadSurface {
+        fn poll(&mut self) -> Vec<SurfaceAction> {
+            let mut actions = Vec::new();
+            while let Some(event) = self.gilrs.next_event() {
+                let action = match event.event {
+                    EventType::ButtonPressed(Button::South, _) => SurfaceAction::TogglePlayback,
+                    EventType::ButtonPressed(Button::DPadLeft, _) => SurfaceAction::StepFrames(-1),
+                    EventType::ButtonPressed(Button::DPadRight, _) => SurfaceAction::StepFrames(1),
+                    EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                        SurfaceAction::Shuttle(value)
+                    }
+                    _ => continue,
+                };
+                actions.push(action);
+            }
+            actions
+        }
+    }
+}
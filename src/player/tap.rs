@@ -0,0 +1,14 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Hook that sees decoded audio before it is queued for playback.
+///
+/// Samples are interleaved f32 at the output sample rate, after resampling.
+/// Modify them in place for ducking or custom DSP, or copy them out for
+/// recording and analysis. Runs on the decoder thread, so keep it fast.
+pub trait AudioTap: Send {
+    fn process(&mut self, samples: &mut [f32], sample_rate: u32, channels: u16);
+}
+
+/// Tap slot shared between the player and the decoder thread
+pub type SharedAudioTap = Arc<Mutex<Option<Box<dyn AudioTap>>>>;
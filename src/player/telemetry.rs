@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+/// MISB ST 0601 UAS Datalink Local Set universal key
+const UAS_LOCAL_SET_KEY: [u8; 16] = [
+    0x06, 0x0E, 0x2B, 0x34, 0x02, 0x0B, 0x01, 0x01, 0x0E, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00,
+];
+
+/// Decoded MISB ST 0601 metadata from an embedded KLV stream.
+/// Fields missing from the local set (or flagged as errors) are None.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TelemetrySample {
+    /// Playback position this sample belongs to
+    pub timestamp: Duration,
+    /// Precision time stamp (microseconds since the Unix epoch)
+    pub unix_time_us: Option<u64>,
+    /// Platform heading in degrees (0..360)
+    pub platform_heading: Option<f64>,
+    /// Platform pitch in degrees (±20)
+    pub platform_pitch: Option<f64>,
+    /// Platform roll in degrees (±50)
+    pub platform_roll: Option<f64>,
+    /// Sensor latitude in degrees
+    pub sensor_latitude: Option<f64>,
+    /// Sensor longitude in degrees
+    pub sensor_longitude: Option<f64>,
+    /// Sensor true altitude in meters (MSL)
+    pub sensor_altitude: Option<f64>,
+    /// Sensor horizontal field of view in degrees
+    pub horizontal_fov: Option<f64>,
+    /// Sensor vertical field of view in degrees
+    pub vertical_fov: Option<f64>,
+    /// Sensor azimuth relative to the platform in degrees
+    pub sensor_azimuth: Option<f64>,
+    /// Sensor elevation relative to the platform in degrees
+    pub sensor_elevation: Option<f64>,
+    /// Sensor roll relative to the platform in degrees
+    pub sensor_roll: Option<f64>,
+    /// Latitude of the frame center in degrees
+    pub frame_center_latitude: Option<f64>,
+    /// Longitude of the frame center in degrees
+    pub frame_center_longitude: Option<f64>,
+    /// Elevation of the frame center in meters (MSL)
+    pub frame_center_elevation: Option<f64>,
+}
+
+/// Parse all UAS local sets contained in a KLV packet
+pub fn parse_klv_packet(data: &[u8], timestamp: f64) -> Vec<TelemetrySample> {
+    let mut samples = Vec::new();
+    let mut rest = data;
+
+    while rest.len() >= UAS_LOCAL_SET_KEY.len() {
+        let (key, after_key) = rest.split_at(UAS_LOCAL_SET_KEY.len());
+        let Some((len, after_len)) = read_ber_length(after_key) else {
+            break;
+        };
+        if after_len.len() < len {
+            break;
+        }
+        let (value, next) = after_len.split_at(len);
+
+        if key == UAS_LOCAL_SET_KEY {
+            samples.push(parse_local_set(value, timestamp));
+        }
+        rest = next;
+    }
+
+    samples
+}
+
+fn parse_local_set(mut data: &[u8], timestamp: f64) -> TelemetrySample {
+    let mut sample = TelemetrySample {
+        timestamp: Duration::from_secs_f64(timestamp.max(0.0)),
+        ..Default::default()
+    };
+
+    while let Some((tag, after_tag)) = read_ber_oid(data) {
+        let Some((len, after_len)) = read_ber_length(after_tag) else {
+            break;
+        };
+        if after_len.len() < len {
+            break;
+        }
+        let (value, next) = after_len.split_at(len);
+        data = next;
+
+        match tag {
+            2 => sample.unix_time_us = read_uint(value),
+            5 => sample.platform_heading = map_unsigned(value, 0.0, 360.0),
+            6 => sample.platform_pitch = map_signed(value, 20.0),
+            7 => sample.platform_roll = map_signed(value, 50.0),
+            13 => sample.sensor_latitude = map_signed(value, 90.0),
+            14 => sample.sensor_longitude = map_signed(value, 180.0),
+            15 => sample.sensor_altitude = map_unsigned(value, -900.0, 19000.0),
+            16 => sample.horizontal_fov = map_unsigned(value, 0.0, 180.0),
+            17 => sample.vertical_fov = map_unsigned(value, 0.0, 180.0),
+            18 => sample.sensor_azimuth = map_unsigned(value, 0.0, 360.0),
+            19 => sample.sensor_elevation = map_signed(value, 180.0),
+            20 => sample.sensor_roll = map_unsigned(value, 0.0, 360.0),
+            23 => sample.frame_center_latitude = map_signed(value, 90.0),
+            24 => sample.frame_center_longitude = map_signed(value, 180.0),
+            25 => sample.frame_center_elevation = map_unsigned(value, -900.0, 19000.0),
+            _ => {} // Checksum, mission ids and other tags are not surfaced
+        }
+    }
+
+    sample
+}
+
+/// Read a BER short or long form length
+fn read_ber_length(data: &[u8]) -> Option<(usize, &[u8])> {
+    let (&first, rest) = data.split_first()?;
+    if first < 0x80 {
+        return Some((first as usize, rest));
+    }
+    let count = (first & 0x7F) as usize;
+    if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+        return None;
+    }
+    let len = rest[..count]
+        .iter()
+        .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+    Some((len, &rest[count..]))
+}
+
+/// Read a BER-OID encoded tag number
+fn read_ber_oid(data: &[u8]) -> Option<(u32, &[u8])> {
+    let mut tag = 0u32;
+    for (i, &b) in data.iter().enumerate().take(4) {
+        tag = (tag << 7) | (b & 0x7F) as u32;
+        if b & 0x80 == 0 {
+            return Some((tag, &data[i + 1..]));
+        }
+    }
+    None
+}
+
+fn read_uint(value: &[u8]) -> Option<u64> {
+    if value.is_empty() || value.len() > 8 {
+        return None;
+    }
+    Some(value.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+}
+
+/// Map an unsigned integer onto `min..=max` (ST 0601 linear mapping)
+fn map_unsigned(value: &[u8], min: f64, max: f64) -> Option<f64> {
+    let raw = read_uint(value)?;
+    let full_scale = (1u128 << (value.len() * 8)) - 1;
+    Some(min + raw as f64 * (max - min) / full_scale as f64)
+}
+
+/// Map a signed integer onto `-range..=range`. The minimum value marks an error.
+fn map_signed(value: &[u8], range: f64) -> Option<f64> {
+    let raw = read_uint(value)?;
+    let bits = value.len() * 8;
+    let error_value = 1u64 << (bits - 1);
+    if raw == error_value {
+        return None;
+    }
+    // Sign-extend to i64
+    let signed = ((raw << (64 - bits)) as i64) >> (64 - bits);
+    let full_scale = (1u128 << bits) - 2;
+    Some(signed as f64 * 2.0 * range / full_scale as f64)
+}
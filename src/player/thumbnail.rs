@@ -0,0 +1,147 @@
+//! Standalone video thumbnails for file browsers and library grids.
+//!
+//! Each call opens its own short-lived decoder, so no `VideoPlayer`, texture
+//! or audio output is needed. Calls block; run them on a worker thread.
+
+use egui::ColorImage;
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::frame::Video as VideoFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::software::scaling::{Context as ScalerContext, Flags};
+use ffmpeg_next::{codec, Packet};
+use std::path::Path;
+use std::time::Duration;
+
+use super::color::{apply_colorspace, ColorManagement};
+use super::decoder::{frame_pixels, has_alpha, rotate_pixels, stream_rotation};
+use super::error::PlayerError;
+use super::sequence;
+
+/// Decode the frame shown at `at` and scale it to fit within `max_size`
+/// (width, height), keeping the aspect ratio. Positions past the end give
+/// the last frame.
+pub fn generate(path: &Path, at: Duration, max_size: [u32; 2]) -> Result<ColorImage, PlayerError> {
+    let mut input =
+        sequence::open_input(path, 0.0).map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+
+    let stream = input
+        .streams()
+        .best(Type::Video)
+        .ok_or(PlayerError::NoVideoStream)?;
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+    let rotation = stream_rotation(&stream);
+    let rate = stream.avg_frame_rate();
+    // A frame starting within one interval of the target is the one shown at it
+    let frame_interval = if rate.numerator() > 0 && rate.denominator() > 0 {
+        1.0 / f64::from(rate)
+    } else {
+        1.0 / 30.0
+    };
+    let mut decoder = codec::Context::from_parameters(stream.parameters())
+        .and_then(|context| context.decoder().video())
+        .map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+
+    // Land on the keyframe before the target, then decode up to it
+    let target = at.as_secs_f64();
+    let target_ts = (target * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+    if target_ts > 0 {
+        let _ = input.seek(target_ts, ..target_ts);
+    }
+
+    let mut packet = Packet::empty();
+    let mut decoded = VideoFrame::empty();
+    // Receiving clears the frame on failure, so keep the latest one separately
+    let mut latest = VideoFrame::empty();
+    let mut found = false;
+    let mut at_eof = false;
+    while !found && !at_eof {
+        match packet.read(&mut input) {
+            Ok(()) => {
+                // Skip other streams and corrupt packets
+                if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+                    continue;
+                }
+            }
+            Err(ffmpeg_next::Error::Eof) => {
+                at_eof = true;
+                let _ = decoder.send_eof();
+            }
+            Err(e) => return Err(PlayerError::from_ffmpeg(e, path)),
+        }
+
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            std::mem::swap(&mut decoded, &mut latest);
+            let pts = latest.timestamp().unwrap_or(0) as f64 * time_base;
+            if pts > target - frame_interval {
+                found = true;
+                break;
+            }
+        }
+    }
+    let decoded = latest;
+    if decoded.width() == 0 || decoded.height() == 0 {
+        return Err(PlayerError::Other(format!(
+            "{}: no frame could be decoded",
+            path.display()
+        )));
+    }
+
+    // Scale before rotating, so fit the stored (sideways) orientation
+    let bounds = if rotation % 180 == 90 {
+        [max_size[1], max_size[0]]
+    } else {
+        max_size
+    };
+    let (width, height) = fit(decoded.width(), decoded.height(), bounds);
+    to_image(&decoded, [width, height], rotation, path)
+}
+
+/// Convert a decoded frame to an upright RGBA image of the given stored size
+pub(super) fn to_image(
+    decoded: &VideoFrame,
+    [width, height]: [u32; 2],
+    rotation: u32,
+    path: &Path,
+) -> Result<ColorImage, PlayerError> {
+    let mut scaler = ScalerContext::get(
+        decoded.format(),
+        decoded.width(),
+        decoded.height(),
+        Pixel::RGBA,
+        width,
+        height,
+        Flags::AREA,
+    )
+    .map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+    let (matrix, range) = ColorManagement::Auto.resolve(
+        decoded.color_space(),
+        decoded.color_range(),
+        decoded.height(),
+    );
+    apply_colorspace(&mut scaler, matrix, range);
+
+    let mut rgba_frame = VideoFrame::empty();
+    scaler
+        .run(decoded, &mut rgba_frame)
+        .map_err(|e| PlayerError::from_ffmpeg(e, path))?;
+
+    let (pixels, [width, height]) = rotate_pixels(
+        frame_pixels(&rgba_frame, has_alpha(decoded.format())),
+        [width, height],
+        rotation,
+    );
+    Ok(ColorImage {
+        size: [width as usize, height as usize],
+        pixels,
+    })
+}
+
+/// Largest size with the source aspect ratio that fits in `max_size`, never upscaled
+pub(super) fn fit(width: u32, height: u32, max_size: [u32; 2]) -> (u32, u32) {
+    let scale = (f64::from(max_size[0]) / f64::from(width))
+        .min(f64::from(max_size[1]) / f64::from(height))
+        .min(1.0);
+    let scaled = |size: u32| ((f64::from(size) * scale).round() as u32).max(1);
+    (scaled(width), scaled(height))
+}
@@ -0,0 +1,317 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::Sender;
+use egui::{Color32, ColorImage, Pos2, Rect};
+use ffmpeg_next::frame::Video as VideoFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::{codec, Packet};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::decoder::stream_rotation;
+use super::events::PlayerEvent;
+use super::{sequence, snapshot, thumbnail};
+
+/// Longest side of a thumbnail in pixels
+const TILE_SIZE: u32 = 160;
+/// More thumbnails are spaced further apart, keeping the sheet within
+/// common GPU texture limits
+const MAX_TILES: usize = 400;
+/// Bytes read from each end of the file for its cache key
+const KEY_SAMPLE: u64 = 1 << 20;
+/// Decoder threads started for one file
+const MAX_WORKERS: usize = 4;
+
+/// Low-resolution thumbnails at regular intervals, packed into one sprite
+/// sheet for hover previews and filmstrips
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThumbnailTrack {
+    sheet: Arc<ColorImage>,
+    interval: Duration,
+    columns: usize,
+    count: usize,
+}
+
+impl ThumbnailTrack {
+    /// All thumbnails in rows, left to right from the start of the media
+    #[must_use]
+    pub fn sheet(&self) -> &Arc<ColorImage> {
+        &self.sheet
+    }
+
+    /// Media time between thumbnails
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Number of thumbnails
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Size of one thumbnail in pixels
+    #[must_use]
+    pub fn tile_size(&self) -> [usize; 2] {
+        let rows = self.count.div_ceil(self.columns);
+        [
+            self.sheet.width() / self.columns,
+            self.sheet.height() / rows.max(1),
+        ]
+    }
+
+    /// Index of the thumbnail covering a media time
+    #[must_use]
+    pub fn index_at(&self, at: Duration) -> usize {
+        let index = (at.as_secs_f64() / self.interval.as_secs_f64()) as usize;
+        index.min(self.count.saturating_sub(1))
+    }
+
+    /// Texture coordinates of a thumbnail within the sheet
+    #[must_use]
+    pub fn uv(&self, index: usize) -> Rect {
+        let [width, height] = self.sheet.size;
+        let [tile_width, tile_height] = self.tile_size();
+        let min = Pos2::new(
+            ((index % self.columns) * tile_width) as f32 / width as f32,
+            ((index / self.columns) * tile_height) as f32 / height as f32,
+        );
+        Rect::from_min_size(
+            min,
+            egui::vec2(
+                tile_width as f32 / width as f32,
+                tile_height as f32 / height as f32,
+            ),
+        )
+    }
+}
+
+/// Build the thumbnail track of `path` on background threads and send it,
+/// reading and writing a cached sheet in `cache_dir` if given.
+/// Failures are reported as a `PlayerEvent::Warning`.
+pub fn start_thumbnail_thread(
+    path: PathBuf,
+    interval: Duration,
+    cache_dir: Option<PathBuf>,
+    sender: Sender<ThumbnailTrack>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(
+        move || match build(&path, interval, cache_dir.as_deref(), &event_sender) {
+            Ok(track) => {
+                let _ = sender.try_send(track);
+            }
+            Err(e) => {
+                let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                    "Thumbnail generation failed: {:#}",
+                    e
+                )));
+            }
+        },
+    );
+}
+
+fn build(
+    path: &Path,
+    interval: Duration,
+    cache_dir: Option<&Path>,
+    event_sender: &Sender<PlayerEvent>,
+) -> Result<ThumbnailTrack> {
+    let duration = sequence::open_input(path, 0.0)
+        .context("Failed to open input file")?
+        .duration();
+    if duration <= 0 {
+        return Err(anyhow!("Media duration is unknown"));
+    }
+    let duration = duration as f64 / ffmpeg_next::ffi::AV_TIME_BASE as f64;
+
+    let interval = interval
+        .as_secs_f64()
+        .max(duration / MAX_TILES as f64)
+        .max(0.1);
+    let count = ((duration / interval).ceil() as usize).max(1);
+    let columns = (count as f64).sqrt().ceil() as usize;
+    let interval = Duration::from_secs_f64(interval);
+
+    let cache_path = match cache_dir {
+        Some(dir) => Some(dir.join(format!("{:016x}.png", cache_key(path, interval)?))),
+        None => None,
+    };
+    if let Some(sheet) = cache_path.as_deref().and_then(load_sheet) {
+        return Ok(ThumbnailTrack {
+            sheet: Arc::new(sheet),
+            interval,
+            columns,
+            count,
+        });
+    }
+
+    // Each worker decodes a contiguous run of thumbnails with its own input
+    let workers = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(MAX_WORKERS)
+        .min(count);
+    let chunk = count.div_ceil(workers);
+    let tiles = thread::scope(|scope| {
+        let handles: Vec<_> = (0..count)
+            .step_by(chunk)
+            .map(|start| {
+                let end = (start + chunk).min(count);
+                scope.spawn(move || decode_tiles(path, interval, start..end))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .map_err(|_| anyhow!("Thumbnail worker panicked"))?
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let tiles: Vec<ColorImage> = tiles.into_iter().flatten().collect();
+
+    let sheet = pack(&tiles, columns);
+    if let Some(cache_path) = cache_path {
+        let saved = std::fs::create_dir_all(cache_path.parent().unwrap_or(Path::new(".")))
+            .map_err(anyhow::Error::from)
+            .and_then(|()| snapshot::save_png(&sheet, &cache_path));
+        if let Err(e) = saved {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Failed to cache thumbnails: {:#}",
+                e
+            )));
+        }
+    }
+    Ok(ThumbnailTrack {
+        sheet: Arc::new(sheet),
+        interval,
+        columns,
+        count,
+    })
+}
+
+/// Decode the keyframe at or before each thumbnail's time in `indices`
+fn decode_tiles(
+    path: &Path,
+    interval: Duration,
+    indices: std::ops::Range<usize>,
+) -> Result<Vec<ColorImage>> {
+    let mut input = sequence::open_input(path, 0.0).context("Failed to open input file")?;
+    let stream = input
+        .streams()
+        .best(Type::Video)
+        .context("No video stream found")?;
+    let stream_index = stream.index();
+    let rotation = stream_rotation(&stream);
+    let mut decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()?;
+
+    let mut tiles = Vec::with_capacity(indices.len());
+    let mut packet = Packet::empty();
+    let mut decoded = VideoFrame::empty();
+    for index in indices {
+        let target = interval.as_secs_f64() * index as f64;
+        let target_ts = (target * ffmpeg_next::ffi::AV_TIME_BASE as f64) as i64;
+        let _ = input.seek(target_ts, ..=target_ts);
+        decoder.flush();
+
+        let mut found = false;
+        let mut at_eof = false;
+        while !found && !at_eof {
+            match packet.read(&mut input) {
+                Ok(()) => {
+                    if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+                        continue;
+                    }
+                }
+                Err(ffmpeg_next::Error::Eof) => {
+                    at_eof = true;
+                    let _ = decoder.send_eof();
+                }
+                Err(e) => return Err(e).context("Failed to read packet"),
+            }
+            found = decoder.receive_frame(&mut decoded).is_ok();
+        }
+        if !found {
+            // Past the last keyframe: repeat the previous thumbnail
+            let previous = tiles.last().cloned().context("No frame could be decoded")?;
+            tiles.push(previous);
+            continue;
+        }
+
+        // A square bound fits the stored and the rotated orientation alike
+        let (width, height) =
+            thumbnail::fit(decoded.width(), decoded.height(), [TILE_SIZE, TILE_SIZE]);
+        tiles.push(thumbnail::to_image(
+            &decoded,
+            [width, height],
+            rotation,
+            path,
+        )?);
+    }
+    Ok(tiles)
+}
+
+/// Lay tiles out in rows, sized by the first one
+fn pack(tiles: &[ColorImage], columns: usize) -> ColorImage {
+    let [tile_width, tile_height] = tiles.first().map_or([1, 1], |tile| tile.size);
+    let rows = tiles.len().div_ceil(columns);
+    let width = columns * tile_width;
+    let mut sheet = ColorImage::new([width, rows * tile_height], Color32::BLACK);
+    for (index, tile) in tiles.iter().enumerate() {
+        let x = (index % columns) * tile_width;
+        let y = (index / columns) * tile_height;
+        let copy_width = tile.width().min(tile_width);
+        for row in 0..tile.height().min(tile_height) {
+            let source = row * tile.width();
+            let target = (y + row) * width + x;
+            sheet.pixels[target..target + copy_width]
+                .copy_from_slice(&tile.pixels[source..source + copy_width]);
+        }
+    }
+    sheet
+}
+
+fn load_sheet(path: &Path) -> Option<ColorImage> {
+    let image = image::open(path).ok()?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Some(ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+/// Hash of the file's length, its first and last megabyte and the spacing,
+/// so renamed copies share a cache entry and edited files do not
+fn cache_key(path: &Path, interval: Duration) -> Result<u64> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let len = file.metadata()?.len();
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(len);
+    hasher.write_u128(interval.as_nanos());
+    hasher.write_u32(TILE_SIZE);
+
+    let mut buffer = Vec::with_capacity(KEY_SAMPLE as usize);
+    file.by_ref().take(KEY_SAMPLE).read_to_end(&mut buffer)?;
+    hasher.write(&buffer);
+    if len > KEY_SAMPLE {
+        buffer.clear();
+        file.seek(SeekFrom::Start(
+            len.saturating_sub(KEY_SAMPLE).max(KEY_SAMPLE),
+        ))?;
+        file.read_to_end(&mut buffer)?;
+        hasher.write(&buffer);
+    }
+    Ok(hasher.finish())
+}
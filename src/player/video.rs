@@ -1,40 +1,168 @@
 use crossbeam_channel::Receiver;
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use super::decoder::DecodedVideoFrame;
+use super::stats::FrameIntervals;
 
-/// Threshold for frame dropping (seconds behind audio)
-const DROP_THRESHOLD: f64 = 0.02;
-/// Threshold for holding frames (seconds ahead of audio)
-const HOLD_THRESHOLD: f64 = 0.02;
+/// Frame interval assumed until enough frames have been seen (seconds)
+pub const DEFAULT_FRAME_INTERVAL: f64 = 1.0 / 30.0;
+/// Gaps longer than this are treated as discontinuities, not frame intervals (seconds)
+const MAX_INTERVAL: f64 = 1.0;
+/// Number of recent frame intervals kept for statistics
+const INTERVAL_WINDOW: usize = 240;
+/// How precisely a seek lands on the requested position
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeekMode {
+    /// Show the frame at the position, decoding forward from the preceding keyframe
+    #[default]
+    Exact,
+    /// Show the keyframe at or before the position. Much faster on long GOPs,
+    /// for scrubbing; playback continues from the keyframe.
+    Keyframe,
+}
+
+impl SeekMode {
+    /// Earliest frame timestamp accepted as the result of a seek to `target` (seconds)
+    pub(crate) fn earliest_pts(self, target: f64, frame_interval: f64) -> f64 {
+        match self {
+            // The frame shown at the target starts within one interval before it
+            Self::Exact => target - frame_interval,
+            Self::Keyframe => f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// How the video queue paces frames against the audio clock.
+/// Thresholds are multiples of each frame's actual interval, so variable
+/// frame rate content (e.g. screen recordings) is paced by its real timestamps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FramePacing {
+    /// Drop a frame once it is this many of its own intervals behind the clock
+    pub drop_intervals: f64,
+    /// Show a frame up to this many of its own intervals before its PTS
+    pub hold_intervals: f64,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        Self {
+            drop_intervals: 1.0,
+            hold_intervals: 0.5,
+        }
+    }
+}
+
+/// How video is kept in sync with the clock during playback
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyncPolicy {
+    /// Frame drop and hold thresholds
+    pub pacing: FramePacing,
+    /// Seek to the clock position once the video is this far ahead of or
+    /// behind it (seconds), e.g. after a decoder stall. None never resyncs.
+    pub max_drift_before_resync: Option<f64>,
+    /// Follow the audio clock. When off, frames are paced on wall time by their
+    /// own timestamps (video master) and audio plays alongside unsynced, for
+    /// clips whose audio track is silent.
+    pub audio_master: bool,
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        Self {
+            pacing: FramePacing::default(),
+            max_drift_before_resync: None,
+            audio_master: true,
+        }
+    }
+}
 
 /// Queue that manages video frames and sync to audio clock
 pub struct VideoFrameQueue {
     receiver: Receiver<DecodedVideoFrame>,
     buffer: VecDeque<DecodedVideoFrame>,
     max_buffer_size: usize,
+    pacing: FramePacing,
+    /// PTS of the most recently received frame
+    last_pts: Option<f64>,
+    /// Recent inter-frame gaps in seconds
+    intervals: VecDeque<f64>,
+    /// Seek generation of the frames wanted; frames decoded before the latest seek are dropped
+    generation: u64,
+    /// Frames dropped for arriving too late to show
+    dropped: u64,
+    /// PTS of the last frame dropped by the latest `get_display_frame`
+    last_dropped: Option<f64>,
 }
 
 impl VideoFrameQueue {
-    pub fn new(receiver: Receiver<DecodedVideoFrame>, max_buffer_size: usize) -> Self {
+    pub fn new(
+        receiver: Receiver<DecodedVideoFrame>,
+        max_buffer_size: usize,
+        pacing: FramePacing,
+    ) -> Self {
         Self {
             receiver,
             buffer: VecDeque::with_capacity(max_buffer_size),
             max_buffer_size,
+            pacing,
+            last_pts: None,
+            intervals: VecDeque::with_capacity(INTERVAL_WINDOW),
+            generation: 0,
+            dropped: 0,
+            last_dropped: None,
         }
     }
 
+    pub fn set_pacing(&mut self, pacing: FramePacing) {
+        self.pacing = pacing;
+    }
+
     /// Update the queue by receiving new frames from the decoder
     pub fn receive_frames(&mut self) {
         // Receive frames up to buffer capacity
         while self.buffer.len() < self.max_buffer_size {
             match self.receiver.try_recv() {
+                // Decoded before the latest seek, but sent after it was requested
+                Ok(frame) if frame.generation < self.generation => {}
                 Ok(frame) => {
+                    self.record_interval(frame.pts);
                     self.buffer.push_back(frame);
                 }
                 Err(_) => break,
             }
         }
+        tracing::trace!(depth = self.buffer.len(), "frame queue depth");
+    }
+
+    fn record_interval(&mut self, pts: f64) {
+        if let Some(last) = self.last_pts {
+            let gap = pts - last;
+            if gap > 0.0 && gap < MAX_INTERVAL {
+                if self.intervals.len() == INTERVAL_WINDOW {
+                    self.intervals.pop_front();
+                }
+                self.intervals.push_back(gap);
+            }
+        }
+        self.last_pts = Some(pts);
+    }
+
+    /// Average of the recent frame intervals, used when a frame has no successor yet
+    fn average_interval(&self) -> f64 {
+        if self.intervals.is_empty() {
+            DEFAULT_FRAME_INTERVAL
+        } else {
+            self.intervals.iter().sum::<f64>() / self.intervals.len() as f64
+        }
+    }
+
+    /// Interval of the buffered frame at `index`: the gap to the next frame's PTS
+    fn interval_at(&self, index: usize) -> f64 {
+        match (self.buffer.get(index), self.buffer.get(index + 1)) {
+            (Some(frame), Some(next)) if next.pts > frame.pts => next.pts - frame.pts,
+            _ => self.average_interval(),
+        }
     }
 
     /// Get the frame that should be displayed for the given audio time.
@@ -42,11 +170,16 @@ impl VideoFrameQueue {
     /// Returns owned frame to allow zero-copy ColorImage creation.
     pub fn get_display_frame(&mut self, audio_time: f64) -> Option<DecodedVideoFrame> {
         self.receive_frames();
+        self.last_dropped = None;
 
         // Drop frames that are too late
         while let Some(frame) = self.buffer.front() {
-            if frame.pts < audio_time - DROP_THRESHOLD {
+            let drop_threshold = self.interval_at(0) * self.pacing.drop_intervals;
+            if frame.pts < audio_time - drop_threshold {
+                tracing::debug!(pts = frame.pts, audio_time, "dropped late frame");
+                self.last_dropped = Some(frame.pts);
                 self.buffer.pop_front();
+                self.dropped += 1;
             } else {
                 break;
             }
@@ -54,7 +187,8 @@ impl VideoFrameQueue {
 
         // Check if next frame should be shown
         if let Some(frame) = self.buffer.front() {
-            if frame.pts <= audio_time + HOLD_THRESHOLD {
+            let hold_threshold = self.interval_at(0) * self.pacing.hold_intervals;
+            if frame.pts <= audio_time + hold_threshold {
                 return self.buffer.pop_front();
             }
         }
@@ -62,15 +196,22 @@ impl VideoFrameQueue {
         None
     }
 
+    /// How far the video is off `audio_time` after `get_display_frame`: the next
+    /// frame's PTS minus the clock, or the last frame it dropped when none is queued
+    pub fn drift(&self, audio_time: f64) -> Option<f64> {
+        let pts = self.buffer.front().map(|frame| frame.pts);
+        Some(pts.or(self.last_dropped)? - audio_time)
+    }
+
     /// Get the first available frame after a seek (more lenient than sync logic)
-    /// Accepts any frame at or after the seek target.
+    /// Accepts any frame at or after `earliest_pts`.
     /// Returns owned frame to allow zero-copy ColorImage creation.
-    pub fn get_first_frame_after_seek(&mut self, seek_target: f64) -> Option<DecodedVideoFrame> {
+    pub fn get_first_frame_after_seek(&mut self, earliest_pts: f64) -> Option<DecodedVideoFrame> {
         self.receive_frames();
 
-        // Drop frames that are before the seek target (with some tolerance)
+        // Drop frames decoded on the way from the keyframe to the target
         while let Some(frame) = self.buffer.front() {
-            if frame.pts < seek_target - 0.5 {
+            if frame.pts < earliest_pts {
                 self.buffer.pop_front();
             } else {
                 break;
@@ -86,10 +227,44 @@ impl VideoFrameQueue {
         self.buffer.clear();
         // Drain the receiver
         while self.receiver.try_recv().is_ok() {}
+        // The next frame starts a new run of timestamps
+        self.last_pts = None;
+    }
+
+    /// Clear the queue for a seek. Returns the generation the decoder tags
+    /// frames for the new position with; older frames still in flight are dropped.
+    pub fn begin_seek(&mut self) -> u64 {
+        self.clear();
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Take frames from another decoder, dropping everything buffered
+    pub fn replace_receiver(&mut self, receiver: Receiver<DecodedVideoFrame>) {
+        self.clear();
+        self.receiver = receiver;
+        // A new decoder counts its seeks from zero
+        self.generation = 0;
     }
 
     /// Check if queue is empty (end of stream reached)
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty() && self.receiver.is_empty()
     }
+
+    /// Frames dropped during playback for arriving too late
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Average, minimum and maximum of the recent frame intervals (None until two frames arrived)
+    pub fn frame_intervals(&self) -> Option<FrameIntervals> {
+        let min = self.intervals.iter().copied().reduce(f64::min)?;
+        let max = self.intervals.iter().copied().reduce(f64::max)?;
+        Some(FrameIntervals {
+            average: Duration::from_secs_f64(self.average_interval()),
+            min: Duration::from_secs_f64(min),
+            max: Duration::from_secs_f64(max),
+        })
+    }
 }
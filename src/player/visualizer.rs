@@ -0,0 +1,218 @@
+use egui::{Color32, ColorImage};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Interleaved samples kept for visualizers, about 85 ms of 48 kHz stereo
+const HISTORY_LEN: usize = 8192;
+/// Lowest frequency shown by the spectrum visualizers
+const MIN_FREQUENCY: f32 = 40.0;
+/// Levels below this are drawn as silence
+const FLOOR_DB: f32 = -70.0;
+
+/// Audio visualization drawn in place of the video in audio-only mode.
+///
+/// Called on the UI thread at the visualizer frame rate while playing. The
+/// image keeps its contents between calls, so scrolling displays only draw
+/// what is new.
+pub trait Visualizer {
+    /// Draw the most recently played `samples` (interleaved f32 at the output
+    /// sample rate, oldest first) into `image`
+    fn render(&mut self, samples: &[f32], channels: u16, sample_rate: u32, image: &mut ColorImage);
+}
+
+/// Samples recorded as the output plays them, for visualizers
+#[derive(Clone, Default)]
+pub struct SampleHistory {
+    samples: Arc<Mutex<VecDeque<f32>>>,
+    /// Recording costs a lock per block, so it only runs with a visualizer set
+    active: Arc<AtomicBool>,
+}
+
+impl SampleHistory {
+    pub fn set_active(&self, active: bool) {
+        self.active.store(active, Ordering::Relaxed);
+        if !active {
+            self.samples.lock().clear();
+        }
+    }
+
+    /// Append a block handed to the output
+    pub fn push(&self, block: &[f32]) {
+        if !self.active.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut samples = self.samples.lock();
+        samples.extend(block);
+        let excess = samples.len().saturating_sub(HISTORY_LEN);
+        samples.drain(..excess);
+    }
+
+    /// Copy out the recorded samples, oldest first
+    pub fn snapshot(&self) -> Vec<f32> {
+        self.samples.lock().iter().copied().collect()
+    }
+}
+
+/// Frequency bars, like a graphic equalizer
+pub struct SpectrumBars {
+    bands: usize,
+    /// Displayed level of each band (0.0 to 1.0), falling back slowly
+    levels: Vec<f32>,
+}
+
+impl SpectrumBars {
+    /// Bars for `bands` log-spaced frequency bands
+    #[must_use]
+    pub fn new(bands: usize) -> Self {
+        Self {
+            bands: bands.max(1),
+            levels: vec![0.0; bands.max(1)],
+        }
+    }
+}
+
+impl Default for SpectrumBars {
+    fn default() -> Self {
+        Self::new(48)
+    }
+}
+
+impl Visualizer for SpectrumBars {
+    fn render(&mut self, samples: &[f32], channels: u16, sample_rate: u32, image: &mut ColorImage) {
+        let mono = mono(samples, channels, 2048);
+        for (band, level) in self.levels.iter_mut().enumerate() {
+            let frequency = band_frequency(band as f32 / self.bands as f32, sample_rate);
+            let target = normalized_level(&mono, frequency, sample_rate);
+            *level = target.max(*level - 0.04);
+        }
+
+        clear(image);
+        let [width, height] = image.size;
+        // Leave a one pixel gap between bars wide enough to spare it
+        let bar_width = (width / self.bands).max(1);
+        let fill = if bar_width > 2 {
+            bar_width - 1
+        } else {
+            bar_width
+        };
+        for (band, level) in self.levels.iter().enumerate() {
+            let bar_height = (level * height as f32) as usize;
+            let color = Color32::from_rgb(80, (120.0 + 135.0 * level) as u8, 255);
+            let x0 = band * width / self.bands;
+            for y in height - bar_height..height {
+                for x in x0..(x0 + fill).min(width) {
+                    image.pixels[y * width + x] = color;
+                }
+            }
+        }
+    }
+}
+
+/// Waveform of the last few milliseconds, like an oscilloscope
+#[derive(Default)]
+pub struct Oscilloscope;
+
+impl Visualizer for Oscilloscope {
+    fn render(
+        &mut self,
+        samples: &[f32],
+        channels: u16,
+        _sample_rate: u32,
+        image: &mut ColorImage,
+    ) {
+        let mono = mono(samples, channels, 2048);
+        clear(image);
+        let [width, height] = image.size;
+        if mono.is_empty() || width == 0 {
+            return;
+        }
+        let row = |value: f32| {
+            let y = (0.5 - value.clamp(-1.0, 1.0) * 0.45) * (height - 1) as f32;
+            y.round() as usize
+        };
+        let mut previous = row(mono[0]);
+        for x in 0..width {
+            let current = row(mono[x * mono.len() / width]);
+            // Join the points vertically so steep edges stay connected
+            for y in previous.min(current)..=previous.max(current) {
+                image.pixels[y * width + x] = Color32::from_rgb(90, 255, 140);
+            }
+            previous = current;
+        }
+    }
+}
+
+/// Scrolling time-frequency display: time runs right, pitch up, loudness in color
+#[derive(Default)]
+pub struct Spectrogram;
+
+impl Visualizer for Spectrogram {
+    fn render(&mut self, samples: &[f32], channels: u16, sample_rate: u32, image: &mut ColorImage) {
+        let mono = mono(samples, channels, 1024);
+        let [width, height] = image.size;
+        if width == 0 {
+            return;
+        }
+        for y in 0..height {
+            let row = &mut image.pixels[y * width..(y + 1) * width];
+            row.copy_within(1.., 0);
+            let frequency = band_frequency(1.0 - y as f32 / height as f32, sample_rate);
+            row[width - 1] = heat(normalized_level(&mono, frequency, sample_rate));
+        }
+    }
+}
+
+/// The last `len` samples mixed to mono
+fn mono(samples: &[f32], channels: u16, len: usize) -> Vec<f32> {
+    let channels = usize::from(channels.max(1));
+    let frames: Vec<f32> = samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    frames[frames.len().saturating_sub(len)..].to_vec()
+}
+
+/// Log-spaced frequency for a position from 0.0 (lowest) to 1.0 (Nyquist, capped at 16 kHz)
+fn band_frequency(position: f32, sample_rate: u32) -> f32 {
+    let max = (sample_rate as f32 / 2.0).min(16_000.0);
+    MIN_FREQUENCY * (max / MIN_FREQUENCY).powf(position)
+}
+
+/// Level of one frequency (Goertzel over a Hann window), mapped from the
+/// floor to full scale onto 0.0 to 1.0
+fn normalized_level(samples: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+    let len = samples.len();
+    if len < 2 {
+        return 0.0;
+    }
+    let coefficient = 2.0 * (2.0 * PI * frequency / sample_rate as f32).cos();
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+    for (n, &sample) in samples.iter().enumerate() {
+        let window = 0.5 - 0.5 * (2.0 * PI * n as f32 / (len - 1) as f32).cos();
+        let s0 = sample * window + coefficient * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    let power = (s1 * s1 + s2 * s2 - coefficient * s1 * s2).max(0.0);
+    // A full-scale sine gives an amplitude of a quarter of the length through the window
+    let amplitude = power.sqrt() / (len as f32 / 4.0);
+    let db = 20.0 * amplitude.max(1e-9).log10();
+    ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+}
+
+/// Black through blue, red and yellow to white
+fn heat(level: f32) -> Color32 {
+    let channel = |start: f32| ((level - start) * 3.0).clamp(0.0, 1.0);
+    Color32::from_rgb(
+        (channel(0.2) * 255.0) as u8,
+        (channel(0.5) * 255.0) as u8,
+        ((channel(0.0) - channel(0.4) + channel(0.75)).clamp(0.0, 1.0) * 255.0) as u8,
+    )
+}
+
+fn clear(image: &mut ColorImage) {
+    image.pixels.fill(Color32::BLACK);
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the watched file's metadata is checked
+pub const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls a file's modification time and size to notice when it is rewritten
+pub struct FileWatch {
+    stamp: Option<(SystemTime, u64)>,
+    changed: bool,
+    last_check: Instant,
+}
+
+impl FileWatch {
+    /// Start watching from the file's current state
+    pub fn new(path: &Path) -> Self {
+        Self {
+            stamp: stamp(path),
+            changed: false,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Returns true once after the file changed and then stayed unchanged
+    /// for a full interval, so a file still being written is not reopened
+    pub fn poll(&mut self, path: &Path) -> bool {
+        if self.last_check.elapsed() < WATCH_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let current = stamp(path);
+        if current != self.stamp {
+            self.stamp = current;
+            self.changed = true;
+            return false;
+        }
+        // A deleted file is waited out until it is written again
+        if self.changed && current.is_some() {
+            self.changed = false;
+            return true;
+        }
+        false
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Context, Result};
+use crossbeam_channel::Sender;
+use ffmpeg_next::frame::Audio as AudioFrame;
+use ffmpeg_next::media::Type;
+use ffmpeg_next::software::resampling::Context as ResamplerContext;
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::util::format::sample::{Sample, Type as SampleType};
+use ffmpeg_next::{codec, Packet};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use super::events::PlayerEvent;
+use super::filter::channel_layout;
+
+/// Number of levels computed across the whole file
+const WAVEFORM_BUCKETS: usize = 2048;
+
+/// Loudness overview of a file's audio track, for drawing along the timeline
+#[derive(Clone, Debug, PartialEq)]
+pub struct Waveform {
+    levels: Vec<f32>,
+    duration: Duration,
+}
+
+impl Waveform {
+    /// RMS levels (0.0 to 1.0) of equal-length slices of the file
+    #[must_use]
+    pub fn levels(&self) -> &[f32] {
+        &self.levels
+    }
+
+    /// Length of media the levels cover
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Loudest level in each of `width` columns, e.g. one per timeline pixel
+    #[must_use]
+    pub fn columns(&self, width: usize) -> Vec<f32> {
+        let len = self.levels.len();
+        if len == 0 {
+            return vec![0.0; width];
+        }
+        (0..width)
+            .map(|x| {
+                let start = (x * len / width).min(len - 1);
+                let end = ((x + 1) * len / width).clamp(start + 1, len);
+                self.levels[start..end].iter().copied().fold(0.0, f32::max)
+            })
+            .collect()
+    }
+}
+
+/// Decode the audio track of `path` on a background thread and send its waveform.
+/// Failures are reported as a `PlayerEvent::Warning`.
+pub fn start_waveform_thread(
+    path: PathBuf,
+    sender: Sender<Waveform>,
+    event_sender: Sender<PlayerEvent>,
+) {
+    thread::spawn(move || match analyze(&path) {
+        Ok(waveform) => {
+            let _ = sender.try_send(waveform);
+        }
+        Err(e) => {
+            let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                "Waveform generation failed: {:#}",
+                e
+            )));
+        }
+    });
+}
+
+fn analyze(path: &Path) -> Result<Waveform> {
+    let mut input = ffmpeg_next::format::input(path).context("Failed to open input file")?;
+
+    let stream = input
+        .streams()
+        .best(Type::Audio)
+        .ok_or_else(|| anyhow!("No audio stream found"))?;
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+    let mut decoder = codec::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .audio()?;
+
+    if input.duration() <= 0 {
+        return Err(anyhow!("Media duration is unknown"));
+    }
+    let duration = input.duration() as f64 / ffmpeg_next::ffi::AV_TIME_BASE as f64;
+
+    // Downmix to mono f32 at the source rate
+    let rate = decoder.rate();
+    let mut resampler = ResamplerContext::get(
+        decoder.format(),
+        channel_layout(&decoder),
+        rate,
+        Sample::F32(SampleType::Packed),
+        ChannelLayout::MONO,
+        rate,
+    )?;
+
+    let bucket_secs = duration / WAVEFORM_BUCKETS as f64;
+    let mut sums = vec![0.0f64; WAVEFORM_BUCKETS];
+    let mut counts = vec![0u32; WAVEFORM_BUCKETS];
+    let mut position = 0.0;
+
+    let mut packet = Packet::empty();
+    let mut decoded = AudioFrame::empty();
+    let mut mono = AudioFrame::empty();
+    loop {
+        match packet.read(&mut input) {
+            Ok(()) => {}
+            Err(ffmpeg_next::Error::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        }
+        // Skip other streams and corrupt packets
+        if packet.stream() != stream_index || decoder.send_packet(&packet).is_err() {
+            continue;
+        }
+
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            if let Some(ts) = decoded.timestamp() {
+                position = ts as f64 * time_base;
+            }
+            if resampler.run(&decoded, &mut mono).is_err() {
+                continue;
+            }
+            for (i, &sample) in mono.plane::<f32>(0).iter().enumerate() {
+                let time = position + i as f64 / f64::from(rate);
+                let bucket = ((time / bucket_secs).max(0.0) as usize).min(WAVEFORM_BUCKETS - 1);
+                sums[bucket] += f64::from(sample) * f64::from(sample);
+                counts[bucket] += 1;
+            }
+            position += decoded.samples() as f64 / f64::from(rate);
+        }
+    }
+
+    let levels = sums
+        .iter()
+        .zip(&counts)
+        .map(|(&sum, &count)| {
+            if count == 0 {
+                0.0
+            } else {
+                ((sum / f64::from(count)).sqrt() as f32).min(1.0)
+            }
+        })
+        .collect();
+
+    Ok(Waveform {
+        levels,
+        duration: Duration::from_secs_f64(duration),
+    })
+}
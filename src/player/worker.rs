@@ -0,0 +1,672 @@
+use anyhow::Result;
+use crossbeam_channel::{Receiver, SendTimeoutError, Sender};
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::frame::{Audio as AudioFrame, Video as VideoFrame};
+use ffmpeg_next::software::resampling::Context as ResamplerContext;
+use ffmpeg_next::software::scaling::{Context as ScalerContext, Flags};
+use ffmpeg_next::util::channel_layout::ChannelLayout;
+use ffmpeg_next::util::format::sample::Sample;
+use ffmpeg_next::{decoder, Packet, Rational};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::budget::FrameBudget;
+use super::clock::AudioClock;
+use super::color::{apply_colorspace, ColorManagement};
+use super::decoder::{has_alpha, DecodedVideoFrame};
+use super::effect::{apply_effects, SharedVideoEffects};
+use super::events::PlayerEvent;
+use super::filter::{channel_layout, pitch_filter, FrameFilter};
+use super::frame_metadata::read_frame_metadata;
+use super::ring_buffer::AudioProducer;
+use super::tap::SharedAudioTap;
+
+/// Packets queued for the video worker; enough to ride out bursts of large keyframes
+pub const VIDEO_PACKET_QUEUE: usize = 64;
+/// Packets queued for the audio worker (audio packets are small and frequent)
+pub const AUDIO_PACKET_QUEUE: usize = 256;
+/// How often a worker waiting for room in the frame channel checks for seeks and stop
+const SEND_POLL: Duration = Duration::from_millis(50);
+/// Audio held while a seek waits for its video frame (seconds)
+const MAX_HELD_AUDIO: f64 = 2.0;
+/// Longest silence put before audio that starts after its frame (seconds)
+const MAX_LEAD_SILENCE: f64 = 10.0;
+/// GPMF packets kept for the video worker; GoPro writes about one per second,
+/// and the demuxer reads at most a few seconds ahead
+const GPMF_PACKETS: usize = 8;
+
+/// Messages from the demuxer to a decode worker
+pub enum WorkerMessage {
+    /// A packet read after the seek with this generation
+    Packet(u64, Packet),
+    /// Discard the codec's buffered frames, e.g. after a reconnect
+    Flush,
+}
+
+/// Where the audio of a seek generation starts
+#[derive(Clone, Copy)]
+enum AudioStart {
+    /// At the frame the video landed on, which the clock is set to. Audio
+    /// beginning later is preceded by silence.
+    Frame(f64),
+    /// At this time, or where the audio begins if later. The clock is set to
+    /// the first sample, as there is no frame to follow.
+    From(f64),
+}
+
+impl AudioStart {
+    fn pts(self) -> f64 {
+        match self {
+            Self::Frame(pts) | Self::From(pts) => pts,
+        }
+    }
+}
+
+/// Seek state shared by the demuxer and its decode workers
+pub struct DecodeSync {
+    /// Generation of the latest seek; packets and frames from older ones are dropped
+    generation: AtomicU64,
+    /// Generation and earliest PTS of the frame that ends a paused seek preview
+    preview: Mutex<Option<(u64, f64)>>,
+    /// Generation and PTS its audio starts at, once known. After a seek that is the
+    /// frame the video lands on, so the clock, picture and sound agree.
+    audio_start: Mutex<(u64, Option<AudioStart>)>,
+    /// PTS of the latest decoded video frame in seconds, as f64 bits
+    last_pts: AtomicU64,
+    /// Conversion matrix choice, read by the video worker for every frame
+    color_management: Mutex<ColorManagement>,
+    /// Audio pitch shift in semitones as f32 bits, read by the audio worker for every frame
+    pitch: AtomicU32,
+    /// Raised when the demuxer stops, so workers give up frames nobody will take
+    finished: AtomicBool,
+    /// Latest GoPro GPMF packets by start PTS, attached by the video worker to
+    /// the frames they cover
+    gpmf: Mutex<VecDeque<(f64, Arc<[u8]>)>>,
+}
+
+impl DecodeSync {
+    pub fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            preview: Mutex::new(None),
+            audio_start: Mutex::new((0, Some(AudioStart::From(f64::NEG_INFINITY)))),
+            last_pts: AtomicU64::new(0.0f64.to_bits()),
+            color_management: Mutex::new(ColorManagement::Auto),
+            pitch: AtomicU32::new(0.0f32.to_bits()),
+            finished: AtomicBool::new(false),
+            gpmf: Mutex::default(),
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Start a seek generation. While paused, decoding continues until a frame
+    /// at or after `preview` has been sent; its audio starts at that frame.
+    /// Without a preview, audio starts at `audio_start` (None: wherever the seek landed).
+    pub fn seek(&self, generation: u64, preview: Option<f64>, audio_start: Option<f64>) {
+        *self.preview.lock() = preview.map(|earliest| (generation, earliest));
+        let start = match preview {
+            Some(_) => None,
+            None => Some(AudioStart::From(audio_start.unwrap_or(f64::NEG_INFINITY))),
+        };
+        *self.audio_start.lock() = (generation, start);
+        self.gpmf.lock().clear();
+        self.generation.store(generation, Ordering::Release);
+    }
+
+    /// Stop waiting for a preview frame, e.g. because video was disabled
+    pub fn cancel_preview(&self) {
+        *self.preview.lock() = None;
+        let mut audio_start = self.audio_start.lock();
+        if audio_start.1.is_none() {
+            audio_start.1 = Some(AudioStart::From(f64::NEG_INFINITY));
+        }
+    }
+
+    pub fn preview_pending(&self) -> bool {
+        self.preview.lock().is_some()
+    }
+
+    fn frame_sent(&self, generation: u64, pts: f64) {
+        let mut preview = self.preview.lock();
+        if preview.is_some_and(|(pending, earliest)| pending == generation && pts >= earliest) {
+            *preview = None;
+            let mut audio_start = self.audio_start.lock();
+            if audio_start.0 == generation {
+                audio_start.1 = Some(AudioStart::Frame(pts));
+            }
+        }
+    }
+
+    /// PTS the audio of `generation` starts at, if known yet
+    fn audio_start(&self, generation: u64) -> Option<AudioStart> {
+        match *self.audio_start.lock() {
+            (started, start) if started == generation => start,
+            _ => Some(AudioStart::From(f64::NEG_INFINITY)),
+        }
+    }
+
+    /// Resume point after a reconnect, and the timestamp of KLV packets without one
+    pub fn last_pts(&self) -> f64 {
+        f64::from_bits(self.last_pts.load(Ordering::Relaxed))
+    }
+
+    pub fn set_last_pts(&self, pts: f64) {
+        self.last_pts.store(pts.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Keep a GPMF packet read by the demuxer, which runs ahead of the video worker
+    pub fn push_gpmf(&self, pts: f64, data: &[u8]) {
+        let mut gpmf = self.gpmf.lock();
+        if gpmf.back().is_some_and(|&(last, _)| last > pts) {
+            gpmf.clear(); // Looped or reconnected
+        }
+        if gpmf.len() == GPMF_PACKETS {
+            gpmf.pop_front();
+        }
+        gpmf.push_back((pts, data.into()));
+    }
+
+    /// GPMF packet covering a frame: the latest one starting at or before it
+    fn gpmf_at(&self, pts: f64) -> Option<Arc<[u8]>> {
+        self.gpmf
+            .lock()
+            .iter()
+            .rev()
+            .find(|&&(start, _)| start <= pts)
+            .map(|(_, data)| data.clone())
+    }
+
+    pub fn set_color_management(&self, mode: ColorManagement) {
+        *self.color_management.lock() = mode;
+    }
+
+    pub fn set_pitch(&self, semitones: f32) {
+        self.pitch.store(semitones.to_bits(), Ordering::Relaxed);
+    }
+
+    fn pitch(&self) -> f32 {
+        f32::from_bits(self.pitch.load(Ordering::Relaxed))
+    }
+
+    /// Mark the start (false) or end (true) of a media's demuxing
+    pub fn set_finished(&self, finished: bool) {
+        self.finished.store(finished, Ordering::Relaxed);
+    }
+
+    /// Whether a frame of `generation` is still wanted
+    fn is_current(&self, generation: u64, stop_flag: &AtomicBool) -> bool {
+        generation >= self.generation()
+            && !self.finished.load(Ordering::Relaxed)
+            && !stop_flag.load(Ordering::Relaxed)
+    }
+}
+
+/// Video decoding, filtering and RGBA conversion on a thread of its own
+pub struct VideoWorker {
+    pub decoder: decoder::Video,
+    pub time_base: Rational,
+    /// Clockwise rotation that turns frames upright
+    pub rotation: u32,
+    pub decoder_threads: usize,
+    pub filter: Option<String>,
+    pub frame_budget: FrameBudget,
+}
+
+impl VideoWorker {
+    /// Decode packets until the demuxer drops its sender
+    pub fn run(
+        mut self,
+        packets: Receiver<WorkerMessage>,
+        frames: &Sender<DecodedVideoFrame>,
+        video_effects: &SharedVideoEffects,
+        sync: &DecodeSync,
+        stop_flag: &AtomicBool,
+        event_sender: &Sender<PlayerEvent>,
+    ) -> Result<()> {
+        // Filter and scaler contexts cannot move between threads, so they are made here
+        let mut filter = self.filter()?;
+        let mut frame_time_base = filter
+            .as_mut()
+            .map_or(self.time_base, |filter| filter.time_base());
+
+        // Create scaler for video (to RGBA)
+        let mut scaler_format = (
+            self.decoder.format(),
+            self.decoder.width(),
+            self.decoder.height(),
+        );
+        let mut scaler = ScalerContext::get(
+            self.decoder.format(),
+            self.decoder.width(),
+            self.decoder.height(),
+            Pixel::RGBA,
+            self.decoder.width(),
+            self.decoder.height(),
+            Flags::BILINEAR,
+        )?;
+
+        // Colorspace currently configured on the scaler
+        let mut applied_colorspace = None;
+
+        let mut video_frame = VideoFrame::empty();
+        let mut rgba_frame = VideoFrame::empty();
+        let mut filtered_video = VideoFrame::empty();
+        let mut generation = sync.generation();
+
+        for message in packets {
+            let (packet_generation, packet) = match message {
+                WorkerMessage::Packet(packet_generation, packet) => (packet_generation, packet),
+                WorkerMessage::Flush => {
+                    self.decoder.flush();
+                    continue;
+                }
+            };
+            // Read before a seek that has since been requested, or after a stop
+            if !sync.is_current(packet_generation, stop_flag) {
+                continue;
+            }
+            if packet_generation != generation {
+                self.decoder.flush();
+                generation = packet_generation;
+                // A fresh graph, so stateful filters (fps, minterpolate, tblend)
+                // neither emit frames from before the seek nor mistime the new ones
+                if filter.is_some() {
+                    filter = self.filter()?;
+                    frame_time_base = filter
+                        .as_mut()
+                        .map_or(self.time_base, |filter| filter.time_base());
+                }
+            }
+
+            if let Err(e) = self.decoder.send_packet(&packet) {
+                tracing::warn!(error = %e, "skipped corrupt video packet");
+                let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                    "Skipped corrupt video packet: {}",
+                    e
+                )));
+            }
+
+            while self.decoder.receive_frame(&mut video_frame).is_ok() {
+                if !sync.is_current(generation, stop_flag) {
+                    continue; // Drain without converting
+                }
+                // Run the optional filtergraph; it may emit zero or several frames
+                if let Some(ref mut filter) = filter {
+                    let timestamp = video_frame.timestamp();
+                    video_frame.set_pts(timestamp);
+                    filter.push(&video_frame)?;
+                }
+                let mut unfiltered = Some(&video_frame);
+                loop {
+                    let frame = match filter {
+                        Some(ref mut filter) => {
+                            if !filter.pull(&mut filtered_video) {
+                                break;
+                            }
+                            &filtered_video
+                        }
+                        None => match unfiltered.take() {
+                            Some(frame) => frame,
+                            None => break,
+                        },
+                    };
+
+                    // Recreate the scaler if a filter changed the frame size or format
+                    let scaler_input = (frame.format(), frame.width(), frame.height());
+                    if scaler_input != scaler_format {
+                        scaler = ScalerContext::get(
+                            frame.format(),
+                            frame.width(),
+                            frame.height(),
+                            Pixel::RGBA,
+                            frame.width(),
+                            frame.height(),
+                            Flags::BILINEAR,
+                        )?;
+                        scaler_format = scaler_input;
+                        applied_colorspace = None;
+                    }
+
+                    // Match the conversion matrix to the frame's colorspace
+                    let colorspace = sync.color_management.lock().resolve(
+                        frame.color_space(),
+                        frame.color_range(),
+                        frame.height(),
+                    );
+                    if applied_colorspace != Some(colorspace) {
+                        apply_colorspace(&mut scaler, colorspace.0, colorspace.1);
+                        applied_colorspace = Some(colorspace);
+                    }
+
+                    // Scale to RGBA
+                    scaler.run(frame, &mut rgba_frame)?;
+
+                    // Calculate PTS in seconds
+                    let pts = frame.pts().unwrap_or(0);
+                    let pts_seconds = pts as f64 * f64::from(frame_time_base);
+                    sync.set_last_pts(pts_seconds);
+
+                    let mut decoded = DecodedVideoFrame::new(
+                        &rgba_frame,
+                        has_alpha(frame.format()),
+                        self.rotation,
+                        pts_seconds,
+                        self.decoder_threads,
+                        generation,
+                    );
+                    decoded.side_data = read_frame_metadata(frame);
+                    decoded.side_data.gpmf = sync.gpmf_at(pts_seconds);
+                    apply_effects(video_effects, &mut decoded);
+                    if !deliver(
+                        frames,
+                        decoded,
+                        &self.frame_budget,
+                        generation,
+                        sync,
+                        stop_flag,
+                    ) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Filtergraph for the custom video filter, if any
+    fn filter(&self) -> Result<Option<FrameFilter>> {
+        self.filter
+            .as_deref()
+            .map(|spec| FrameFilter::video(spec, &self.decoder, self.time_base))
+            .transpose()
+    }
+}
+
+/// Send a frame, waiting while the channel or the memory budget is full.
+/// Frames overtaken by a seek are dropped. Returns false once the player is gone.
+fn deliver(
+    frames: &Sender<DecodedVideoFrame>,
+    mut frame: DecodedVideoFrame,
+    frame_budget: &FrameBudget,
+    generation: u64,
+    sync: &DecodeSync,
+    stop_flag: &AtomicBool,
+) -> bool {
+    let pts = frame.pts;
+    let bytes = std::mem::size_of_val(frame.pixels.as_slice()) as u64;
+    frame.lease = frame_budget.acquire(bytes, || sync.is_current(generation, stop_flag));
+    if frame.lease.is_none() {
+        return true;
+    }
+    while sync.is_current(generation, stop_flag) {
+        match frames.send_timeout(frame, SEND_POLL) {
+            Ok(()) => {
+                sync.frame_sent(generation, pts);
+                break;
+            }
+            Err(SendTimeoutError::Timeout(f)) => frame = f,
+            Err(SendTimeoutError::Disconnected(_)) => return false,
+        }
+    }
+    true
+}
+
+/// Audio decoding, filtering and resampling on a thread of its own
+pub struct AudioWorker {
+    pub decoder: decoder::Audio,
+    pub time_base: Rational,
+    pub filter: Option<String>,
+}
+
+impl AudioWorker {
+    /// Decode packets into the audio queue until the demuxer drops its sender
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        mut self,
+        packets: Receiver<WorkerMessage>,
+        audio_producer: &mut AudioProducer,
+        clock: &AudioClock,
+        audio_tap: &SharedAudioTap,
+        sync: &DecodeSync,
+        stop_flag: &AtomicBool,
+        event_sender: &Sender<PlayerEvent>,
+    ) -> Result<()> {
+        let mut pitch = sync.pitch();
+        let mut filter = self.filter(pitch)?;
+
+        // Create resampler for audio (to f32 stereo)
+        let mut resampler = ResamplerContext::get(
+            self.decoder.format(),
+            channel_layout(&self.decoder),
+            self.decoder.rate(),
+            Sample::F32(ffmpeg_next::util::format::sample::Type::Packed),
+            ChannelLayout::STEREO,
+            clock.sample_rate(),
+        )?;
+
+        let mut frame_time_base = filter
+            .as_mut()
+            .map_or(self.time_base, |filter| filter.time_base());
+        let sample_rate = f64::from(clock.sample_rate());
+
+        let mut audio_frame = AudioFrame::empty();
+        let mut filtered_audio = AudioFrame::empty();
+        let mut generation = sync.generation();
+        // Audio decoded after a seek, held until the frame it starts at is known
+        let mut held: Vec<f32> = Vec::new();
+        let mut held_pts = 0.0;
+        // Whether the clock or queue has been lined up with this generation's first audio
+        let mut primed = false;
+
+        for message in packets {
+            let (packet_generation, packet) = match message {
+                WorkerMessage::Packet(packet_generation, packet) => (packet_generation, packet),
+                WorkerMessage::Flush => {
+                    self.decoder.flush();
+                    continue;
+                }
+            };
+            if !sync.is_current(packet_generation, stop_flag) {
+                continue;
+            }
+            if packet_generation != generation {
+                self.decoder.flush();
+                generation = packet_generation;
+                audio_producer.discard_queued();
+                held.clear();
+                primed = false;
+                // Drop samples and timestamps buffered from before the seek (atempo, pitch)
+                if filter.is_some() {
+                    filter = self.filter(pitch)?;
+                    frame_time_base = filter
+                        .as_mut()
+                        .map_or(self.time_base, |filter| filter.time_base());
+                }
+            }
+
+            if let Err(e) = self.decoder.send_packet(&packet) {
+                tracing::warn!(error = %e, "skipped corrupt audio packet");
+                let _ = event_sender.try_send(PlayerEvent::Warning(format!(
+                    "Skipped corrupt audio packet: {}",
+                    e
+                )));
+            }
+
+            while self.decoder.receive_frame(&mut audio_frame).is_ok() {
+                if !sync.is_current(generation, stop_flag) {
+                    continue;
+                }
+                if sync.pitch() != pitch {
+                    // Rebuilding drops the few samples buffered in the old filtergraph
+                    pitch = sync.pitch();
+                    filter = self.filter(pitch)?;
+                    frame_time_base = filter
+                        .as_mut()
+                        .map_or(self.time_base, |filter| filter.time_base());
+                }
+                if let Some(ref mut filter) = filter {
+                    let timestamp = audio_frame.timestamp();
+                    audio_frame.set_pts(timestamp);
+                    filter.push(&audio_frame)?;
+                }
+                let mut unfiltered = Some(&audio_frame);
+                loop {
+                    let frame = match filter {
+                        Some(ref mut filter) => {
+                            if !filter.pull(&mut filtered_audio) {
+                                break;
+                            }
+                            &filtered_audio
+                        }
+                        None => match unfiltered.take() {
+                            Some(frame) => frame,
+                            None => break,
+                        },
+                    };
+
+                    let pts = frame
+                        .pts()
+                        .or_else(|| frame.timestamp())
+                        .map(|pts| pts as f64 * f64::from(frame_time_base));
+
+                    // Resample to f32 stereo
+                    let mut resampled = AudioFrame::empty();
+                    if resampler.run(frame, &mut resampled).is_ok() {
+                        // Get samples as f32
+                        let data = resampled.data_mut(0);
+                        let samples: &mut [f32] = unsafe {
+                            std::slice::from_raw_parts_mut(
+                                data.as_mut_ptr() as *mut f32,
+                                data.len() / 4,
+                            )
+                        };
+
+                        let Some(start) = sync.audio_start(generation) else {
+                            // Hold the audio while the seek's frame is being decoded
+                            if held.is_empty() {
+                                held_pts = pts.unwrap_or(0.0);
+                            }
+                            held.extend_from_slice(samples);
+                            let excess = held
+                                .len()
+                                .saturating_sub((MAX_HELD_AUDIO * sample_rate) as usize * 2);
+                            held.drain(..excess);
+                            held_pts += (excess / 2) as f64 / sample_rate;
+                            continue;
+                        };
+                        if !primed {
+                            primed = true;
+                            let first = if held.is_empty() { pts } else { Some(held_pts) };
+                            if let Some(first) = first {
+                                prime(start, first, sample_rate, audio_producer, clock, || {
+                                    sync.is_current(generation, stop_flag)
+                                });
+                            }
+                        }
+                        let start = start.pts();
+                        if !held.is_empty() {
+                            let skip = start_offset(held_pts, start, sample_rate, held.len());
+                            let mut released = std::mem::take(&mut held);
+                            queue_audio(
+                                &mut released[skip..],
+                                audio_producer,
+                                clock,
+                                audio_tap,
+                                || sync.is_current(generation, stop_flag),
+                            );
+                        }
+                        let skip = pts.map_or(0, |pts| {
+                            start_offset(pts, start, sample_rate, samples.len())
+                        });
+                        queue_audio(
+                            &mut samples[skip..],
+                            audio_producer,
+                            clock,
+                            audio_tap,
+                            || sync.is_current(generation, stop_flag),
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AudioWorker {
+    /// Filtergraph for the pitch shift and the custom audio filter, if any.
+    /// Its output is converted back to the decoder's format so the resampler is unchanged.
+    fn filter(&self, pitch: f32) -> Result<Option<FrameFilter>> {
+        let spec: Vec<String> = pitch_filter(self.decoder.rate(), pitch)
+            .into_iter()
+            .chain(self.filter.clone())
+            .collect();
+        if spec.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(FrameFilter::audio(
+            &spec.join(","),
+            &self.decoder,
+            self.time_base,
+            |sink| {
+                sink.set_sample_format(self.decoder.format());
+                sink.set_channel_layout(channel_layout(&self.decoder));
+                sink.set_sample_rate(self.decoder.rate());
+            },
+        )?))
+    }
+}
+
+/// Number of interleaved stereo samples before `start` in a block starting at `pts`
+fn start_offset(pts: f64, start: f64, sample_rate: f64, len: usize) -> usize {
+    let frames = ((start - pts) * sample_rate).max(0.0) as usize;
+    (frames * 2).min(len)
+}
+
+/// Line playback up with audio that does not begin where it should, as in
+/// cut clips and TS captures not starting at zero: fill the gap before
+/// audio starting after its frame with silence, or move the clock to the
+/// first sample when no frame sets it.
+fn prime(
+    start: AudioStart,
+    first_pts: f64,
+    sample_rate: f64,
+    audio_producer: &mut AudioProducer,
+    clock: &AudioClock,
+    wanted: impl Fn() -> bool,
+) {
+    tracing::debug!(first_pts, start = start.pts(), "first audio of seek");
+    match start {
+        AudioStart::Frame(pts) if first_pts > pts => {
+            let gap = (first_pts - pts).min(MAX_LEAD_SILENCE);
+            let silence = vec![0.0; (gap * sample_rate) as usize * 2];
+            audio_producer.push(&silence, wanted);
+        }
+        AudioStart::Frame(_) => {}
+        AudioStart::From(pts) => clock.sync_position(first_pts.max(pts)),
+    }
+}
+
+/// Hand decoded samples to the tap and the output queue
+fn queue_audio(
+    samples: &mut [f32],
+    audio_producer: &mut AudioProducer,
+    clock: &AudioClock,
+    audio_tap: &SharedAudioTap,
+    wanted: impl Fn() -> bool,
+) {
+    // Let the application inspect or modify the audio
+    if let Some(tap) = audio_tap.lock().as_mut() {
+        tap.process(samples, clock.sample_rate(), 2);
+    }
+
+    // Waits while the queue is above its high watermark, until a seek or stop
+    audio_producer.push(samples, wanted);
+    tracing::trace!(queued = audio_producer.queued(), "audio queue depth");
+}
@@ -0,0 +1,50 @@
+//! ASS/SSA subtitles drawn over the video as rendered by libass.
+//!
+//! Requires the `libass` feature.
+
+use crate::player::VideoPlayer;
+use egui::{pos2, Color32, ColorImage, Rect, TextureHandle, TextureOptions, Ui};
+use std::sync::Arc;
+
+pub struct AssOverlay;
+
+impl AssOverlay {
+    /// Draw the player's ASS/SSA subtitles over the video rect, rendered at its
+    /// size in physical pixels so text stays sharp. Returns false if none are
+    /// loaded, so plain subtitles can be drawn with `SubtitleOverlay` instead.
+    pub fn show(ui: &Ui, video_rect: Rect, player: &VideoPlayer) -> bool {
+        if !player.has_ass_subtitles() {
+            return false;
+        }
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let size = [
+            (video_rect.width() * pixels_per_point).round() as usize,
+            (video_rect.height() * pixels_per_point).round() as usize,
+        ];
+        let Some(image) = player.ass_subtitle_image(size) else {
+            return true;
+        };
+
+        // Upload only when libass drew something new
+        let id = ui.id().with("ass_overlay");
+        let cached: Option<(Arc<ColorImage>, TextureHandle)> = ui.data(|data| data.get_temp(id));
+        let texture = match cached {
+            Some((shown, texture)) if Arc::ptr_eq(&shown, &image) => texture,
+            Some((_, mut texture)) => {
+                texture.set(image.as_ref().clone(), TextureOptions::LINEAR);
+                texture
+            }
+            None => ui.ctx().load_texture(
+                "ass_subtitles",
+                image.as_ref().clone(),
+                TextureOptions::LINEAR,
+            ),
+        };
+        ui.data_mut(|data| data.insert_temp(id, (image, texture.clone())));
+
+        let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+        ui.painter_at(video_rect)
+            .image(texture.id(), video_rect, uv, Color32::WHITE);
+        true
+    }
+}
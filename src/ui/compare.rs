@@ -0,0 +1,110 @@
+use crate::player::{TimeDisplayMode, VideoPlayer};
+use crate::ui::controls::format_time;
+use egui::{
+    pos2, Color32, CursorIcon, FontId, Painter, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2,
+};
+use std::time::Duration;
+
+/// How `CompareView` lays out the reference and live frames
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Reference on the left, live frame on the right
+    #[default]
+    SideBySide,
+    /// Reference left of a draggable divider, live frame right of it
+    Wipe,
+}
+
+pub struct CompareView;
+
+impl CompareView {
+    /// Show the reference frame frozen with `VideoPlayer::freeze_reference`
+    /// against the live frame. In wipe mode the reference is stretched over the
+    /// live frame so different resolutions line up, and dragging or clicking
+    /// moves the divider; `split` holds its position, 0.0 to 1.0 from the left.
+    /// Returns None until both frames exist.
+    pub fn show(
+        ui: &mut Ui,
+        player: &VideoPlayer,
+        mode: CompareMode,
+        split: &mut f32,
+    ) -> Option<Response> {
+        let live = player.texture_id()?;
+        let reference = player.reference_texture()?;
+        let (width, height) = player.video_size();
+        let live_size = Vec2::new(width.max(1) as f32, height.max(1) as f32);
+        let [width, height] = reference.size();
+        let reference_size = Vec2::new(width.max(1) as f32, height.max(1) as f32);
+
+        let (area, response) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        let painter = ui.painter_at(area);
+        let uv = Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0));
+        let reference_label = label("Reference", player.reference_position()?);
+        let live_label = label("Live", player.position());
+
+        match mode {
+            CompareMode::SideBySide => {
+                let (left, right) = area.split_left_right_at_fraction(0.5);
+                let reference_rect = fit(left, reference_size);
+                let live_rect = fit(right, live_size);
+                painter.image(reference.id(), reference_rect, uv, Color32::WHITE);
+                painter.image(live, live_rect, uv, Color32::WHITE);
+                draw_label(&painter, reference_rect.left_top(), reference_label, false);
+                draw_label(&painter, live_rect.right_top(), live_label, true);
+            }
+            CompareMode::Wipe => {
+                let rect = fit(area, live_size);
+                if response.dragged() || response.clicked() {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        *split = ((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+                    }
+                }
+                if response.hovered() {
+                    ui.ctx().set_cursor_icon(CursorIcon::ResizeHorizontal);
+                }
+
+                let x = rect.left() + rect.width() * split.clamp(0.0, 1.0);
+                painter.image(live, rect, uv, Color32::WHITE);
+                painter
+                    .with_clip_rect(Rect::from_min_max(rect.min, pos2(x, rect.bottom())))
+                    .image(reference.id(), rect, uv, Color32::WHITE);
+                painter.vline(x, rect.y_range(), Stroke::new(2.0, Color32::WHITE));
+                painter.circle(
+                    pos2(x, rect.center().y),
+                    8.0,
+                    Color32::from_black_alpha(160),
+                    Stroke::new(2.0, Color32::WHITE),
+                );
+                draw_label(&painter, rect.left_top(), reference_label, false);
+                draw_label(&painter, rect.right_top(), live_label, true);
+            }
+        }
+
+        Some(response)
+    }
+}
+
+/// Largest rect of the given aspect ratio centered in `area`
+fn fit(area: Rect, size: Vec2) -> Rect {
+    let scale = (area.width() / size.x).min(area.height() / size.y);
+    Rect::from_center_size(area.center(), size * scale)
+}
+
+fn label(name: &str, position: Duration) -> String {
+    format!(
+        "{} {}",
+        name,
+        format_time(position, TimeDisplayMode::WithMillis, 0.0)
+    )
+}
+
+/// Text on a dark box inside the corner at `corner`, to its left if `right`
+fn draw_label(painter: &Painter, corner: Pos2, text: String, right: bool) {
+    let galley = painter.layout_no_wrap(text, FontId::monospace(14.0), Color32::WHITE);
+    let padding = Vec2::new(6.0, 4.0);
+    let size = galley.size() + padding * 2.0;
+    let x = if right { -8.0 - size.x } else { 8.0 };
+    let background = Rect::from_min_size(corner + Vec2::new(x, 8.0), size);
+    painter.rect_filled(background, 4.0, Color32::from_black_alpha(160));
+    painter.galley(background.min + padding, galley, Color32::WHITE);
+}
@@ -1,108 +1,742 @@
-use crate::player::{DisplayMode, PlayerState, VideoPlayer, Volume};
-use egui::{Slider, Ui};
+use crate::player::{DisplayMode, PlayerState, SeekMode, TimeDisplayMode, VideoPlayer, Volume};
+use crate::ui::level_meter::LevelMeter;
+use crate::ui::media_info::{media_badge, MediaInfoWindow};
+use crate::ui::seek_slider::SeekSlider;
+use crate::ui::waveform::WaveformBar;
+use egui::{
+    popup_above_or_below_widget, AboveOrBelow, Button, Color32, Frame, Image, Label, Margin,
+    PopupCloseBehavior, Pos2, Rect, Response, RichText, Sense, Shape, Slider, Ui, UiBuilder, Vec2,
+    WidgetInfo, WidgetType,
+};
 use std::time::Duration;
 
+/// Seconds the pointer may rest on the video before on-video controls fade out
+const OVERLAY_HIDE_DELAY: f32 = 2.0;
+/// Gap between on-video controls and the edges of the video
+const OVERLAY_MARGIN: f32 = 8.0;
+/// Seconds a rewind or fast-forward button is held before it starts repeating
+const HOLD_DELAY: f64 = 0.4;
+/// Seconds between repeated jumps while held
+const HOLD_INTERVAL: f64 = 0.2;
+/// Repeats after which a held jump doubles, up to the large increment
+const HOLD_ACCELERATION: u32 = 4;
+
+/// Arrangement of the control bar
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlsLayout {
+    /// Every control at normal size
+    #[default]
+    Full,
+    /// One dense row with small buttons and a combined "elapsed / duration" label
+    Compact,
+    /// Play/pause, a seek bar stretched over the available width and the time,
+    /// on a translucent strip for drawing over the video
+    Overlay,
+}
+
+/// Symbols drawn on the control buttons
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlIcons {
+    pub play: String,
+    pub pause: String,
+    pub stop: String,
+    pub rewind: String,
+    pub fast_forward: String,
+    pub previous_scene: String,
+    pub next_scene: String,
+    pub volume: String,
+    pub fit_to_window: String,
+    pub native_size: String,
+    pub physical_size: String,
+    pub integer_scale: String,
+}
+
+impl Default for ControlIcons {
+    fn default() -> Self {
+        Self {
+            play: "▶".to_owned(),
+            pause: "⏸".to_owned(),
+            stop: "⏹".to_owned(),
+            rewind: "⏪".to_owned(),
+            fast_forward: "⏩".to_owned(),
+            previous_scene: "⏮".to_owned(),
+            next_scene: "⏭".to_owned(),
+            volume: "🔊".to_owned(),
+            fit_to_window: "⛶".to_owned(),
+            native_size: "⊞".to_owned(),
+            physical_size: "⊟".to_owned(),
+            integer_scale: "⊡".to_owned(),
+        }
+    }
+}
+
+impl ControlIcons {
+    /// Plain text labels, for fonts without the media symbols
+    #[must_use]
+    pub fn text() -> Self {
+        Self {
+            play: "Play".to_owned(),
+            pause: "Pause".to_owned(),
+            stop: "Stop".to_owned(),
+            rewind: "-".to_owned(),
+            fast_forward: "+".to_owned(),
+            previous_scene: "<<".to_owned(),
+            next_scene: ">>".to_owned(),
+            volume: "Vol".to_owned(),
+            fit_to_window: "Fit".to_owned(),
+            native_size: "1:1".to_owned(),
+            physical_size: "1:1 px".to_owned(),
+            integer_scale: "Int".to_owned(),
+        }
+    }
+}
+
+/// Look of `PlayerControls`, so embedders can match their app's theme.
+/// Colors left as None follow the egui visuals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlsStyle {
+    pub layout: ControlsLayout,
+    pub icons: ControlIcons,
+    pub icon_color: Option<Color32>,
+    /// Played part of the seek bar
+    pub accent_color: Option<Color32>,
+    /// Fill behind the bar
+    pub background: Option<Color32>,
+    /// Horizontal space between controls
+    pub spacing: f32,
+    /// Thickness of the seek and volume slider rails
+    pub slider_height: f32,
+    pub show_stop: bool,
+    /// Rewind and fast-forward buttons, jumping by `VideoPlayer::seek_increments`
+    pub show_jump_buttons: bool,
+    /// Previous/next scene buttons, shown once scene detection found cuts
+    pub show_scene_buttons: bool,
+    pub show_time: bool,
+    pub show_volume: bool,
+    /// Level meter beside the volume slider, once `VideoPlayer::set_level_meter` is on
+    pub show_level_meter: bool,
+    pub show_display_mode: bool,
+    /// "1080p • H.264 • AAC 5.1" summary; click it for the full media info
+    pub show_media_badge: bool,
+}
+
+impl Default for ControlsStyle {
+    fn default() -> Self {
+        Self {
+            layout: ControlsLayout::Full,
+            icons: ControlIcons::default(),
+            icon_color: None,
+            accent_color: None,
+            background: None,
+            spacing: 8.0,
+            slider_height: 8.0,
+            show_stop: true,
+            show_jump_buttons: true,
+            show_scene_buttons: true,
+            show_time: true,
+            show_volume: true,
+            show_level_meter: true,
+            show_display_mode: true,
+            show_media_badge: true,
+        }
+    }
+}
+
+impl ControlsStyle {
+    /// Single dense row for small windows
+    #[must_use]
+    pub fn compact() -> Self {
+        Self {
+            layout: ControlsLayout::Compact,
+            spacing: 4.0,
+            slider_height: 4.0,
+            show_scene_buttons: false,
+            show_display_mode: false,
+            show_media_badge: false,
+            ..Default::default()
+        }
+    }
+
+    /// Minimal bar to draw over the bottom of the video
+    #[must_use]
+    pub fn overlay() -> Self {
+        Self {
+            layout: ControlsLayout::Overlay,
+            icon_color: Some(Color32::WHITE),
+            background: Some(Color32::from_black_alpha(160)),
+            spacing: 6.0,
+            slider_height: 4.0,
+            show_stop: false,
+            show_jump_buttons: false,
+            show_scene_buttons: false,
+            show_volume: false,
+            show_display_mode: false,
+            show_media_badge: false,
+            ..Default::default()
+        }
+    }
+}
+
 pub struct PlayerControls;
 
 impl PlayerControls {
+    /// Show the control bar. Every control is reachable with Tab and activated with
+    /// Space or Enter, and carries a name for screen readers (via AccessKit).
     pub fn show(ui: &mut Ui, player: &mut VideoPlayer) {
-        ui.horizontal(|ui| {
-            // Play/Pause button
-            let play_pause_text = match player.state() {
-                PlayerState::Playing => "⏸",
-                _ => "▶",
-            };
+        Self::show_with_style(ui, player, &ControlsStyle::default());
+    }
 
-            if ui.button(play_pause_text).clicked() {
-                if player.is_playing() {
-                    player.pause();
-                } else {
-                    player.play();
+    /// Show the control bar with custom colors, icons, spacing and layout
+    pub fn show_with_style(ui: &mut Ui, player: &mut VideoPlayer, style: &ControlsStyle) {
+        let frame = match style.background {
+            Some(fill) => Frame::none()
+                .fill(fill)
+                .rounding(4.0)
+                .inner_margin(Margin::symmetric(style.spacing, 4.0)),
+            None => Frame::none(),
+        };
+        frame.show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = style.spacing;
+                ui.spacing_mut().slider_rail_height = style.slider_height;
+                if let Some(accent) = style.accent_color {
+                    ui.visuals_mut().selection.bg_fill = accent;
                 }
-            }
+                Self::show_row(ui, player, style);
+            });
+        });
+    }
+
+    /// Draw translucent controls over the bottom of `rect`, usually the video's,
+    /// as in web and mpv players. They fade in while the pointer moves over
+    /// the rect and stay up while paused.
+    pub fn show_overlay(ui: &mut Ui, player: &mut VideoPlayer, rect: Rect) {
+        Self::show_overlay_with_style(ui, player, rect, &ControlsStyle::overlay());
+    }
+
+    /// Draw on-video controls with a custom style, see `show_overlay()`
+    pub fn show_overlay_with_style(
+        ui: &mut Ui,
+        player: &mut VideoPlayer,
+        rect: Rect,
+        style: &ControlsStyle,
+    ) {
+        let pointer_active = ui.rect_contains_pointer(rect)
+            && ui.input(|i| i.pointer.time_since_last_movement()) < OVERLAY_HIDE_DELAY;
+        let opacity = ui.ctx().animate_bool(
+            ui.id().with("overlay_controls"),
+            pointer_active || !player.is_playing(),
+        );
+        if opacity == 0.0 {
+            return;
+        }
+
+        let height = ui.spacing().interact_size.y + 8.0;
+        let bar = Rect::from_min_max(
+            Pos2::new(
+                rect.left() + OVERLAY_MARGIN,
+                rect.bottom() - OVERLAY_MARGIN - height,
+            ),
+            Pos2::new(
+                rect.right() - OVERLAY_MARGIN,
+                rect.bottom() - OVERLAY_MARGIN,
+            ),
+        );
+        ui.allocate_new_ui(UiBuilder::new().max_rect(bar), |ui| {
+            ui.set_opacity(opacity);
+            Self::show_with_style(ui, player, style);
+        });
+    }
 
-            // Stop button
-            if ui.button("⏹").clicked() {
-                player.stop();
+    /// Jump by the small increment on click, the large one with Shift held.
+    /// Holding the button repeats the jump, doubling it every few repeats.
+    fn jump_button(
+        ui: &mut Ui,
+        player: &mut VideoPlayer,
+        style: &ControlsStyle,
+        icon: &str,
+        name: &str,
+        direction: f64,
+    ) {
+        let (small, large) = player.seek_increments();
+        let response = accessible_button(ui, style, icon, name).on_hover_text(format!(
+            "{} {} s (Shift: {} s), hold to repeat",
+            name,
+            small.as_secs_f64(),
+            large.as_secs_f64()
+        ));
+
+        // Press time and number of repeats so far, while held
+        let hold_id = response.id.with("hold");
+        let held = ui.data(|d| d.get_temp::<(f64, u32)>(hold_id));
+        if response.is_pointer_button_down_on() {
+            let now = ui.input(|i| i.time);
+            let (pressed_at, mut repeats) = held.unwrap_or((now, 0));
+            if now - pressed_at >= HOLD_DELAY + f64::from(repeats) * HOLD_INTERVAL {
+                let step = small
+                    .mul_f64(f64::from(1 << (repeats / HOLD_ACCELERATION).min(16)))
+                    .min(large);
+                player.seek_relative(direction * step.as_secs_f64());
+                repeats += 1;
             }
+            ui.data_mut(|d| d.insert_temp(hold_id, (pressed_at, repeats)));
+            ui.ctx().request_repaint();
+        } else if held.is_some() {
+            ui.data_mut(|d| d.remove::<(f64, u32)>(hold_id));
+        }
 
-            ui.separator();
+        // A click after repeating ends the hold instead of jumping again
+        if response.clicked() && !matches!(held, Some((_, repeats)) if repeats > 0) {
+            let step = if ui.input(|i| i.modifiers.shift) {
+                large
+            } else {
+                small
+            };
+            player.seek_relative(direction * step.as_secs_f64());
+        }
+    }
 
-            // Timeline / seek bar
-            let duration_secs = player.duration().as_secs_f64();
-            let player_position_secs = player.position().as_secs_f64();
+    /// Balance slider shown above the volume icon when it is clicked
+    fn audio_popup(ui: &mut Ui, player: &mut VideoPlayer, icon: &Response) {
+        let popup_id = icon.id.with("audio_popup");
+        if icon.clicked() {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        }
+        popup_above_or_below_widget(
+            ui,
+            popup_id,
+            icon,
+            AboveOrBelow::Above,
+            PopupCloseBehavior::CloseOnClickOutside,
+            |ui| {
+                ui.set_min_width(160.0);
+                ui.horizontal(|ui| {
+                    let balance_label = ui.label("Balance");
+                    let mut balance = player.balance();
+                    ui.label("L");
+                    let response = ui
+                        .add(Slider::new(&mut balance, -1.0..=1.0).show_value(false))
+                        .labelled_by(balance_label.id)
+                        .on_hover_text("Double-click to center");
+                    ui.label("R");
+                    if response.double_clicked() {
+                        balance = 0.0;
+                    }
+                    if response.changed() || response.double_clicked() {
+                        player.set_balance(balance);
+                    }
+                });
+            },
+        );
+    }
 
-            ui.label(format_time(player.position()));
+    fn show_row(ui: &mut Ui, player: &mut VideoPlayer, style: &ControlsStyle) {
+        let icons = &style.icons;
+        let compact = style.layout != ControlsLayout::Full;
 
-            // Use memory to persist slider position during drag
-            let slider_id = ui.id().with("seek_slider");
-            let mut position = ui.memory(|mem| {
-                mem.data.get_temp::<f64>(slider_id).unwrap_or(player_position_secs)
-            });
+        // Play/Pause button
+        let (play_pause_icon, play_pause_name) = match player.state() {
+            PlayerState::Playing => (&icons.pause, "Pause"),
+            _ => (&icons.play, "Play"),
+        };
 
-            let slider_response = ui.add(
-                Slider::new(&mut position, 0.0..=duration_secs)
-                    .show_value(false)
-                    .trailing_fill(true),
-            );
-
-            // Update memory with current position
-            if slider_response.dragged() {
-                // While dragging, store the dragged position
-                ui.memory_mut(|mem| mem.data.insert_temp(slider_id, position));
-            } else if !player.is_seeking() {
-                // When not dragging and not seeking, sync with player
-                ui.memory_mut(|mem| mem.data.insert_temp(slider_id, player_position_secs));
+        if accessible_button(ui, style, play_pause_icon, play_pause_name).clicked() {
+            if player.is_playing() {
+                player.pause();
+            } else {
+                player.play();
             }
+        }
 
-            if slider_response.drag_stopped() || slider_response.clicked() {
-                player.seek(Duration::from_secs_f64(position));
-            }
+        // Stop button
+        if style.show_stop && accessible_button(ui, style, &icons.stop, "Stop").clicked() {
+            player.stop();
+        }
+
+        if style.show_jump_buttons && player.is_seekable() {
+            Self::jump_button(ui, player, style, &icons.rewind, "Rewind", -1.0);
+            Self::jump_button(ui, player, style, &icons.fast_forward, "Fast forward", 1.0);
+        }
 
-            ui.label(format_time(player.duration()));
+        // Scene navigation once scene detection found cuts
+        if style.show_scene_buttons && player.is_seekable() && !player.scene_changes().is_empty() {
+            if accessible_button(ui, style, &icons.previous_scene, "Previous scene")
+                .on_hover_text("Previous scene")
+                .clicked()
+            {
+                player.seek_to_previous_scene();
+            }
+            if accessible_button(ui, style, &icons.next_scene, "Next scene")
+                .on_hover_text("Next scene")
+                .clicked()
+            {
+                player.seek_to_next_scene();
+            }
+        }
 
+        if !compact {
             ui.separator();
+            if style.show_time {
+                Self::time_label(ui, player, player.position(), "Elapsed");
+            }
+        }
+
+        if style.layout == ControlsLayout::Overlay {
+            // Stretch the seek bar over the room left by the time label
+            let time_width = if style.show_time { 110.0 } else { 0.0 };
+            ui.spacing_mut().slider_width = (ui.available_width() - time_width).max(40.0);
+        }
+        Self::seek_bar(ui, player);
+
+        if style.show_time {
+            if compact {
+                Self::combined_time_label(ui, player);
+            } else {
+                Self::time_label(ui, player, player.duration(), "Duration");
+            }
+        }
 
-            // Volume control
-            ui.label("🔊");
+        // Volume control
+        if style.show_volume {
+            if !compact {
+                ui.separator();
+            } else {
+                ui.spacing_mut().slider_width = 60.0;
+            }
+            // The icon opens the other audio settings
+            let volume_label = ui
+                .add(Label::new(style_icon(style, &icons.volume)).sense(Sense::click()))
+                .on_hover_text("Audio settings");
+            Self::audio_popup(ui, player, &volume_label);
             let mut volume = player.volume().get();
-            if ui
+            let volume_response = ui
                 .add(Slider::new(&mut volume, 0.0..=1.0).show_value(false))
-                .changed()
-            {
+                .labelled_by(volume_label.id);
+            volume_response.widget_info(|| {
+                let mut info = WidgetInfo::slider(true, f64::from(volume), "Volume");
+                info.current_text_value = Some(format!("{:.0}%", volume * 100.0));
+                info
+            });
+            if volume_response.changed() {
                 if let Some(v) = Volume::new(volume) {
                     player.set_volume(v);
                 }
             }
+            if style.show_level_meter {
+                if let Some(levels) = player.audio_levels() {
+                    let width = if compact { 36.0 } else { 48.0 };
+                    LevelMeter::show(ui, &levels, width);
+                }
+            }
+        }
 
-            ui.separator();
-
-            // Display mode toggle
-            let mode_text = match player.display_mode() {
-                DisplayMode::FitToWindow => "⛶",
-                DisplayMode::NativeSize => "⊞",
+        // Display mode toggle
+        if style.show_display_mode {
+            if !compact {
+                ui.separator();
+            }
+            let (mode_icon, mode_name) = match player.display_mode() {
+                DisplayMode::FitToWindow => (&icons.fit_to_window, "Display mode: fit to window"),
+                DisplayMode::NativeSize => (&icons.native_size, "Display mode: native size"),
+                DisplayMode::NativePhysical => (
+                    &icons.physical_size,
+                    "Display mode: native size in physical pixels",
+                ),
+                DisplayMode::IntegerScale => {
+                    (&icons.integer_scale, "Display mode: integer scaling")
+                }
             };
 
-            if ui
-                .button(mode_text)
+            if accessible_button(ui, style, mode_icon, mode_name)
                 .on_hover_text("Toggle display mode (double-click video)")
                 .clicked()
             {
                 player.toggle_display_mode();
             }
+        }
+
+        if style.show_media_badge {
+            Self::media_badge(ui, player, style.layout == ControlsLayout::Full);
+        }
+    }
+
+    /// Codec summary that opens the media info window when clicked
+    fn media_badge(ui: &mut Ui, player: &VideoPlayer, separated: bool) {
+        let id = ui.id().with("media_info_open");
+        let mut open = ui.data(|data| data.get_temp(id)).unwrap_or(false);
+        if let Some(badge) = media_badge(player) {
+            if separated {
+                ui.separator();
+            }
+            let response = ui.add(Label::new(RichText::new(&badge).small()).sense(Sense::click()));
+            response.widget_info(|| {
+                WidgetInfo::labeled(
+                    WidgetType::Button,
+                    true,
+                    format!("{}, activate for media info", badge),
+                )
+            });
+            if response.on_hover_text("Show media info").clicked() {
+                open = !open;
+            }
+        }
+        if open {
+            MediaInfoWindow::show(ui.ctx(), player, &mut open);
+        }
+        ui.data_mut(|data| data.insert_temp(id, open));
+    }
+
+    /// Timeline split into chapters, with the waveform, scene ticks and quiet parts behind it
+    fn seek_bar(ui: &mut Ui, player: &mut VideoPlayer) {
+        let duration_secs = player.duration().as_secs_f64();
+        let player_position_secs = player.position().as_secs_f64();
+        let chapters = player.chapters();
+
+        // Use memory to persist slider position during drag
+        let slider_id = ui.id().with("seek_slider");
+        let mut position = ui.memory(|mem| {
+            mem.data
+                .get_temp::<f64>(slider_id)
+                .unwrap_or(player_position_secs)
+        });
+
+        // Reserve a slot so the waveform and markers are painted behind the slider
+        let timeline_slot = ui.painter().add(Shape::Noop);
+        // Pipes play straight through, so there is nothing to seek
+        let slider_response = ui.add_enabled(
+            player.is_seekable(),
+            SeekSlider::new(&mut position, duration_secs).chapters(&chapters),
+        );
+        // Preview the frame under the pointer once thumbnails are generated,
+        // with the title of the chapter it is in
+        let hovered = slider_response.hover_pos().map(|pointer| {
+            let rail = SeekSlider::rail(slider_response.rect);
+            let t = ((pointer.x - rail.min) / rail.span()).clamp(0.0, 1.0);
+            Duration::from_secs_f64(f64::from(t) * duration_secs)
+        });
+        let preview = hovered.and_then(|time| player.thumbnail_preview(time));
+        let chapter = hovered.and_then(|time| {
+            chapters
+                .iter()
+                .find(|chapter| chapter.start <= time && time < chapter.end)
+        });
+        let details = hovered.filter(|_| preview.is_some() || chapter.is_some());
+        let slider_response = if let Some(time) = details {
+            slider_response.on_hover_ui_at_pointer(|ui| {
+                if let Some((texture_id, uv)) = preview {
+                    let size = player.thumbnails().map_or([160, 90], |t| t.tile_size());
+                    let size = Vec2::new(size[0] as f32, size[1] as f32);
+                    ui.add(Image::new((texture_id, size)).uv(uv));
+                }
+                if let Some(chapter) = chapter {
+                    ui.strong(&chapter.title);
+                }
+                ui.label(format_time(
+                    time,
+                    player.time_display_mode(),
+                    player.frame_rate(),
+                ));
+            })
+        } else {
+            slider_response.on_hover_text(
+                "Drag to scrub by keyframes\nHold Shift when releasing for a frame-exact seek",
+            )
+        };
+        let mut timeline = Vec::new();
+        if let Some(waveform) = player.waveform() {
+            timeline.extend(WaveformBar::shapes(
+                slider_response.rect,
+                waveform,
+                WaveformBar::color(ui),
+            ));
+        }
+        if duration_secs > 0.0 {
+            // Same inset as the slider's rail so ticks line up with the handle
+            let rect = slider_response.rect;
+            let rail = SeekSlider::rail(rect);
+            let x = |time: Duration| {
+                rail.min + (time.as_secs_f64() / duration_secs) as f32 * rail.span()
+            };
+            for scene in player.scene_changes() {
+                let x = x(scene);
+                timeline.push(Shape::line_segment(
+                    [Pos2::new(x, rect.top()), Pos2::new(x, rect.top() + 4.0)],
+                    (1.0, WaveformBar::color(ui)),
+                ));
+            }
+            // Quiet parts the player skips or speeds through, as bars along the bottom
+            let quiet = ui.visuals().warn_fg_color.gamma_multiply(0.6);
+            for range in player.silent_ranges() {
+                let bar = Rect::from_x_y_ranges(
+                    x(range.start)..=x(range.end),
+                    rect.bottom() - 3.0..=rect.bottom(),
+                );
+                timeline.push(Shape::rect_filled(bar, 0.0, quiet));
+            }
+        }
+        ui.painter().set(timeline_slot, Shape::Vec(timeline));
+
+        // Update memory with current position
+        if slider_response.dragged() {
+            // While dragging, store the dragged position
+            ui.memory_mut(|mem| mem.data.insert_temp(slider_id, position));
+        } else if !player.is_seeking() {
+            // When not dragging and not seeking, sync with player
+            ui.memory_mut(|mem| mem.data.insert_temp(slider_id, player_position_secs));
+        }
+
+        // Announce the position as text rather than a bare number of seconds
+        slider_response.widget_info(|| {
+            let mut info = WidgetInfo::slider(true, position, "Seek");
+            info.current_text_value = Some(format!(
+                "{} of {}",
+                format_time(
+                    Duration::from_secs_f64(position),
+                    TimeDisplayMode::Standard,
+                    0.0
+                ),
+                format_time(player.duration(), TimeDisplayMode::Standard, 0.0)
+            ));
+            info
+        });
+
+        // Scrub by keyframes while dragging; Shift on release settles on the exact frame
+        if slider_response.dragged() && slider_response.changed() {
+            player.seek_with_mode(Duration::from_secs_f64(position), SeekMode::Keyframe);
+            player.scrub_audio_at(Duration::from_secs_f64(position));
+        } else if slider_response.changed() && slider_response.has_focus() {
+            // Arrow keys step the focused slider
+            player.seek(Duration::from_secs_f64(position));
+        }
+        if slider_response.drag_stopped() || slider_response.clicked() {
+            let mode = if ui.input(|i| i.modifiers.shift) {
+                SeekMode::Exact
+            } else {
+                SeekMode::Keyframe
+            };
+            player.seek_with_mode(Duration::from_secs_f64(position), mode);
+        }
+    }
+
+    /// Time label in the player's display mode; click to cycle modes
+    fn time_label(ui: &mut Ui, player: &mut VideoPlayer, time: Duration, name: &str) {
+        let text = format_time(time, player.time_display_mode(), player.frame_rate());
+        Self::clickable_time(ui, player, text.clone(), format!("{} {}", name, text));
+    }
+
+    /// "elapsed / duration" in one label, for the compact layouts
+    fn combined_time_label(ui: &mut Ui, player: &mut VideoPlayer) {
+        let mode = player.time_display_mode();
+        let elapsed = format_time(player.position(), mode, player.frame_rate());
+        let duration = format_time(player.duration(), mode, player.frame_rate());
+        Self::clickable_time(
+            ui,
+            player,
+            format!("{} / {}", elapsed, duration),
+            format!("Elapsed {} of {}", elapsed, duration),
+        );
+    }
+
+    fn clickable_time(ui: &mut Ui, player: &mut VideoPlayer, text: String, spoken: String) {
+        let response = ui.add(Label::new(text).sense(Sense::click()));
+        response.widget_info(|| {
+            WidgetInfo::labeled(
+                WidgetType::Button,
+                true,
+                format!("{}, activate to change time format", spoken),
+            )
         });
+        if response
+            .on_hover_text("Click to change time format")
+            .clicked()
+        {
+            player.toggle_time_display_mode();
+        }
     }
 }
 
-fn format_time(duration: Duration) -> String {
+/// Icon text in the style's color
+fn style_icon(style: &ControlsStyle, icon: &str) -> RichText {
+    let text = RichText::new(icon);
+    match style.icon_color {
+        Some(color) => text.color(color),
+        None => text,
+    }
+}
+
+/// Icon button with a spoken name, as screen readers cannot read symbols like "⏸"
+fn accessible_button(ui: &mut Ui, style: &ControlsStyle, icon: &str, name: &str) -> Response {
+    let mut button = Button::new(style_icon(style, icon));
+    if style.layout != ControlsLayout::Full {
+        button = button.small();
+    }
+    if style.background.is_some() {
+        button = button.frame(false);
+    }
+    let response = ui.add(button);
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, name));
+    response
+}
+
+pub(crate) fn format_time(duration: Duration, mode: TimeDisplayMode, frame_rate: f64) -> String {
+    // Frame-based modes need a known frame rate
+    let mode = match mode {
+        TimeDisplayMode::Smpte | TimeDisplayMode::FrameNumber if frame_rate <= 0.0 => {
+            TimeDisplayMode::WithMillis
+        }
+        mode => mode,
+    };
     let total_seconds = duration.as_secs();
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
     let secs = total_seconds % 60;
+    let frame = (duration.as_secs_f64() * frame_rate).round() as u64;
+
+    match mode {
+        TimeDisplayMode::Standard if hours > 0 => {
+            format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+        }
+        TimeDisplayMode::Standard => format!("{:02}:{:02}", minutes, secs),
+        TimeDisplayMode::WithMillis => format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            hours,
+            minutes,
+            secs,
+            duration.subsec_millis()
+        ),
+        TimeDisplayMode::Smpte => format_smpte(frame, frame_rate),
+        TimeDisplayMode::FrameNumber => frame.to_string(),
+    }
+}
+
+/// SMPTE timecode for a frame number. NTSC rates (29.97, 59.94) use drop-frame
+/// counting, skipping frame numbers so the timecode keeps up with the clock.
+fn format_smpte(frame: u64, frame_rate: f64) -> String {
+    let nominal = frame_rate.round().max(1.0) as u64;
+    let drop_frame = (frame_rate - frame_rate.round()).abs() > 0.01 && matches!(nominal, 30 | 60);
 
-    if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
-    } else {
-        format!("{:02}:{:02}", minutes, secs)
+    let mut frame = frame;
+    if drop_frame {
+        // Two numbers (four at 59.94) are skipped each minute, except every tenth minute
+        let dropped = nominal / 15;
+        let per_minute = nominal * 60 - dropped;
+        let per_ten_minutes = per_minute * 10 + dropped;
+        let tens = frame / per_ten_minutes;
+        let rest = frame % per_ten_minutes;
+        frame += dropped * 9 * tens;
+        if rest > dropped {
+            frame += dropped * ((rest - dropped) / per_minute);
+        }
     }
+
+    let frames = frame % nominal;
+    let total_seconds = frame / nominal;
+    format!(
+        "{:02}:{:02}:{:02}{}{:02}",
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60,
+        if drop_frame { ';' } else { ':' },
+        frames
+    )
 }
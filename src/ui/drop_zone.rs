@@ -0,0 +1,124 @@
+use crate::player::is_supported_media;
+use egui::{Color32, Id, Stroke, Ui, UiBuilder};
+use std::path::{Path, PathBuf};
+
+/// Files dropped on the window in the last frame, split by whether they can be played
+#[derive(Debug, Default)]
+pub struct DropZoneResponse {
+    /// The "Open Video File..." button was clicked
+    pub open_clicked: bool,
+    pub dropped: Vec<PathBuf>,
+    pub rejected: Vec<PathBuf>,
+}
+
+pub struct DropZone;
+
+impl DropZone {
+    /// Show the empty state: an open button and a drop target that lights up
+    /// while files are dragged over the window, listing them and marking the
+    /// ones that cannot be played. `error` is shown above the heading.
+    pub fn show(ui: &mut Ui, error: Option<&str>) -> DropZoneResponse {
+        let id = Id::new("egui_video_drop_zone");
+        let (hovered, dropped) = ui.input(|i| {
+            (
+                i.raw
+                    .hovered_files
+                    .iter()
+                    .map(|file| (file.path.clone(), file.mime.clone()))
+                    .collect::<Vec<_>>(),
+                i.raw
+                    .dropped_files
+                    .iter()
+                    .filter_map(|file| file.path.clone())
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        let mut response = DropZoneResponse::default();
+        (response.dropped, response.rejected) = dropped
+            .into_iter()
+            .partition(|path| is_supported_media(path));
+        // Keep naming rejected files until the next drag
+        if !response.rejected.is_empty() {
+            ui.data_mut(|data| data.insert_temp(id, response.rejected.clone()));
+        } else if !hovered.is_empty() || !response.dropped.is_empty() {
+            ui.data_mut(|data| data.remove::<Vec<PathBuf>>(id));
+        }
+        let rejected: Vec<PathBuf> = ui.data(|data| data.get_temp(id)).unwrap_or_default();
+
+        let rect = ui.available_rect_before_wrap().shrink(16.0);
+        let highlight = ui.ctx().animate_bool(id, !hovered.is_empty());
+        if highlight > 0.0 {
+            // Pulse the border while files hover, fading in and out with the drag
+            let pulse = (ui.input(|i| i.time) * 4.0).sin() as f32 * 0.25 + 0.75;
+            let accent = ui.visuals().selection.bg_fill;
+            ui.painter().rect(
+                rect,
+                8.0,
+                accent.gamma_multiply(0.15 * highlight),
+                Stroke::new(2.0, accent.gamma_multiply(pulse * highlight)),
+            );
+            ui.ctx().request_repaint();
+        }
+
+        ui.allocate_new_ui(UiBuilder::new().max_rect(rect), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 3.0);
+
+                if let Some(error) = error {
+                    ui.colored_label(Color32::RED, error);
+                    ui.add_space(20.0);
+                }
+
+                if hovered.is_empty() {
+                    ui.heading("No video loaded");
+                    ui.add_space(10.0);
+                    response.open_clicked = ui.button("Open Video File...").clicked();
+                    ui.add_space(10.0);
+                    ui.label("Or drag and drop a video file");
+                } else {
+                    ui.heading("Drop to play");
+                    ui.add_space(10.0);
+                    for (path, mime) in &hovered {
+                        match path {
+                            Some(path) if is_supported_media(path) => {
+                                ui.label(format!("✔ {}", file_name(path)));
+                            }
+                            // Some platforms only reveal the path on drop
+                            None => {
+                                ui.weak(if mime.is_empty() {
+                                    "File"
+                                } else {
+                                    mime.as_str()
+                                });
+                            }
+                            Some(path) => {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("✖ {} (unsupported)", file_name(path)),
+                                );
+                            }
+                        }
+                    }
+                }
+
+                for path in &rejected {
+                    ui.add_space(4.0);
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("Can't play {}: unsupported file type", file_name(path)),
+                    );
+                }
+            });
+        });
+
+        response
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
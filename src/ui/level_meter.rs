@@ -0,0 +1,100 @@
+use crate::player::AudioLevels;
+use egui::{Color32, Rangef, Rect, Response, Sense, Ui, Vec2};
+use std::time::Duration;
+
+/// Quietest level on the scale (dBFS)
+const FLOOR_DB: f32 = -60.0;
+/// Levels above this are drawn as hot
+const WARN_DB: f32 = -12.0;
+/// Levels above this are drawn as near clipping
+const HOT_DB: f32 = -3.0;
+/// On and off time of a flashing clip light
+const FLASH: Duration = Duration::from_millis(250);
+
+/// Compact stereo level meter: one bar per channel with the RMS level, the
+/// peak and a held peak tick, and a light at the end that flashes on clipping
+pub struct LevelMeter;
+
+impl LevelMeter {
+    /// Draw the meter at `width` by the height of a control row
+    pub fn show(ui: &mut Ui, levels: &AudioLevels, width: f32) -> Response {
+        let height = ui.spacing().interact_size.y * 0.6;
+        let (rect, response) = ui.allocate_exact_size(Vec2::new(width, height), Sense::hover());
+        if !ui.is_rect_visible(rect) {
+            return response;
+        }
+
+        let painter = ui.painter_at(rect);
+        let background = ui.visuals().widgets.inactive.bg_fill;
+        let flash_on = (ui.input(|i| i.time) / FLASH.as_secs_f64()) as u64 & 1 == 0;
+        let light_size = height / 2.0 - 1.0;
+        let bar_range = Rangef::new(rect.left(), rect.right() - light_size - 2.0);
+        for side in 0..2 {
+            let top = rect.top() + side as f32 * (light_size + 2.0);
+            let y_range = Rangef::new(top, top + light_size);
+            let bar = Rect::from_x_y_ranges(bar_range, y_range);
+            painter.rect_filled(bar, 1.0, background);
+
+            let x = |level: f32| bar.left() + Self::fraction(level) * bar.width();
+            let mut peak = bar;
+            peak.max.x = x(levels.peak[side]);
+            painter.rect_filled(
+                peak,
+                1.0,
+                Self::color(levels.peak[side]).gamma_multiply(0.5),
+            );
+            let mut rms = bar;
+            rms.max.x = x(levels.rms[side]);
+            painter.rect_filled(rms, 1.0, Self::color(levels.rms[side]));
+            if levels.peak_hold[side] > 0.0 {
+                let hold_x = x(levels.peak_hold[side]).min(bar.right() - 1.0);
+                painter.vline(hold_x, y_range, (1.5, Self::color(levels.peak_hold[side])));
+            }
+
+            let light = Rect::from_min_size(
+                egui::pos2(rect.right() - light_size, top),
+                Vec2::splat(light_size),
+            );
+            let lit = levels.clipping[side] && flash_on;
+            let light_color = if lit { Color32::RED } else { background };
+            painter.rect_filled(light, 1.0, light_color);
+        }
+
+        if levels.is_clipping() {
+            ui.ctx().request_repaint_after(FLASH);
+        } else if levels.peak_hold.iter().any(|&level| level > 0.0) {
+            // Keep the held peaks falling while paused
+            ui.ctx().request_repaint();
+        }
+
+        response.on_hover_ui(|ui| {
+            for (side, name) in ["L", "R"].iter().enumerate() {
+                let mut text = format!(
+                    "{name}: {:.1} dB RMS, {:.1} dB peak",
+                    AudioLevels::to_db(levels.rms[side]),
+                    AudioLevels::to_db(levels.peak_hold[side]),
+                );
+                if levels.clipping[side] {
+                    text.push_str(", clipping");
+                }
+                ui.label(text);
+            }
+        })
+    }
+
+    /// Position of a linear level on the dB scale, 0.0 to 1.0
+    fn fraction(level: f32) -> f32 {
+        ((AudioLevels::to_db(level) - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+    }
+
+    fn color(level: f32) -> Color32 {
+        let db = AudioLevels::to_db(level);
+        if db >= HOT_DB {
+            Color32::from_rgb(230, 70, 50)
+        } else if db >= WARN_DB {
+            Color32::from_rgb(230, 190, 40)
+        } else {
+            Color32::from_rgb(70, 190, 90)
+        }
+    }
+}
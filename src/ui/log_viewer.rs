@@ -0,0 +1,176 @@
+use crate::player::PlayerError;
+use egui::{Color32, RichText, ScrollArea, TextEdit, Ui};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// A captured tracing event
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogRecord {
+    /// Time since the capture was created
+    pub elapsed: Duration,
+    pub level: Level,
+    pub target: String,
+    /// Enclosing spans, outermost first, e.g. "open:seek"
+    pub spans: String,
+    /// The message followed by the other fields as `name=value`
+    pub message: String,
+}
+
+/// Keeps the most recent tracing events for `LogViewer`.
+/// Add it to a subscriber as a layer, or set it up alone with `install()`.
+#[derive(Clone)]
+pub struct LogCapture {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    capacity: usize,
+    max_level: Level,
+    start: Instant,
+}
+
+impl LogCapture {
+    /// Keep up to `capacity` events at debug level and above
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::default(),
+            capacity: capacity.max(1),
+            max_level: Level::DEBUG,
+            start: Instant::now(),
+        }
+    }
+
+    /// Capture events up to this verbosity, e.g. `Level::TRACE` for the queue depths
+    #[must_use]
+    pub fn with_max_level(mut self, level: Level) -> Self {
+        self.max_level = level;
+        self
+    }
+
+    /// Make this the process-wide subscriber. Fails if one is already set.
+    pub fn install(self) -> Result<Self, PlayerError> {
+        let subscriber = tracing_subscriber::registry().with(self.clone());
+        tracing::subscriber::set_global_default(subscriber)
+            .map_err(|e| PlayerError::Other(e.to_string()))?;
+        Ok(self)
+    }
+
+    /// Copy of the captured events, oldest first
+    #[must_use]
+    pub fn records(&self) -> Vec<LogRecord> {
+        self.records.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().clear();
+    }
+}
+
+impl<S> Layer<S> for LogCapture
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > self.max_level {
+            return;
+        }
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name())
+                    .collect::<Vec<_>>()
+                    .join(":")
+            })
+            .unwrap_or_default();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let mut records = self.records.lock();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(LogRecord {
+            elapsed: self.start.elapsed(),
+            level: *metadata.level(),
+            target: metadata.target().to_owned(),
+            spans,
+            message: visitor.message + &visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+pub struct LogViewer;
+
+impl LogViewer {
+    /// Show the captured events, newest at the bottom, with a text filter
+    /// over targets, spans and messages; `filter` holds it between frames
+    pub fn show(ui: &mut Ui, capture: &LogCapture, filter: &mut String) {
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(filter).hint_text("Filter"));
+            if ui.button("Clear").clicked() {
+                capture.clear();
+            }
+        });
+        ui.separator();
+
+        let needle = filter.trim().to_lowercase();
+        ScrollArea::vertical()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for record in capture.records() {
+                    let line = format!(
+                        "{:>8.3} {:<5} {} {}{}{}",
+                        record.elapsed.as_secs_f64(),
+                        record.level,
+                        record.target,
+                        record.spans,
+                        if record.spans.is_empty() { "" } else { ": " },
+                        record.message
+                    );
+                    if !needle.is_empty() && !line.to_lowercase().contains(&needle) {
+                        continue;
+                    }
+                    let color = match record.level {
+                        Level::ERROR => Color32::from_rgb(255, 90, 90),
+                        Level::WARN => Color32::from_rgb(255, 200, 80),
+                        Level::INFO => ui.visuals().text_color(),
+                        _ => ui.visuals().weak_text_color(),
+                    };
+                    ui.label(RichText::new(line).monospace().color(color));
+                }
+            });
+    }
+}
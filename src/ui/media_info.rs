@@ -0,0 +1,100 @@
+use crate::player::{StreamInfo, StreamKind, TimeDisplayMode, VideoPlayer};
+use crate::ui::controls::format_time;
+use egui::{Context, Grid, Window};
+
+pub struct MediaInfoWindow;
+
+impl MediaInfoWindow {
+    /// Show the container and stream details of the player's media in a window,
+    /// closed with its title bar button
+    pub fn show(ctx: &Context, player: &VideoPlayer, open: &mut bool) {
+        Window::new("Media info")
+            .open(open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                Grid::new("egui_video_media_info")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("File");
+                        ui.label(player.path().display().to_string());
+                        ui.end_row();
+                        ui.label("Duration");
+                        ui.label(format_time(
+                            player.duration(),
+                            TimeDisplayMode::WithMillis,
+                            0.0,
+                        ));
+                        ui.end_row();
+                        if let Some(bit_rate) = player.bit_rate() {
+                            ui.label("Bit rate");
+                            ui.label(format_bit_rate(bit_rate));
+                            ui.end_row();
+                        }
+                        for stream in player.streams() {
+                            ui.label(format!("Stream #{}", stream.index));
+                            ui.label(describe_stream(stream));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+/// One-line summary such as "1080p • H.264 • AAC 5.1" from the first video
+/// and audio streams, or None if the media has neither
+#[must_use]
+pub fn media_badge(player: &VideoPlayer) -> Option<String> {
+    let mut parts = Vec::new();
+    let first = |kind| player.streams().iter().find(|s| s.kind == kind);
+    if let Some(video) = first(StreamKind::Video) {
+        let lines = video.width.min(video.height);
+        if lines > 0 {
+            parts.push(format!("{}p", lines));
+        }
+        parts.push(video.codec_label());
+    }
+    if let Some(audio) = first(StreamKind::Audio) {
+        parts.push(format!("{} {}", audio.codec_label(), audio.channel_label()));
+    }
+    (!parts.is_empty()).then(|| parts.join(" • "))
+}
+
+fn describe_stream(stream: &StreamInfo) -> String {
+    let mut text = match stream.kind {
+        StreamKind::Video => "Video",
+        StreamKind::Audio => "Audio",
+        StreamKind::Subtitle => "Subtitle",
+        StreamKind::Other => "Data",
+    }
+    .to_owned();
+    text += &format!(": {}", stream.codec);
+    if let Some(ref profile) = stream.profile {
+        text += &format!(" ({})", profile);
+    }
+    match stream.kind {
+        StreamKind::Video if stream.width > 0 => {
+            text += &format!(", {}x{}", stream.width, stream.height);
+        }
+        StreamKind::Audio if stream.channels > 0 => {
+            text += &format!(", {} Hz {}", stream.sample_rate, stream.channel_label());
+        }
+        _ => {}
+    }
+    if let Some(bit_rate) = stream.bit_rate {
+        text += &format!(", {}", format_bit_rate(bit_rate));
+    }
+    if let Some(ref language) = stream.language {
+        text += &format!(" [{}]", language);
+    }
+    text
+}
+
+fn format_bit_rate(bits_per_second: u64) -> String {
+    if bits_per_second >= 1_000_000 {
+        format!("{:.1} Mb/s", bits_per_second as f64 / 1e6)
+    } else {
+        format!("{} kb/s", bits_per_second / 1000)
+    }
+}
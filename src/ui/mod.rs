@@ -1 +1,18 @@
+#[cfg(feature = "libass")]
+pub mod ass;
+pub mod compare;
 pub mod controls;
+pub mod drop_zone;
+pub mod level_meter;
+#[cfg(feature = "log-viewer")]
+pub mod log_viewer;
+pub mod media_info;
+#[cfg(feature = "render-wgpu")]
+pub mod renderer;
+pub mod seek_slider;
+pub mod subtitle_list;
+pub mod subtitles;
+pub mod telemetry;
+pub mod timecode;
+pub mod video;
+pub mod waveform;
@@ -0,0 +1,248 @@
+//! Video drawn by a wgpu paint callback, so a WGSL shader can post-process it.
+//!
+//! Requires the `render-wgpu` feature and an eframe app running on the wgpu backend.
+
+use crate::player::{PlayerError, VideoPlayer};
+use egui::{Rect, Response, Sense, Ui, Vec2};
+use egui_wgpu::wgpu::{self, naga};
+use egui_wgpu::{CallbackTrait, RenderState};
+use std::sync::Arc;
+
+/// Post-processing that leaves the frame unchanged
+const IDENTITY_SHADER: &str = "
+fn post_process(color: vec4<f32>, uv: vec2<f32>) -> vec4<f32> {
+    return color;
+}
+";
+
+/// Draws the frame as a quad filling the viewport egui sets to the callback rect.
+/// `post_process` is supplied by the user.
+const VIDEO_SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0) var video: texture_2d<f32>;
+@group(0) @binding(1) var video_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32(index & 1u), f32(index >> 1u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn gamma_from_linear(linear: vec3<f32>) -> vec3<f32> {
+    let cutoff = linear < vec3<f32>(0.0031308);
+    let lower = linear * 12.92;
+    let higher = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(higher, lower, cutoff);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = post_process(textureSample(video, video_sampler, in.uv), in.uv);
+    if OUTPUT_GAMMA {
+        return vec4<f32>(gamma_from_linear(color.rgb), color.a);
+    }
+    return color;
+}
+";
+
+/// Renders the player's frame through a custom WGSL shader instead of `ui.image`.
+///
+/// The shader defines `fn post_process(color: vec4<f32>, uv: vec2<f32>) -> vec4<f32>`,
+/// receiving the linear color at `uv`. It may sample other texels itself through
+/// `video` and `video_sampler`, e.g. for sharpening, and use `textureDimensions(video)`.
+pub struct VideoRenderer {
+    render_state: RenderState,
+    pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl VideoRenderer {
+    /// Create a renderer that draws frames unchanged
+    pub fn new(render_state: &RenderState) -> Self {
+        Self::with_shader(render_state, IDENTITY_SHADER).expect("built-in video shader is valid")
+    }
+
+    /// Create a renderer that post-processes frames with `post_process` WGSL code.
+    /// Returns an error describing the problem if the shader does not compile.
+    pub fn with_shader(
+        render_state: &RenderState,
+        post_process: &str,
+    ) -> Result<Self, PlayerError> {
+        // egui renders into a non-sRGB target in gamma space
+        let output_gamma = !render_state.target_format.is_srgb();
+        let source = format!(
+            "const OUTPUT_GAMMA: bool = {};\n{}\n{}",
+            output_gamma, VIDEO_SHADER, post_process
+        );
+        validate(&source)?;
+
+        let device = &render_state.device;
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("egui_video shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("egui_video bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("egui_video pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("egui_video pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                compilation_options: Default::default(),
+                // Frames are premultiplied, like everything egui draws
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: render_state.target_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("egui_video sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            render_state: render_state.clone(),
+            pipeline: Arc::new(pipeline),
+            bind_group_layout,
+            sampler,
+        })
+    }
+
+    /// Show the current frame scaled to fit the available space, keeping its aspect ratio
+    pub fn show(&self, ui: &mut Ui, player: &VideoPlayer) -> Response {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click());
+        let (width, height) = player.video_size();
+        let video_size = Vec2::new(width.max(1) as f32, height.max(1) as f32);
+        let scale = (rect.width() / video_size.x).min(rect.height() / video_size.y);
+        self.paint(
+            ui,
+            Rect::from_center_size(rect.center(), video_size * scale),
+            player,
+        );
+        response
+    }
+
+    /// Paint the current frame into `rect`
+    pub fn paint(&self, ui: &Ui, rect: Rect, player: &VideoPlayer) {
+        let Some(texture_id) = player.texture_id() else {
+            return;
+        };
+        // egui replaces the wgpu texture when the frame size changes, so bind it per paint
+        let view = {
+            let renderer = self.render_state.renderer.read();
+            let Some(texture) = renderer
+                .texture(&texture_id)
+                .and_then(|texture| texture.texture.as_ref())
+            else {
+                return;
+            };
+            texture.create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let bind_group = self
+            .render_state
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("egui_video bind group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+        ui.painter().add(egui_wgpu::Callback::new_paint_callback(
+            rect,
+            VideoCallback {
+                pipeline: self.pipeline.clone(),
+                bind_group,
+            },
+        ));
+    }
+}
+
+/// Parse and validate WGSL so mistakes surface as an error rather than a wgpu panic
+fn validate(source: &str) -> Result<(), PlayerError> {
+    let module = naga::front::wgsl::parse_str(source)
+        .map_err(|e| PlayerError::Other(e.emit_to_string(source)))?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .map_err(|e| PlayerError::Other(e.emit_to_string(source)))?;
+    Ok(())
+}
+
+struct VideoCallback {
+    pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl CallbackTrait for VideoCallback {
+    fn paint(
+        &self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'static>,
+        _callback_resources: &egui_wgpu::CallbackResources,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..4, 0..1);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::player::Chapter;
+use egui::{pos2, EventFilter, Key, Rangef, Rect, Response, Sense, TextStyle, Ui, Vec2, Widget};
+
+/// Space between the sections of chapters (points)
+const CHAPTER_GAP: f32 = 2.0;
+/// Rail thickness of the section under the pointer, relative to the others
+const HOVER_GROWTH: f32 = 1.8;
+
+/// Timeline slider in seconds, drawn like `egui::Slider` with trailing fill.
+/// With chapters, the rail is split into one section per chapter, and the
+/// section under the pointer grows.
+pub struct SeekSlider<'a> {
+    position: &'a mut f64,
+    duration: f64,
+    chapters: &'a [Chapter],
+}
+
+impl<'a> SeekSlider<'a> {
+    pub fn new(position: &'a mut f64, duration: f64) -> Self {
+        Self {
+            position,
+            duration: duration.max(0.0),
+            chapters: &[],
+        }
+    }
+
+    /// Split the rail at the start of each chapter
+    pub fn chapters(mut self, chapters: &'a [Chapter]) -> Self {
+        self.chapters = chapters;
+        self
+    }
+
+    /// Horizontal span the timeline maps onto: the slider rect inset by the handle radius
+    pub(crate) fn rail(rect: Rect) -> Rangef {
+        rect.x_range().shrink(handle_radius(rect))
+    }
+
+    fn x(&self, rail: Rangef, seconds: f64) -> f32 {
+        if self.duration <= 0.0 {
+            return rail.min;
+        }
+        rail.min + (seconds / self.duration).clamp(0.0, 1.0) as f32 * rail.span()
+    }
+
+    /// Left and right ends of each chapter's section of the rail, gaps excluded
+    fn sections(&self, rect: Rect) -> Vec<Rangef> {
+        let rail = Self::rail(rect);
+        let mut splits: Vec<f32> = self
+            .chapters
+            .iter()
+            .map(|chapter| self.x(rail, chapter.start.as_secs_f64()))
+            .filter(|&x| x > rail.min && x < rail.max)
+            .collect();
+        splits.dedup();
+        let starts =
+            std::iter::once(rect.left()).chain(splits.iter().map(|x| x + CHAPTER_GAP / 2.0));
+        let ends = splits
+            .iter()
+            .map(|x| x - CHAPTER_GAP / 2.0)
+            .chain(std::iter::once(rect.right()));
+        starts
+            .zip(ends)
+            .map(|(min, max)| Rangef::new(min, max))
+            .collect()
+    }
+}
+
+impl Widget for SeekSlider<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let height = ui
+            .text_style_height(&TextStyle::Body)
+            .max(ui.spacing().interact_size.y);
+        let size = Vec2::new(ui.spacing().slider_width, height);
+        let (rect, mut response) = ui.allocate_exact_size(size, Sense::drag());
+        let rail = Self::rail(rect);
+        let previous = *self.position;
+
+        if let Some(pointer) = response.interact_pointer_pos() {
+            let t = ((pointer.x - rail.min) / rail.span()).clamp(0.0, 1.0);
+            *self.position = f64::from(t) * self.duration;
+        }
+        if response.has_focus() {
+            ui.memory_mut(|mem| {
+                mem.set_focus_lock_filter(
+                    response.id,
+                    EventFilter {
+                        horizontal_arrows: true,
+                        ..Default::default()
+                    },
+                );
+            });
+            // Arrow keys move the handle one point, as on egui's slider
+            let steps = ui.input(|i| {
+                i.num_presses(Key::ArrowRight) as f64 - i.num_presses(Key::ArrowLeft) as f64
+            });
+            if steps != 0.0 && rail.span() > 0.0 {
+                let step = self.duration / f64::from(rail.span());
+                *self.position = (*self.position + steps * step).clamp(0.0, self.duration);
+            }
+        }
+        if *self.position != previous {
+            response.mark_changed();
+        }
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            let rail_color = ui.visuals().widgets.inactive.bg_fill;
+            let fill_color = ui.visuals().selection.bg_fill;
+            let rounding = ui.visuals().widgets.inactive.rounding;
+            let radius = (ui.spacing().slider_rail_height / 2.0).max(0.0);
+            let handle_x = self.x(rail, *self.position);
+            let hover_x = response.hover_pos().map(|pointer| pointer.x);
+            let painter = ui.painter();
+
+            let sections = self.sections(rect);
+            let split = sections.len() > 1;
+            for section in sections {
+                let hovered = split && hover_x.is_some_and(|x| section.contains(x));
+                let radius = if hovered {
+                    radius * HOVER_GROWTH
+                } else {
+                    radius
+                };
+                let section_rect = Rect::from_x_y_ranges(
+                    section,
+                    rect.center().y - radius..=rect.center().y + radius,
+                );
+                painter.rect_filled(section_rect, rounding, rail_color);
+                if handle_x > section.min {
+                    let mut filled = section_rect;
+                    filled.max.x = filled.max.x.min(handle_x);
+                    painter.rect_filled(filled, rounding, fill_color);
+                }
+            }
+
+            painter.circle(
+                pos2(handle_x, rect.center().y),
+                handle_radius(rect) + visuals.expansion,
+                visuals.bg_fill,
+                visuals.fg_stroke,
+            );
+        }
+
+        response
+    }
+}
+
+/// Radius of the handle, the same share of the height as on egui's slider
+fn handle_radius(rect: Rect) -> f32 {
+    rect.height() / 2.5
+}
@@ -0,0 +1,57 @@
+use crate::player::{TimeDisplayMode, VideoPlayer};
+use crate::ui::controls::format_time;
+use egui::{ScrollArea, TextEdit, Ui};
+
+pub struct SubtitleList;
+
+impl SubtitleList {
+    /// Show the loaded subtitle cues with a search field, for a side panel.
+    /// Clicking a cue seeks to it; `query` holds the search text between frames.
+    /// Matching ignores case and line breaks.
+    pub fn show(ui: &mut Ui, player: &mut VideoPlayer, query: &mut String) {
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("Previous subtitle").clicked() {
+                player.seek_to_previous_subtitle();
+            }
+            if ui.button("⏭").on_hover_text("Next subtitle").clicked() {
+                player.seek_to_next_subtitle();
+            }
+            ui.add(TextEdit::singleline(query).hint_text("Search subtitles"));
+        });
+        ui.separator();
+
+        if player.subtitle_cues().is_empty() {
+            ui.label("No subtitles loaded");
+            return;
+        }
+
+        let needle = query.trim().to_lowercase();
+        let position = player.position();
+        let mut selected = None;
+        ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+            let cues = player.subtitle_cues();
+            // The last cue started is the one on screen, or the one just shown
+            let current = cues
+                .iter()
+                .rposition(|cue| player.subtitle_cue_position(cue) <= position);
+            for (i, cue) in cues.iter().enumerate() {
+                let text = cue.text.replace('\n', " ");
+                if !needle.is_empty() && !text.to_lowercase().contains(&needle) {
+                    continue;
+                }
+                let start = player.subtitle_cue_position(cue);
+                let label = format!(
+                    "{}  {}",
+                    format_time(start, TimeDisplayMode::Standard, 0.0),
+                    text
+                );
+                if ui.selectable_label(current == Some(i), label).clicked() {
+                    selected = Some(start);
+                }
+            }
+        });
+        if let Some(start) = selected {
+            player.seek(start);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use egui::{Align2, Color32, FontFamily, FontId, Rect, Ui, Vec2};
+
+/// Which edge of the video a subtitle track is anchored to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubtitlePosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// Placement and look of one subtitle track, so two tracks can be told apart
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubtitleStyle {
+    pub position: SubtitlePosition,
+    pub color: Color32,
+    pub background: Color32,
+    /// Multiplier on the default font size, which follows the video height
+    pub size: f32,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            position: SubtitlePosition::Bottom,
+            color: Color32::WHITE,
+            background: Color32::from_black_alpha(160),
+            size: 1.0,
+        }
+    }
+}
+
+impl SubtitleStyle {
+    /// Smaller yellow text at the top of the frame, for a secondary track
+    #[must_use]
+    pub fn secondary() -> Self {
+        Self {
+            position: SubtitlePosition::Top,
+            color: Color32::from_rgb(255, 230, 120),
+            size: 0.85,
+            ..Self::default()
+        }
+    }
+}
+
+pub struct SubtitleOverlay;
+
+impl SubtitleOverlay {
+    /// Draw subtitle text centered near the bottom of the video rect
+    pub fn show(ui: &Ui, video_rect: Rect, text: &str) {
+        Self::show_with_font(ui, video_rect, text, FontFamily::Proportional);
+    }
+
+    /// Draw subtitle text in the given font family, e.g. `VideoPlayer::subtitle_font()`
+    /// for the fonts attached to the media
+    pub fn show_with_font(ui: &Ui, video_rect: Rect, text: &str, family: FontFamily) {
+        Self::show_styled(ui, video_rect, text, family, &SubtitleStyle::default());
+    }
+
+    /// Draw subtitle text with the given placement and colors, e.g. the
+    /// primary track with the default style and `VideoPlayer::secondary_subtitle()`
+    /// with `SubtitleStyle::secondary()`
+    pub fn show_styled(
+        ui: &Ui,
+        video_rect: Rect,
+        text: &str,
+        family: FontFamily,
+        style: &SubtitleStyle,
+    ) {
+        let painter = ui.painter_at(video_rect);
+        let font_size = (video_rect.height() * 0.05).clamp(14.0, 48.0) * style.size;
+        let galley = painter.layout(
+            text.to_owned(),
+            FontId::new(font_size, family),
+            style.color,
+            video_rect.width() * 0.9,
+        );
+
+        let padding = Vec2::new(8.0, 4.0);
+        let margin = Vec2::new(0.0, video_rect.height() * 0.06);
+        let (anchor, align) = match style.position {
+            SubtitlePosition::Top => (video_rect.center_top() + margin, Align2::CENTER_TOP),
+            SubtitlePosition::Bottom => {
+                (video_rect.center_bottom() - margin, Align2::CENTER_BOTTOM)
+            }
+        };
+        let background = align.anchor_size(anchor, galley.size() + padding * 2.0);
+        painter.rect_filled(background, 4.0, style.background);
+        painter.galley(background.min + padding, galley, style.color);
+    }
+}
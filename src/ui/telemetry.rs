@@ -0,0 +1,55 @@
+use crate::player::TelemetrySample;
+use egui::{Color32, FontId, Rect, Ui, Vec2};
+
+pub struct TelemetryOverlay;
+
+impl TelemetryOverlay {
+    /// Draw the telemetry fields in the top-left corner of the video rect
+    pub fn show(ui: &Ui, video_rect: Rect, sample: &TelemetrySample) {
+        let text = format_sample(sample);
+        if text.is_empty() {
+            return;
+        }
+
+        let painter = ui.painter_at(video_rect);
+        let galley = painter.layout_no_wrap(text, FontId::monospace(12.0), Color32::WHITE);
+
+        let padding = Vec2::new(6.0, 4.0);
+        let background = Rect::from_min_size(
+            video_rect.min + Vec2::splat(8.0),
+            galley.size() + padding * 2.0,
+        );
+        painter.rect_filled(background, 4.0, Color32::from_black_alpha(160));
+        painter.galley(background.min + padding, galley, Color32::WHITE);
+    }
+}
+
+pub(crate) fn format_sample(sample: &TelemetrySample) -> String {
+    let mut lines = Vec::new();
+
+    if let (Some(lat), Some(lon)) = (sample.sensor_latitude, sample.sensor_longitude) {
+        lines.push(format!("SENSOR  {:>10.5} {:>11.5}", lat, lon));
+    }
+    if let Some(alt) = sample.sensor_altitude {
+        lines.push(format!("ALT     {:>8.1} m", alt));
+    }
+    if let (Some(lat), Some(lon)) = (sample.frame_center_latitude, sample.frame_center_longitude) {
+        lines.push(format!("CENTER  {:>10.5} {:>11.5}", lat, lon));
+    }
+    if let Some(heading) = sample.platform_heading {
+        lines.push(format!(
+            "HDG {:>6.1}  PITCH {:>5.1}  ROLL {:>5.1}",
+            heading,
+            sample.platform_pitch.unwrap_or(0.0),
+            sample.platform_roll.unwrap_or(0.0)
+        ));
+    }
+    if let (Some(az), Some(el)) = (sample.sensor_azimuth, sample.sensor_elevation) {
+        lines.push(format!("SENSOR AZ {:>6.1}  EL {:>6.1}", az, el));
+    }
+    if let Some(hfov) = sample.horizontal_fov {
+        lines.push(format!("HFOV {:>6.2}", hfov));
+    }
+
+    lines.join("\n")
+}
@@ -0,0 +1,31 @@
+use crate::player::TimeDisplayMode;
+use crate::ui::controls::format_time;
+use egui::{Color32, FontId, Rect, Ui, Vec2};
+use std::time::Duration;
+
+pub struct TimecodeOverlay;
+
+impl TimecodeOverlay {
+    /// Draw the timestamp, SMPTE timecode and number of a frame in the
+    /// top-right corner of the video rect
+    pub fn show(ui: &Ui, video_rect: Rect, pts: Duration, frame_number: u64, frame_rate: f64) {
+        let mut text = format_time(pts, TimeDisplayMode::WithMillis, 0.0);
+        if frame_rate > 0.0 {
+            text += &format!(
+                "  {}  #{}",
+                format_time(pts, TimeDisplayMode::Smpte, frame_rate),
+                frame_number
+            );
+        }
+
+        let painter = ui.painter_at(video_rect);
+        let galley = painter.layout_no_wrap(text, FontId::monospace(14.0), Color32::WHITE);
+
+        let padding = Vec2::new(6.0, 4.0);
+        let size = galley.size() + padding * 2.0;
+        let background =
+            Rect::from_min_size(video_rect.right_top() + Vec2::new(-8.0 - size.x, 8.0), size);
+        painter.rect_filled(background, 4.0, Color32::from_black_alpha(160));
+        painter.galley(background.min + padding, galley, Color32::WHITE);
+    }
+}
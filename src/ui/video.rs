@@ -0,0 +1,347 @@
+use crate::player::{DisplayMode, OverlayFlags, VideoPlayer, ViewTransform};
+use crate::ui::timecode::TimecodeOverlay;
+use egui::{
+    Button, Color32, CursorIcon, Image, PointerButton, Pos2, Rect, Response, ScrollArea, Sense,
+    Shape, Spinner, TextureId, Ui, Vec2,
+};
+use std::time::Duration;
+
+/// Seconds a seek runs before the spinner appears, so quick seeks do not flicker
+const SPINNER_DELAY: f64 = 0.15;
+
+pub struct VideoView;
+
+impl VideoView {
+    /// Show the current video frame using the player's display mode.
+    /// Double-click toggles the display mode; right-click opens frame actions.
+    /// Ctrl+scroll or pinch zooms and dragging pans the zoomed video.
+    /// The timecode overlay, if enabled, is drawn over the visible part, and a
+    /// spinner while a slow seek runs, over the last frame.
+    /// Returns the response of the video image, sized to its visible part.
+    pub fn show(ui: &mut Ui, player: &mut VideoPlayer) -> Option<Response> {
+        let texture_id = player.texture_id()?;
+        // Piped input has no size until its first frame arrives
+        let (width, height) = player.video_size();
+        let video_size = Vec2::new(width.max(1) as f32, height.max(1) as f32);
+
+        // Reserve a slot under the image for the transparency checkerboard
+        let background = ui.painter().add(Shape::Noop);
+
+        let (response, image_rect) = match player.display_mode() {
+            DisplayMode::FitToWindow => {
+                // Scale to fit while maintaining aspect ratio
+                let available_size = ui.available_size();
+                let aspect = video_size.x / video_size.y;
+                let available_aspect = available_size.x / available_size.y;
+
+                let display_size = if aspect > available_aspect {
+                    Vec2::new(available_size.x, available_size.x / aspect)
+                } else {
+                    Vec2::new(available_size.y * aspect, available_size.y)
+                };
+
+                Self::zoomable_image(ui, player, texture_id, display_size)
+            }
+            DisplayMode::IntegerScale => {
+                // Largest whole multiple of the native size that fits (never below 1x)
+                let available_size = ui.available_size();
+                let scale = (available_size.x / video_size.x)
+                    .min(available_size.y / video_size.y)
+                    .floor()
+                    .max(1.0);
+
+                Self::zoomable_image(ui, player, texture_id, video_size * scale)
+            }
+            DisplayMode::NativeSize => {
+                let response = ScrollArea::both()
+                    .show(ui, |ui| {
+                        ui.add(Image::new((texture_id, video_size)).sense(Sense::click()))
+                    })
+                    .inner;
+                let image_rect = response.rect;
+                (response, image_rect)
+            }
+            DisplayMode::NativePhysical => {
+                let size = video_size / ui.ctx().pixels_per_point();
+                let response = ScrollArea::both()
+                    .show(ui, |ui| {
+                        let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+                        // Start on a physical pixel so each video pixel covers exactly one
+                        let min = ui.painter().round_pos_to_pixels(rect.min);
+                        let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+                        ui.painter().image(
+                            texture_id,
+                            Rect::from_min_size(min, size),
+                            uv,
+                            Color32::WHITE,
+                        );
+                        response
+                    })
+                    .inner;
+                let image_rect = response.rect;
+                (response, image_rect)
+            }
+        };
+
+        if player.has_alpha() && player.checkerboard() {
+            // Only the visible part, as the native sizes may scroll
+            ui.painter()
+                .set(background, checkerboard(response.interact_rect));
+        }
+
+        if player.timecode_overlay() {
+            if let Some((pts, frame_number)) = player.presented_frame() {
+                TimecodeOverlay::show(
+                    ui,
+                    response.interact_rect,
+                    pts,
+                    frame_number,
+                    player.frame_rate(),
+                );
+            }
+        }
+
+        if player.pixel_inspector() {
+            Self::inspect_pixel(ui, player, &response, image_rect);
+        }
+
+        Self::seek_spinner(ui, response.interact_rect, player.is_seeking());
+
+        if response.double_clicked() {
+            player.toggle_display_mode();
+        }
+
+        response.context_menu(|ui| Self::frame_menu(ui, player));
+
+        Some(response)
+    }
+
+    /// Frame centered in the available space, zoomed and panned by the player's view.
+    /// Returns the response for the visible part and the rect of the whole frame.
+    fn zoomable_image(
+        ui: &mut Ui,
+        player: &mut VideoPlayer,
+        texture_id: TextureId,
+        display_size: Vec2,
+    ) -> (Response, Rect) {
+        let (area, mut response) =
+            ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        let image_rect = |view: ViewTransform| {
+            let size = display_size * view.zoom;
+            Rect::from_center_size(area.center() - Vec2::from(view.pan) * size, size)
+        };
+
+        let mut view = player.view_transform();
+        if let Some(pointer) = response.hover_pos() {
+            let zoom_delta = ui.input(|i| i.zoom_delta());
+            if zoom_delta != 1.0 {
+                // Keep the point under the pointer in place
+                let before = image_rect(view);
+                let anchor = (pointer - before.min) / before.size();
+                view.zoom = (view.zoom * zoom_delta).clamp(1.0, ViewTransform::MAX_ZOOM);
+                let size = display_size * view.zoom;
+                let center = pointer - anchor * size + size / 2.0;
+                view.pan = ((area.center() - center) / size).into();
+            }
+        }
+        if response.dragged_by(PointerButton::Primary) {
+            let pan = response.drag_delta() / (display_size * view.zoom);
+            view.pan = (Vec2::from(view.pan) - pan).into();
+        }
+        player.set_view_transform(view);
+
+        let rect = image_rect(player.view_transform());
+        ui.painter_at(area).image(
+            texture_id,
+            rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::WHITE,
+        );
+        response.rect = rect.intersect(area);
+        response.interact_rect = response.rect;
+        (response, rect)
+    }
+
+    /// Draw a small spinner in the middle of `rect` once a seek has run for `SPINNER_DELAY`
+    fn seek_spinner(ui: &Ui, rect: Rect, seeking: bool) {
+        let id = ui.id().with("seek_started");
+        if !seeking {
+            ui.data_mut(|data| data.remove::<f64>(id));
+            return;
+        }
+        let now = ui.input(|i| i.time);
+        let started = ui.data_mut(|data| *data.get_temp_mut_or(id, now));
+        if now - started < SPINNER_DELAY {
+            ui.ctx()
+                .request_repaint_after(Duration::from_secs_f64(SPINNER_DELAY));
+            return;
+        }
+        let size = 24.0;
+        ui.painter_at(rect)
+            .circle_filled(rect.center(), size, Color32::from_black_alpha(120));
+        Spinner::new()
+            .size(size)
+            .color(Color32::from_white_alpha(200))
+            .paint_at(ui, Rect::from_center_size(rect.center(), Vec2::splat(size)));
+    }
+
+    /// Show the native coordinates and color of the pixel under the pointer
+    /// next to it, and copy the color as hex on click
+    fn inspect_pixel(ui: &Ui, player: &VideoPlayer, response: &Response, image_rect: Rect) {
+        let Some(pointer) = response.hover_pos() else {
+            return;
+        };
+        let Some(frame) = player.current_frame() else {
+            return;
+        };
+        let [width, height] = frame.size;
+        let uv = (pointer - image_rect.min) / image_rect.size();
+        if !(0.0..1.0).contains(&uv.x) || !(0.0..1.0).contains(&uv.y) {
+            return;
+        }
+        let x = ((uv.x * width as f32) as usize).min(width - 1);
+        let y = ((uv.y * height as f32) as usize).min(height - 1);
+        let Some(color) = player.pixel_at(x, y) else {
+            return;
+        };
+
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let (hex, values) = if player.has_alpha() {
+            (
+                format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a),
+                format!("R {}  G {}  B {}  A {}", r, g, b, a),
+            )
+        } else {
+            (
+                format!("#{:02X}{:02X}{:02X}", r, g, b),
+                format!("R {}  G {}  B {}", r, g, b),
+            )
+        };
+
+        ui.ctx().set_cursor_icon(CursorIcon::Crosshair);
+        response.clone().on_hover_ui_at_pointer(|ui| {
+            ui.horizontal(|ui| {
+                let (swatch, _) = ui.allocate_exact_size(Vec2::splat(32.0), Sense::hover());
+                ui.painter().rect_filled(swatch, 2.0, color);
+                ui.vertical(|ui| {
+                    ui.monospace(format!("{}, {}", x, y));
+                    ui.monospace(values);
+                    ui.monospace(format!("{}  (click to copy)", hex));
+                });
+            });
+        });
+        if response.clicked() {
+            ui.ctx().copy_text(hex);
+        }
+    }
+
+    fn frame_menu(ui: &mut Ui, player: &mut VideoPlayer) {
+        let has_frame = player.current_frame().is_some();
+
+        if ui
+            .add_enabled(
+                !player.view_transform().is_identity(),
+                Button::new("Reset zoom"),
+            )
+            .clicked()
+        {
+            ui.close_menu();
+            player.reset_view();
+        }
+
+        if ui
+            .add_enabled(has_frame, Button::new("Copy frame"))
+            .clicked()
+        {
+            ui.close_menu();
+            if let Err(e) = player.copy_frame_to_clipboard() {
+                player.warn(format!("{:#}", e));
+            }
+        }
+
+        if ui
+            .add_enabled(has_frame, Button::new("Save frame..."))
+            .clicked()
+        {
+            ui.close_menu();
+            let file_name = format!(
+                "frame_{}.png",
+                format_timestamp(player.position()).replace(':', "-")
+            );
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("PNG image", &["png"])
+                .set_file_name(file_name)
+                .save_file()
+            {
+                if let Err(e) = player.save_frame(&path) {
+                    player.warn(format!("{:#}", e));
+                }
+            }
+        }
+
+        if ui
+            .add_enabled(has_frame, Button::new("Save frame with overlays..."))
+            .clicked()
+        {
+            ui.close_menu();
+            let file_name = format!(
+                "frame_{}_qc.png",
+                format_timestamp(player.position()).replace(':', "-")
+            );
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("PNG image", &["png"])
+                .set_file_name(file_name)
+                .save_file()
+            {
+                if let Err(e) = player.save_frame_with_overlay(&path, OverlayFlags::ALL) {
+                    player.warn(format!("{:#}", e));
+                }
+            }
+        }
+
+        let mut timecode = player.timecode_overlay();
+        if ui.checkbox(&mut timecode, "Show timecode").changed() {
+            player.set_timecode_overlay(timecode);
+        }
+
+        let mut inspector = player.pixel_inspector();
+        if ui.checkbox(&mut inspector, "Pixel inspector").changed() {
+            player.set_pixel_inspector(inspector);
+        }
+
+        if ui.button("Copy timestamp").clicked() {
+            ui.close_menu();
+            ui.ctx().copy_text(format_timestamp(player.position()));
+        }
+    }
+}
+
+/// Side length of a checkerboard square in points
+const CHECKER_SIZE: f32 = 8.0;
+
+/// Light and dark checkerboard squares drawn behind transparent video
+fn checkerboard(rect: Rect) -> Shape {
+    let mut shapes = vec![Shape::rect_filled(rect, 0.0, Color32::from_gray(204))];
+    let columns = (rect.width() / CHECKER_SIZE).ceil() as usize;
+    let rows = (rect.height() / CHECKER_SIZE).ceil() as usize;
+    for row in 0..rows {
+        for column in (row % 2..columns).step_by(2) {
+            let min = rect.min + Vec2::new(column as f32, row as f32) * CHECKER_SIZE;
+            let square = Rect::from_min_size(min, Vec2::splat(CHECKER_SIZE)).intersect(rect);
+            shapes.push(Shape::rect_filled(square, 0.0, Color32::from_gray(153)));
+        }
+    }
+    Shape::Vec(shapes)
+}
+
+/// Format a position as HH:MM:SS.mmm
+fn format_timestamp(position: Duration) -> String {
+    let total_seconds = position.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60,
+        position.subsec_millis()
+    )
+}
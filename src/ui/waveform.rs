@@ -0,0 +1,49 @@
+use crate::player::Waveform;
+use egui::{Color32, Pos2, Rect, Shape, Ui};
+
+pub struct WaveformBar;
+
+impl WaveformBar {
+    /// Draw the waveform as mirrored bars filling the rect, one per pixel column
+    pub fn show(ui: &Ui, rect: Rect, waveform: &Waveform) {
+        ui.painter_at(rect)
+            .extend(Self::shapes(rect, waveform, Self::color(ui)));
+    }
+
+    /// Subdued foreground color that keeps the slider readable on top
+    pub(crate) fn color(ui: &Ui) -> Color32 {
+        ui.visuals()
+            .widgets
+            .inactive
+            .fg_stroke
+            .color
+            .gamma_multiply(0.4)
+    }
+
+    /// Bar shapes for the waveform, scaled so the loudest column fills the rect
+    pub(crate) fn shapes(rect: Rect, waveform: &Waveform, color: Color32) -> Vec<Shape> {
+        let columns = waveform.columns(rect.width().max(1.0) as usize);
+        let peak = columns.iter().copied().fold(0.0, f32::max);
+        if peak <= 0.0 {
+            return Vec::new();
+        }
+
+        let center = rect.center().y;
+        columns
+            .iter()
+            .enumerate()
+            .filter(|(_, &level)| level > 0.0)
+            .map(|(x, &level)| {
+                let x = rect.left() + x as f32 + 0.5;
+                let half_height = (level / peak * rect.height() * 0.5).max(0.5);
+                Shape::line_segment(
+                    [
+                        Pos2::new(x, center - half_height),
+                        Pos2::new(x, center + half_height),
+                    ],
+                    (1.0, color),
+                )
+            })
+            .collect()
+    }
+}
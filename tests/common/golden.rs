@@ -0,0 +1,169 @@
+//! Golden snapshots of what widgets paint: frames run headless through
+//! `egui::Context::run` with fixed input, and the tessellated meshes are
+//! reduced to one line per primitive.
+//!
+//! Set `UPDATE_GOLDEN=1` to write the snapshots under `tests/golden`.
+
+use egui::epaint::{ClippedPrimitive, Primitive};
+use egui::{CentralPanel, Context, Event, Frame, Pos2, RawInput, Rect, Ui, Vec2, Visuals};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// Fixed input time, so time-based widgets draw the same every run
+const TIME: f64 = 0.0;
+/// Positions are compared to 1/64 point, below any visible difference
+const POSITION_STEPS: f32 = 64.0;
+/// UVs are compared to 1/65536 of the font atlas
+const UV_STEPS: f32 = 65_536.0;
+
+/// Context with the dark theme and no animations
+pub fn context() -> Context {
+    let ctx = Context::default();
+    ctx.set_visuals(Visuals::dark());
+    ctx.style_mut(|style| style.animation_time = 0.0);
+    ctx
+}
+
+/// Input for one frame of a `size` screen
+pub fn raw_input(size: Vec2, events: Vec<Event>) -> RawInput {
+    RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, size)),
+        time: Some(TIME),
+        predicted_dt: 1.0 / 60.0,
+        events,
+        ..Default::default()
+    }
+}
+
+/// Run one frame with `add_contents` in a frameless central panel and
+/// tessellate what it painted
+pub fn run(
+    ctx: &Context,
+    input: RawInput,
+    mut add_contents: impl FnMut(&mut Ui),
+) -> Vec<ClippedPrimitive> {
+    let output = ctx.run(input, |ctx| {
+        CentralPanel::default()
+            .frame(Frame::none())
+            .show(ctx, |ui| add_contents(ui));
+    });
+    ctx.tessellate(output.shapes, output.pixels_per_point)
+}
+
+/// Render two frames without input and return the second, after the first
+/// has laid out fonts and sizes
+pub fn render(size: Vec2, mut add_contents: impl FnMut(&mut Ui)) -> Vec<ClippedPrimitive> {
+    render_with(&context(), size, &mut add_contents)
+}
+
+/// Like `render`, on an existing context
+pub fn render_with(
+    ctx: &Context,
+    size: Vec2,
+    mut add_contents: impl FnMut(&mut Ui),
+) -> Vec<ClippedPrimitive> {
+    run(ctx, raw_input(size, Vec::new()), &mut add_contents);
+    run(ctx, raw_input(size, Vec::new()), &mut add_contents)
+}
+
+/// One line per primitive: clip rect, texture, mesh sizes and a hash of the
+/// vertices and indices
+pub fn snapshot(primitives: &[ClippedPrimitive]) -> String {
+    let mut text = String::new();
+    for ClippedPrimitive {
+        clip_rect,
+        primitive,
+    } in primitives
+    {
+        let _ = write!(
+            text,
+            "clip [{} {} {} {}] ",
+            clip_rect.min.x, clip_rect.min.y, clip_rect.max.x, clip_rect.max.y
+        );
+        match primitive {
+            Primitive::Mesh(mesh) => {
+                let mut hash = Fnv::default();
+                for vertex in &mesh.vertices {
+                    for value in [vertex.pos.x, vertex.pos.y] {
+                        hash.write(&((value * POSITION_STEPS).round() as i32).to_le_bytes());
+                    }
+                    for value in [vertex.uv.x, vertex.uv.y] {
+                        hash.write(&((value * UV_STEPS).round() as i32).to_le_bytes());
+                    }
+                    hash.write(&vertex.color.to_array());
+                }
+                for index in &mesh.indices {
+                    hash.write(&index.to_le_bytes());
+                }
+                let _ = writeln!(
+                    text,
+                    "mesh {:?} vertices {} indices {} hash {:016x}",
+                    mesh.texture_id,
+                    mesh.vertices.len(),
+                    mesh.indices.len(),
+                    hash.0
+                );
+            }
+            Primitive::Callback(_) => text.push_str("callback\n"),
+        }
+    }
+    text
+}
+
+/// Compare `snapshot` with `tests/golden/{name}.txt`. A mismatch, or a
+/// missing golden file, is written next to it as `{name}.new.txt` for review.
+pub fn assert_golden(name: &str, snapshot: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let path = dir.join(format!("{}.txt", name));
+    let new_path = dir.join(format!("{}.new.txt", name));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(&dir).expect("Failed to create the golden folder");
+        std::fs::write(&path, snapshot).expect("Failed to write the golden file");
+        let _ = std::fs::remove_file(new_path);
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(e) => {
+            let _ = std::fs::write(&new_path, snapshot);
+            panic!(
+                "{} has no golden file at {} ({}); check {} \
+                 and rerun with UPDATE_GOLDEN=1 to record it",
+                name,
+                path.display(),
+                e,
+                new_path.display()
+            );
+        }
+    };
+    if expected != snapshot {
+        let _ = std::fs::write(&new_path, snapshot);
+        panic!(
+            "{} does not match its golden file; compare {} with {} \
+             and rerun with UPDATE_GOLDEN=1 if the change is intended",
+            name,
+            path.display(),
+            new_path.display()
+        );
+    }
+    let _ = std::fs::remove_file(new_path);
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is the same on every Rust version
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
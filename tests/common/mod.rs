@@ -0,0 +1,217 @@
+//! Synthetic media for the integration tests, encoded on the fly so no
+//! sample files are checked in
+
+#![allow(dead_code)] // Each test binary uses a different part
+
+pub mod golden;
+
+use egui_video::VideoPlayer;
+use ffmpeg_next::format::{self, Pixel, Sample};
+use ffmpeg_next::frame::{Audio as AudioFrame, Video as VideoFrame};
+use ffmpeg_next::{codec, encoder, ChannelLayout, Packet, Rational};
+use std::f32::consts::TAU;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const FRAME_RATE: i32 = 25;
+pub const SAMPLE_RATE: i32 = 48_000;
+pub const SIZE: [u32; 2] = [160, 120];
+/// Frames between keyframes, so exact seeks have to decode forward
+const GOP: u32 = 12;
+/// Samples per channel in each audio frame
+const AUDIO_FRAME: usize = 1024;
+const TONE: f32 = 440.0;
+/// Longest wait for the decoder to reach a state
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Write a clip of `seconds` to the temp folder: a moving gradient at 25 fps
+/// in MPEG-4 and a 440 Hz stereo tone in PCM, muxed into Matroska.
+/// `name` keeps the files of tests running in parallel apart.
+pub fn synthetic_clip(name: &str, seconds: u32) -> PathBuf {
+    ffmpeg_next::init().expect("Failed to initialize FFmpeg");
+    let path = std::env::temp_dir().join(format!("egui_video_{}_{}.mkv", name, std::process::id()));
+    write_clip(&path, seconds).expect("Failed to write synthetic clip");
+    path
+}
+
+/// Call `update` like a UI would until `done` holds
+pub fn update_until(
+    player: &mut VideoPlayer,
+    ctx: &egui::Context,
+    done: impl Fn(&VideoPlayer) -> bool,
+) {
+    let start = Instant::now();
+    while !done(player) {
+        assert!(
+            start.elapsed() < TIMEOUT,
+            "timed out waiting for the player"
+        );
+        player.update(ctx);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Keep updating for `duration`
+pub fn update_for(player: &mut VideoPlayer, ctx: &egui::Context, duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        player.update(ctx);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn write_clip(path: &Path, seconds: u32) -> Result<(), ffmpeg_next::Error> {
+    let mut octx = format::output(path)?;
+    let global_header = octx
+        .format()
+        .flags()
+        .contains(format::flag::Flags::GLOBAL_HEADER);
+
+    let video_time_base = Rational::new(1, FRAME_RATE);
+    let video_codec = encoder::find(codec::Id::MPEG4).ok_or(ffmpeg_next::Error::EncoderNotFound)?;
+    let mut video = {
+        let mut stream = octx.add_stream(video_codec)?;
+        let mut encoder = codec::Context::from_parameters(stream.parameters())?
+            .encoder()
+            .video()?;
+        encoder.set_width(SIZE[0]);
+        encoder.set_height(SIZE[1]);
+        encoder.set_format(Pixel::YUV420P);
+        encoder.set_time_base(video_time_base);
+        encoder.set_frame_rate(Some(Rational::new(FRAME_RATE, 1)));
+        encoder.set_gop(GOP);
+        if global_header {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+        stream.set_time_base(video_time_base);
+        let encoder = encoder.open_as(video_codec)?;
+        stream.set_parameters(&encoder);
+        encoder
+    };
+
+    let audio_time_base = Rational::new(1, SAMPLE_RATE);
+    let audio_codec = encoder::find(codec::Id::PCM_S16LE)
+        .ok_or(ffmpeg_next::Error::EncoderNotFound)?
+        .audio()?;
+    let mut audio = {
+        let mut stream = octx.add_stream(audio_codec)?;
+        let mut encoder = codec::Context::from_parameters(stream.parameters())?
+            .encoder()
+            .audio()?;
+        encoder.set_rate(SAMPLE_RATE);
+        encoder.set_channel_layout(ChannelLayout::STEREO);
+        encoder.set_format(Sample::I16(format::sample::Type::Packed));
+        encoder.set_time_base(audio_time_base);
+        stream.set_time_base(audio_time_base);
+        let encoder = encoder.open_as(audio_codec)?;
+        stream.set_parameters(&encoder);
+        encoder
+    };
+
+    octx.write_header()?;
+    // The muxer may pick its own time bases when writing the header
+    let stream_time_bases = [0, 1].map(|index| {
+        octx.stream(index)
+            .map_or(Rational::new(1, 1000), |stream| stream.time_base())
+    });
+
+    let frames = seconds as i64 * i64::from(FRAME_RATE);
+    let mut samples_written = 0;
+    for index in 0..frames {
+        video.send_frame(&video_frame(index))?;
+        drain(
+            &mut video,
+            0,
+            video_time_base,
+            stream_time_bases[0],
+            &mut octx,
+        )?;
+
+        // Keep the audio level with the video so the muxer interleaves them
+        let video_end = (index + 1) * i64::from(SAMPLE_RATE) / i64::from(FRAME_RATE);
+        while samples_written < video_end {
+            audio.send_frame(&audio_frame(samples_written))?;
+            samples_written += AUDIO_FRAME as i64;
+            drain(
+                &mut audio,
+                1,
+                audio_time_base,
+                stream_time_bases[1],
+                &mut octx,
+            )?;
+        }
+    }
+    video.send_eof()?;
+    drain(
+        &mut video,
+        0,
+        video_time_base,
+        stream_time_bases[0],
+        &mut octx,
+    )?;
+    audio.send_eof()?;
+    drain(
+        &mut audio,
+        1,
+        audio_time_base,
+        stream_time_bases[1],
+        &mut octx,
+    )?;
+
+    octx.write_trailer()
+}
+
+/// Gradient that moves one pixel per frame, so every frame differs
+fn video_frame(index: i64) -> VideoFrame {
+    let [width, height] = SIZE.map(|size| size as usize);
+    let mut frame = VideoFrame::new(Pixel::YUV420P, SIZE[0], SIZE[1]);
+    let stride = frame.stride(0);
+    let luma = frame.data_mut(0);
+    for y in 0..height {
+        for x in 0..width {
+            luma[y * stride + x] = (x + y + index as usize) as u8;
+        }
+    }
+    for plane in 1..3 {
+        frame.data_mut(plane).fill(128);
+    }
+    frame.set_pts(Some(index));
+    frame
+}
+
+/// Sine tone starting at sample `start`, the same on both channels
+fn audio_frame(start: i64) -> AudioFrame {
+    let mut frame = AudioFrame::new(
+        Sample::I16(format::sample::Type::Packed),
+        AUDIO_FRAME,
+        ChannelLayout::STEREO,
+    );
+    frame.set_rate(SAMPLE_RATE as u32);
+    let data = frame.data_mut(0);
+    for n in 0..AUDIO_FRAME {
+        let t = (start + n as i64) as f32 / SAMPLE_RATE as f32;
+        let sample = ((TAU * TONE * t).sin() * f32::from(i16::MAX) * 0.5) as i16;
+        let bytes = sample.to_le_bytes();
+        data[n * 4..n * 4 + 2].copy_from_slice(&bytes);
+        data[n * 4 + 2..n * 4 + 4].copy_from_slice(&bytes);
+    }
+    frame.set_pts(Some(start));
+    frame
+}
+
+/// Write the packets the encoder has ready
+fn drain(
+    encoder: &mut encoder::Encoder,
+    stream: usize,
+    encoder_time_base: Rational,
+    stream_time_base: Rational,
+    octx: &mut format::context::Output,
+) -> Result<(), ffmpeg_next::Error> {
+    let mut packet = Packet::empty();
+    while encoder.receive_packet(&mut packet).is_ok() {
+        packet.set_stream(stream);
+        packet.rescale_ts(encoder_time_base, stream_time_base);
+        packet.write_interleaved(octx)?;
+    }
+    Ok(())
+}
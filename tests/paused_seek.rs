@@ -0,0 +1,66 @@
+//! A seek while paused shows the frame at the target, leaves the audio
+//! paused, and reports that frame's PTS as the position.
+
+mod common;
+
+use common::{update_for, update_until};
+use egui_video::{PlayerError, PlayerState, VideoPlayer};
+use std::time::Duration;
+
+#[test]
+fn paused_seek_lands_on_the_shown_frame() {
+    let clip = common::synthetic_clip("paused_seek", 4);
+    let ctx = egui::Context::default();
+    let mut player = match VideoPlayer::open(&clip, ctx.clone()) {
+        Ok(player) => player,
+        Err(PlayerError::AudioDevice(e)) => {
+            eprintln!("skipped: no audio output ({})", e);
+            return;
+        }
+        Err(e) => panic!("failed to open the synthetic clip: {}", e),
+    };
+
+    player.play();
+    update_until(&mut player, &ctx, |player| {
+        player.presented_frame().is_some() && player.position() > Duration::from_millis(200)
+    });
+    player.pause();
+    update_for(&mut player, &ctx, Duration::from_millis(100));
+
+    // Between two frames, which are 40 ms apart
+    let target = Duration::from_millis(2_230);
+    let frame_interval = Duration::from_secs(1) / common::FRAME_RATE as u32;
+    player.seek(target);
+    update_until(&mut player, &ctx, |player| {
+        !player.is_seeking()
+            && player
+                .presented_frame()
+                .is_some_and(|(pts, _)| pts + frame_interval > target)
+    });
+
+    let (pts, frame_number) = player.presented_frame().unwrap();
+    assert!(
+        pts <= target && target - pts < frame_interval,
+        "showing the frame at {:?} for a seek to {:?}",
+        pts,
+        target
+    );
+    assert_eq!(frame_number, 55);
+    // The clock counts whole microseconds
+    assert!(
+        player.position().abs_diff(pts) <= Duration::from_micros(1),
+        "position {:?} is not the shown frame's PTS {:?}",
+        player.position(),
+        pts
+    );
+    assert_eq!(player.state(), PlayerState::Paused);
+
+    // The audio clock stays frozen at the frame until play()
+    let position = player.position();
+    update_for(&mut player, &ctx, Duration::from_millis(300));
+    assert_eq!(player.position(), position);
+    assert_eq!(player.presented_frame(), Some((pts, frame_number)));
+
+    drop(player);
+    let _ = std::fs::remove_file(clip);
+}
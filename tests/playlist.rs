@@ -0,0 +1,57 @@
+//! Playlists that list playlists are expanded once each, so loops end.
+
+use egui_video::Playlist;
+use std::path::{Path, PathBuf};
+
+/// Folder of its own for each test, as they run in parallel
+fn folder(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("egui_video_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create the test folder");
+    dir
+}
+
+fn paths(playlist: &Playlist, dir: &Path) -> Vec<PathBuf> {
+    playlist
+        .items()
+        .iter()
+        .map(|item| item.path.strip_prefix(dir).unwrap().to_path_buf())
+        .collect()
+}
+
+#[test]
+fn playlist_listing_itself_ends() {
+    let dir = folder("playlist_self");
+    std::fs::write(dir.join("loop.m3u"), "a.mkv\nloop.m3u\nb.mkv\n").unwrap();
+
+    let playlist = Playlist::load(&dir.join("loop.m3u")).unwrap();
+    assert_eq!(
+        paths(&playlist, &dir),
+        [PathBuf::from("a.mkv"), PathBuf::from("b.mkv")]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn nested_playlists_are_expanded_in_place() {
+    let dir = folder("playlist_nested");
+    std::fs::write(dir.join("outer.m3u"), "a.mkv\ninner.pls\nd.mkv\n").unwrap();
+    std::fs::write(
+        dir.join("inner.pls"),
+        "[playlist]\nFile1=b.mkv\nFile2=outer.m3u\nFile3=c.mkv\nNumberOfEntries=3\n",
+    )
+    .unwrap();
+    // HLS manifests are streams, not lists
+    std::fs::write(
+        dir.join("live.m3u8"),
+        "#EXTM3U\n#EXT-X-VERSION:3\nseg0.ts\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("mixed.m3u"), "outer.m3u\nlive.m3u8\n").unwrap();
+
+    let playlist = Playlist::load(&dir.join("mixed.m3u")).unwrap();
+    assert_eq!(
+        paths(&playlist, &dir),
+        ["a.mkv", "b.mkv", "c.mkv", "d.mkv", "live.m3u8"].map(PathBuf::from)
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
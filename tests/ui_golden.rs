@@ -0,0 +1,234 @@
+//! Headless snapshots of the widgets, compared with the files in
+//! `tests/golden`, and checks of how the seek slider reacts to input.
+
+mod common;
+
+use common::golden::{self, assert_golden, render, snapshot};
+use egui::{Event, Key, Modifiers, PointerButton, Pos2, Rect, Vec2, Widget};
+use egui_video::{
+    AudioLevels, Chapter, LevelMeter, PlayerControls, PlayerError, SeekSlider, SubtitleOverlay,
+    TelemetryOverlay, TelemetrySample, TimecodeOverlay, VideoPlayer, VideoView,
+};
+use std::cell::Cell;
+use std::time::Duration;
+
+const SCREEN: Vec2 = Vec2::new(320.0, 180.0);
+const DURATION: f64 = 120.0;
+
+fn chapters() -> Vec<Chapter> {
+    [("Intro", 0, 30), ("Middle", 30, 90), ("End", 90, 120)]
+        .into_iter()
+        .map(|(title, start, end)| Chapter {
+            title: title.to_owned(),
+            start: Duration::from_secs(start),
+            end: Duration::from_secs(end),
+        })
+        .collect()
+}
+
+fn pointer_button(pos: Pos2, pressed: bool) -> Event {
+    Event::PointerButton {
+        pos,
+        button: PointerButton::Primary,
+        pressed,
+        modifiers: Modifiers::NONE,
+    }
+}
+
+fn key_press(key: Key) -> Event {
+    Event::Key {
+        key,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    }
+}
+
+/// Lay out a seek slider at `position`, then press and release at `x` (as a
+/// share of the slider's width) and return where the slider moved to
+fn click_slider(position: f64, x: f32) -> f64 {
+    let ctx = golden::context();
+    let chapters = chapters();
+    let mut position = position;
+    let rect = Cell::new(Rect::NOTHING);
+    let mut show = |ui: &mut egui::Ui| {
+        rect.set(
+            SeekSlider::new(&mut position, DURATION)
+                .chapters(&chapters)
+                .ui(ui)
+                .rect,
+        );
+    };
+    golden::run(&ctx, golden::raw_input(SCREEN, Vec::new()), &mut show);
+
+    let rect = rect.get();
+    let pos = Pos2::new(rect.left() + x * rect.width(), rect.center().y);
+    for events in [
+        vec![Event::PointerMoved(pos)],
+        vec![pointer_button(pos, true)],
+        vec![pointer_button(pos, false)],
+    ] {
+        golden::run(&ctx, golden::raw_input(SCREEN, events), &mut show);
+    }
+    position
+}
+
+#[test]
+fn seek_slider_with_chapters() {
+    let chapters = chapters();
+    let mut position = 45.0;
+    let primitives = render(SCREEN, |ui| {
+        SeekSlider::new(&mut position, DURATION)
+            .chapters(&chapters)
+            .ui(ui);
+    });
+    assert_golden("seek_slider_chapters", &snapshot(&primitives));
+}
+
+#[test]
+fn seek_slider_grows_the_hovered_chapter() {
+    let ctx = golden::context();
+    let chapters = chapters();
+    let mut position = 45.0;
+    let rect = Cell::new(Rect::NOTHING);
+    let mut show = |ui: &mut egui::Ui| {
+        rect.set(
+            SeekSlider::new(&mut position, DURATION)
+                .chapters(&chapters)
+                .ui(ui)
+                .rect,
+        );
+    };
+    let plain = golden::run(&ctx, golden::raw_input(SCREEN, Vec::new()), &mut show);
+    // Over the middle chapter, away from the handle
+    let rect = rect.get();
+    let pos = Pos2::new(rect.left() + rect.width() * 0.6, rect.center().y);
+    golden::run(
+        &ctx,
+        golden::raw_input(SCREEN, vec![Event::PointerMoved(pos)]),
+        &mut show,
+    );
+    let hovered = golden::run(&ctx, golden::raw_input(SCREEN, Vec::new()), &mut show);
+
+    assert_ne!(snapshot(&plain), snapshot(&hovered));
+    assert_golden("seek_slider_hovered", &snapshot(&hovered));
+}
+
+#[test]
+fn seek_slider_click_moves_to_the_pointer() {
+    let middle = click_slider(10.0, 0.5);
+    assert!((middle - DURATION / 2.0).abs() < 1.0, "moved to {}", middle);
+    // The rail is inset by the handle, so the last points reach the end
+    let end = click_slider(10.0, 0.999);
+    assert!((end - DURATION).abs() < 1e-9, "moved to {}", end);
+}
+
+#[test]
+fn seek_slider_arrow_keys_step_with_focus() {
+    let ctx = golden::context();
+    let mut position = 60.0;
+    let id = Cell::new(None);
+    let mut show = |ui: &mut egui::Ui| {
+        id.set(Some(SeekSlider::new(&mut position, DURATION).ui(ui).id));
+    };
+    golden::run(&ctx, golden::raw_input(SCREEN, Vec::new()), &mut show);
+    ctx.memory_mut(|mem| mem.request_focus(id.get().unwrap()));
+    golden::run(&ctx, golden::raw_input(SCREEN, Vec::new()), &mut show);
+    golden::run(
+        &ctx,
+        golden::raw_input(SCREEN, vec![key_press(Key::ArrowRight)]),
+        &mut show,
+    );
+    assert!(position > 60.0, "stayed at {}", position);
+}
+
+#[test]
+fn level_meter_clipping() {
+    let levels = AudioLevels {
+        rms: [0.3, 0.1],
+        peak: [1.0, 0.4],
+        peak_hold: [1.0, 0.6],
+        clipping: [true, false],
+    };
+    let primitives = render(SCREEN, |ui| {
+        LevelMeter::show(ui, &levels, 120.0);
+    });
+    assert_golden("level_meter_clipping", &snapshot(&primitives));
+}
+
+#[test]
+fn on_video_overlays() {
+    let video_rect = Rect::from_min_size(Pos2::ZERO, SCREEN);
+    let sample = TelemetrySample {
+        sensor_latitude: Some(52.520_08),
+        sensor_longitude: Some(13.404_95),
+        sensor_altitude: Some(1_250.0),
+        platform_heading: Some(87.5),
+        platform_pitch: Some(-2.0),
+        platform_roll: Some(1.5),
+        ..Default::default()
+    };
+    let primitives = render(SCREEN, |ui| {
+        SubtitleOverlay::show(ui, video_rect, "Two lines of\nsubtitle text");
+        TimecodeOverlay::show(ui, video_rect, Duration::from_millis(83_440), 2_086, 25.0);
+        TelemetryOverlay::show(ui, video_rect, &sample);
+    });
+    assert_golden("on_video_overlays", &snapshot(&primitives));
+}
+
+/// Open the synthetic clip paused on frame 55, or None without an audio device
+fn paused_player(name: &str, ctx: &egui::Context) -> Option<VideoPlayer> {
+    let clip = common::synthetic_clip(name, 4);
+    let player = VideoPlayer::open(&clip, ctx.clone());
+    let _ = std::fs::remove_file(&clip);
+    let mut player = match player {
+        Ok(player) => player,
+        Err(PlayerError::AudioDevice(e)) => {
+            eprintln!("skipped: no audio output ({})", e);
+            return None;
+        }
+        Err(e) => panic!("failed to open the synthetic clip: {}", e),
+    };
+    player.play();
+    common::update_until(&mut player, ctx, |player| {
+        player.presented_frame().is_some()
+    });
+    player.pause();
+    player.seek(Duration::from_millis(2_230));
+    common::update_until(&mut player, ctx, |player| {
+        !player.is_seeking()
+            && player
+                .presented_frame()
+                .is_some_and(|(_, frame)| frame == 55)
+    });
+    Some(player)
+}
+
+// The player snapshots depend on the FFmpeg build that decodes the clip, so
+// their golden files are recorded on the machine that runs them
+#[test]
+#[ignore = "record tests/golden/player_controls.txt with UPDATE_GOLDEN=1 first"]
+fn player_controls() {
+    let ctx = golden::context();
+    let Some(mut player) = paused_player("golden_controls", &ctx) else {
+        return;
+    };
+    let primitives = golden::render_with(&ctx, Vec2::new(640.0, 60.0), |ui| {
+        PlayerControls::show(ui, &mut player);
+    });
+    assert_golden("player_controls", &snapshot(&primitives));
+}
+
+#[test]
+#[ignore = "record tests/golden/video_view.txt with UPDATE_GOLDEN=1 first"]
+fn video_view() {
+    let ctx = golden::context();
+    let Some(mut player) = paused_player("golden_view", &ctx) else {
+        return;
+    };
+    let primitives = golden::render_with(&ctx, Vec2::new(320.0, 240.0), |ui| {
+        VideoView::show(ui, &mut player);
+    });
+    assert_golden("video_view", &snapshot(&primitives));
+}